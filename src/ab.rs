@@ -0,0 +1,268 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::openai::{self, Usage};
+use crate::pdf::extract_text_from_pdf;
+use crate::pipeline::{self, MAX_PROMPT_CHARS, SUMMARY_SECTIONS};
+use chrono::Local;
+use rand::seq::SliceRandom;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Stdout, Write};
+
+#[derive(Serialize)]
+struct Pair {
+    id: String,
+    title: String,
+    usage_a: Usage,
+    usage_b: Usage,
+    preference: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Failure {
+    id: String,
+    prompt: String,
+    error: String,
+}
+
+#[derive(Serialize, Default)]
+struct Report {
+    prompt_a: String,
+    prompt_b: String,
+    judged: usize,
+    wins_a: usize,
+    wins_b: usize,
+    ties: usize,
+    win_rate_a: f64,
+    win_rate_b: f64,
+    avg_tokens_a: f64,
+    avg_tokens_b: f64,
+    avg_cost_a: f64,
+    avg_cost_b: f64,
+    pairs: Vec<Pair>,
+    failures: Vec<Failure>,
+}
+
+fn render_template(template: &str, record: &PaperRecord, text: &str) -> String {
+    let text: String = text.chars().take(MAX_PROMPT_CHARS).collect();
+    pipeline::fill_prompt_template(template, &record.to_paper(), SUMMARY_SECTIONS, &text)
+}
+
+fn sample(db: &Db, size: usize) -> Result<Vec<PaperRecord>, RasError> {
+    let papers_dir = crate::ras_dir()?.join("papers");
+    let mut candidates: Vec<PaperRecord> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && !r.pdf_file.is_empty() && papers_dir.join(&r.pdf_file).exists())
+        .cloned()
+        .collect();
+    candidates.shuffle(&mut rand::rng());
+    candidates.truncate(size);
+    Ok(candidates)
+}
+
+fn ask_preference(stdin: &io::Stdin) -> Option<char> {
+    loop {
+        print!("Which summary is better? [1/2, t = tie, s = skip, q = quit]: ");
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        match line.trim() {
+            "1" => return Some('1'),
+            "2" => return Some('2'),
+            "t" => return Some('t'),
+            "s" => return Some('s'),
+            "q" => return None,
+            _ => println!("Please answer 1, 2, t, s or q"),
+        }
+    }
+}
+
+struct Screen {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl Screen {
+    fn enter() -> Result<Screen, String> {
+        enable_raw_mode().map_err(|e| e.to_string())?;
+        let screen = Terminal::new(CrosstermBackend::new(io::stdout())).map(|terminal| Screen { terminal });
+        let mut screen = screen.map_err(|e| {
+            let _ = disable_raw_mode();
+            e.to_string()
+        })?;
+        execute!(screen.terminal.backend_mut(), EnterAlternateScreen).map_err(|e| e.to_string())?;
+        Ok(screen)
+    }
+
+    fn ask(&mut self, heading: &str, first: &str, second: &str) -> Result<Option<char>, String> {
+        let mut scroll: u16 = 0;
+        loop {
+            self.terminal
+                .draw(|frame| {
+                    let [top, middle, bottom] =
+                        Layout::vertical([Constraint::Length(3), Constraint::Min(5), Constraint::Length(1)]).areas(frame.area());
+                    let [left, right] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(middle);
+                    frame.render_widget(Paragraph::new(heading).block(Block::default().borders(Borders::ALL).title(" ab ")), top);
+                    for (area, title, text) in [(left, " Summary 1 ", first), (right, " Summary 2 ", second)] {
+                        let summary = Paragraph::new(text).wrap(Wrap { trim: false }).scroll((scroll, 0));
+                        frame.render_widget(summary.block(Block::default().borders(Borders::ALL).title(title)), area);
+                    }
+                    frame.render_widget(Paragraph::new("1/2 pick the better summary  t tie  s skip  q quit  ↑/↓ PgUp/PgDn scroll"), bottom);
+                })
+                .map_err(|e| e.to_string())?;
+            let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char(c @ ('1' | '2' | 't' | 's')) => return Ok(Some(c)),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                KeyCode::Up | KeyCode::Char('k') => scroll = scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => scroll = scroll.saturating_add(1),
+                KeyCode::PageUp => scroll = scroll.saturating_sub(20),
+                KeyCode::PageDown | KeyCode::Char(' ') => scroll = scroll.saturating_add(20),
+                KeyCode::Home => scroll = 0,
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+fn average(values: impl Iterator<Item = f64>) -> f64 {
+    let values: Vec<f64> = values.collect();
+    if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let usage = "Usage: ab --prompts a.tmpl,b.tmpl [--sample 20]";
+    let prompts: Vec<&str> = args.value("prompts").ok_or(usage)?.split(',').map(str::trim).collect();
+    let [path_a, path_b] = prompts.as_slice() else {
+        return Err(usage.to_string());
+    };
+    let template_a = fs::read_to_string(path_a).map_err(|e| format!("Failed to read {}: {}", path_a, e))?;
+    let template_b = fs::read_to_string(path_b).map_err(|e| format!("Failed to read {}: {}", path_b, e))?;
+    let sample_size: usize = args.parsed("sample", 20)?;
+
+    let client = crate::build_client()?;
+    let api_key = openai::chat_key()?;
+    let records = sample(&Db::load()?, sample_size)?;
+    if records.is_empty() {
+        return Err("No summarized papers with a local PDF to sample".to_string());
+    }
+
+    let out_dir = crate::ras_dir()?.join("ab").join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+    let papers_dir = crate::ras_dir()?.join("papers");
+
+    let mut report = Report { prompt_a: path_a.to_string(), prompt_b: path_b.to_string(), ..Default::default() };
+    let mut outputs: Vec<(String, String)> = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        println!("Generating pair {}/{}: {}", i + 1, records.len(), record.title);
+        let text = match extract_text_from_pdf(&papers_dir.join(&record.pdf_file)) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("  Skipping: {}", e);
+                continue;
+            }
+        };
+        let mut generate = |path: &str, template: &str| match openai::chat_with_usage(&client, &api_key, &render_template(template, record, &text), 2000) {
+            Ok(output) => Some(output),
+            Err(e) => {
+                println!("  {} failed: {}", path, e);
+                report.failures.push(Failure { id: record.id.clone(), prompt: path.to_string(), error: e });
+                None
+            }
+        };
+        let (a, b) = (generate(path_a, &template_a), generate(path_b, &template_b));
+        let (Some((a, usage_a)), Some((b, usage_b))) = (a, b) else {
+            continue;
+        };
+        fs::write(out_dir.join(format!("{}-a.md", record.id)), &a).map_err(|e| e.to_string())?;
+        fs::write(out_dir.join(format!("{}-b.md", record.id)), &b).map_err(|e| e.to_string())?;
+        report.pairs.push(Pair { id: record.id.clone(), title: record.title.clone(), usage_a, usage_b, preference: None });
+        outputs.push((a, b));
+    }
+
+    let stdin = io::stdin();
+    let mut screen = if io::stdout().is_terminal() && stdin.is_terminal() && !outputs.is_empty() { Some(Screen::enter()?) } else { None };
+    let total = report.pairs.len();
+    for (i, (pair, (a, b))) in report.pairs.iter_mut().zip(&outputs).enumerate() {
+        let swapped = rand::random::<bool>();
+        let (first, second) = if swapped { (b, a) } else { (a, b) };
+        let heading = format!("{}/{}: {}", i + 1, total, pair.title);
+        let answer = match screen.as_mut() {
+            Some(screen) => screen.ask(&heading, first, second)?,
+            None => {
+                println!("\n==================== {} ====================", heading);
+                println!("\n---------- Summary 1 ----------\n{}", first);
+                println!("\n---------- Summary 2 ----------\n{}\n", second);
+                ask_preference(&stdin)
+            }
+        };
+        let Some(answer) = answer else {
+            break;
+        };
+        pair.preference = match (answer, swapped) {
+            ('1', false) | ('2', true) => Some("a".to_string()),
+            ('1', true) | ('2', false) => Some("b".to_string()),
+            ('t', _) => Some("tie".to_string()),
+            _ => None,
+        };
+    }
+
+    drop(screen);
+
+    for pair in &report.pairs {
+        match pair.preference.as_deref() {
+            Some("a") => report.wins_a += 1,
+            Some("b") => report.wins_b += 1,
+            Some(_) => report.ties += 1,
+            None => {}
+        }
+    }
+    report.judged = report.wins_a + report.wins_b + report.ties;
+    if report.judged > 0 {
+        report.win_rate_a = (report.wins_a as f64 + report.ties as f64 / 2.0) / report.judged as f64;
+        report.win_rate_b = 1.0 - report.win_rate_a;
+    }
+    report.avg_tokens_a = average(report.pairs.iter().map(|p| (p.usage_a.prompt_tokens + p.usage_a.completion_tokens) as f64));
+    report.avg_tokens_b = average(report.pairs.iter().map(|p| (p.usage_b.prompt_tokens + p.usage_b.completion_tokens) as f64));
+    report.avg_cost_a = average(report.pairs.iter().map(|p| p.usage_a.cost));
+    report.avg_cost_b = average(report.pairs.iter().map(|p| p.usage_b.cost));
+
+    println!("\nResults over {} judged pairs:", report.judged);
+    println!("  A ({}): {} wins, win rate {:.0}%", path_a, report.wins_a, report.win_rate_a * 100.0);
+    println!("  B ({}): {} wins, win rate {:.0}%", path_b, report.wins_b, report.win_rate_b * 100.0);
+    println!("  Ties: {}", report.ties);
+    println!("  Avg tokens per summary: A {:.0}, B {:.0} ({:+.0})", report.avg_tokens_a, report.avg_tokens_b, report.avg_tokens_b - report.avg_tokens_a);
+    println!("  Avg cost per summary: A ${:.5}, B ${:.5} ({:+.5})", report.avg_cost_a, report.avg_cost_b, report.avg_cost_b - report.avg_cost_a);
+
+    if !report.failures.is_empty() {
+        println!("  Failed generations: {} (listed in results.json)", report.failures.len());
+    }
+
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    fs::write(out_dir.join("results.json"), json).map_err(|e| e.to_string())?;
+    println!("Summaries and results saved in {}", out_dir.display());
+    Ok(())
+}
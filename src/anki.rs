@@ -0,0 +1,59 @@
+use crate::cli::Args;
+use crate::db::Db;
+use crate::html::{escape_html, render_markdown};
+use crate::summary;
+use std::fs;
+
+const CARDS: &[(&str, &str)] = &[
+    ("overview", "What does it introduce?"),
+    ("key results", "What are the key results?"),
+    ("methodology", "How does the method work?"),
+    ("critical insights", "What are its limitations?"),
+];
+
+fn field(markdown: &str) -> String {
+    render_markdown(markdown).replace(['\t', '\n', '\r'], " ").trim().to_string()
+}
+
+pub fn export(args: &Args) -> Result<(), String> {
+    let output = args.value("output").unwrap_or("ras-anki.tsv");
+    let deck = args.value("deck").unwrap_or("ras");
+    let db = Db::load()?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+
+    let mut rows = Vec::new();
+    let mut papers = 0;
+    for record in db.papers.values().filter(|r| r.status == "summarized" && (r.starred || !args.flag("starred"))) {
+        let Ok(content) = fs::read_to_string(summary_dir.join(&record.summary_file)) else {
+            continue;
+        };
+        let sections = summary::sections(&content);
+        let mut tags: Vec<String> = record.tags.iter().chain([&record.category]).filter(|t| !t.is_empty()).map(|t| t.replace(' ', "_")).collect();
+        tags.push("ras".to_string());
+        let tags = tags.join(" ");
+        let title = format!("<b>{}</b> ({})", escape_html(&record.title), escape_html(&record.id));
+
+        let before = rows.len();
+        for (name, question) in CARDS {
+            if let Some((_, text)) = sections.iter().find(|(heading, _)| heading.to_lowercase() == *name) {
+                rows.push(format!("{}<br>{}\t{}\t{}", title, question, field(text), tags));
+            }
+        }
+        if rows.len() == before {
+            let tldr = summary::tldr(&content);
+            if tldr.is_empty() {
+                continue;
+            }
+            rows.push(format!("{}<br>What is it about?\t{}\t{}", title, field(&tldr), tags));
+        }
+        papers += 1;
+    }
+    if rows.is_empty() {
+        return Err("No summarized papers to export".to_string());
+    }
+
+    let content = format!("#separator:tab\n#html:true\n#deck:{}\n#tags column:3\n{}\n", deck, rows.join("\n"));
+    fs::write(output, content).map_err(|e| format!("Failed to write {}: {}", output, e))?;
+    println!("Wrote {} cards for {} papers to {}, import it in Anki with File > Import", rows.len(), papers, output);
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::arxiv;
+use crate::retry;
+use reqwest::blocking::Client;
+use scraper::{ElementRef, Html, Selector};
+
+const MIN_TEXT_CHARS: usize = 2000;
+
+pub fn urls(id: &str) -> Vec<String> {
+    vec![format!("https://arxiv.org/html/{}", id), format!("https://ar5iv.labs.arxiv.org/html/{}", id)]
+}
+
+pub fn html_to_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let blocks = Selector::parse("h1, h2, h3, h4, h5, h6, p, figcaption").unwrap();
+    let article = Selector::parse("article").unwrap();
+    let scope = document.select(&article).next().unwrap_or(document.root_element());
+    scope
+        .select(&blocks)
+        .filter(|block| !block.ancestors().filter_map(ElementRef::wrap).any(|a| matches!(a.value().name(), "nav" | "footer")))
+        .map(|block| block.text().flat_map(|t| t.split_whitespace()).collect::<Vec<_>>().join(" "))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn fetch_text(client: &Client, id: &str) -> Result<(String, String), String> {
+    let id = arxiv::normalize_id(id).ok_or("not an arXiv paper")?;
+    let mut last_error = String::new();
+    for url in urls(&id) {
+        let html = match retry::get(client, &url).and_then(|r| r.error_for_status().and_then(|r| r.text()).map_err(|e| e.to_string())) {
+            Ok(html) => html,
+            Err(e) => {
+                last_error = e;
+                continue;
+            }
+        };
+        let text = html_to_text(&html);
+        if text.chars().count() >= MIN_TEXT_CHARS {
+            return Ok((url, text));
+        }
+        last_error = format!("{} has no full text", url);
+    }
+    Err(last_error)
+}
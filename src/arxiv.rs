@@ -0,0 +1,330 @@
+use crate::cache;
+use crate::pwc::CodeInfo;
+use crate::scholar::ScholarInfo;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::blocking::Client;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SEARCH_PAGE_SIZE: usize = 200;
+const SEARCH_URL: &str = "https://export.arxiv.org/api/query";
+
+static REQUEST_DELAY_MS: AtomicU64 = AtomicU64::new(3000);
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+static ID_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"/abs/(\d+\.\d+)").unwrap());
+static ENTRY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<entry>(.*?)</entry>").unwrap());
+static ENTRY_ID_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<id>https?://arxiv\.org/abs/([^<]+?)(?:v(\d+))?</id>").unwrap());
+static ENTRY_SUMMARY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<summary[^>]*>(.*?)</summary>").unwrap());
+static ENTRY_TITLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<title[^>]*>(.*?)</title>").unwrap());
+static ENTRY_AUTHOR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<author>\s*<name>(.*?)</name>").unwrap());
+static ENTRY_PUBLISHED_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<published>([^<]+)</published>").unwrap());
+static ENTRY_DOI_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<arxiv:doi[^>]*>(.*?)</arxiv:doi>").unwrap());
+static ENTRY_JOURNAL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<arxiv:journal_ref[^>]*>(.*?)</arxiv:journal_ref>").unwrap());
+static ENTRY_CATEGORY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<arxiv:primary_category[^>]*term="([^"]+)""#).unwrap());
+static NORMALIZED_ID_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d{4}\.\d{4,5}|[a-z\-]+(?:\.[A-Z]{2})?/\d{7})(?:v\d+)?$").unwrap());
+static CATEGORY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(([a-z\-]+(?:\.[A-Za-z\-]+)?)\)").unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Paper {
+    pub id: String,
+    pub title: String,
+    pub pdf_url: String,
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scholar: Option<ScholarInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<CodeInfo>,
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub published: String,
+    pub summary: String,
+    pub primary_category: String,
+    pub doi: Option<String>,
+    pub journal_ref: Option<String>,
+    pub version: u32,
+}
+
+impl Metadata {
+    pub fn year(&self) -> &str {
+        self.published.get(..4).unwrap_or("")
+    }
+
+    pub fn to_paper(&self) -> Paper {
+        Paper {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            pdf_url: format!("https://arxiv.org/pdf/{}.pdf", self.id),
+            category: if self.primary_category.is_empty() { "cs.AI".to_string() } else { self.primary_category.clone() },
+            scholar: None,
+            code: None,
+            sources: Vec::new(),
+        }
+    }
+}
+
+pub fn pdf_urls(paper: &Paper, mirrors: &[String]) -> Vec<String> {
+    let mut urls = vec![paper.pdf_url.clone()];
+    if let Some(id) = normalize_id(&paper.id) {
+        for file in [id.clone(), format!("{}v1", id)] {
+            for mirror in mirrors {
+                let url = format!("{}/pdf/{}.pdf", mirror.trim_end_matches('/'), file);
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+    }
+    urls
+}
+
+pub fn normalize_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value = value.strip_prefix("arXiv:").or_else(|| value.strip_prefix("arxiv:")).unwrap_or(value);
+    let value = value.rsplit("/abs/").next().unwrap_or(value);
+    let value = value.rsplit("/pdf/").next().unwrap_or(value);
+    let value = value.trim_end_matches(".pdf");
+    NORMALIZED_ID_REGEX.captures(value).map(|c| c[1].to_string())
+}
+
+pub fn set_request_delay(seconds: f64) {
+    REQUEST_DELAY_MS.store((seconds.max(0.0) * 1000.0) as u64, Ordering::SeqCst);
+}
+
+pub fn is_arxiv_url(url: &str) -> bool {
+    url.split('/').nth(2).is_some_and(|host| host == "arxiv.org" || host.ends_with(".arxiv.org"))
+}
+
+pub fn throttle() {
+    let delay = Duration::from_millis(REQUEST_DELAY_MS.load(Ordering::SeqCst));
+    let mut last = LAST_REQUEST.lock().unwrap();
+    if let Some(at) = *last {
+        let wait = delay.saturating_sub(at.elapsed());
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+    *last = Some(Instant::now());
+}
+
+fn fetch_feed(client: &Client, url: &str) -> Result<Vec<Metadata>, String> {
+    let body = cache::get_text(client, url)?;
+    Ok(ENTRY_REGEX.captures_iter(&body).filter_map(|entry| parse_metadata(&entry[1])).collect())
+}
+
+pub fn fetch_metadata(client: &Client, ids: &[String]) -> HashMap<String, Metadata> {
+    let mut metadata = HashMap::new();
+    for batch in ids.chunks(50) {
+        let url = format!("https://export.arxiv.org/api/query?id_list={}&max_results={}", batch.join(","), batch.len());
+        match fetch_feed(client, &url) {
+            Ok(entries) => metadata.extend(entries.into_iter().map(|meta| (meta.id.clone(), meta))),
+            Err(e) => println!("  Failed to fetch metadata from arXiv API: {}", e),
+        }
+    }
+    metadata
+}
+
+fn search_url(query: &str, start: usize, max_results: usize) -> String {
+    let params = [
+        ("search_query", query.to_string()),
+        ("sortBy", "submittedDate".to_string()),
+        ("sortOrder", "descending".to_string()),
+        ("start", start.to_string()),
+        ("max_results", max_results.to_string()),
+    ];
+    reqwest::Url::parse_with_params(SEARCH_URL, &params).unwrap().to_string()
+}
+
+pub fn search(client: &Client, query: &str, max_results: usize) -> Result<Vec<Metadata>, String> {
+    let mut results: Vec<Metadata> = Vec::new();
+    while results.len() < max_results {
+        let requested = SEARCH_PAGE_SIZE.min(max_results - results.len());
+        let page = fetch_feed(client, &search_url(query, results.len(), requested))?;
+        let done = page.len() < requested;
+        results.extend(page);
+        if done {
+            break;
+        }
+    }
+    Ok(results)
+}
+
+pub fn search_since(client: &Client, query: &str, since: DateTime<Utc>, limit: usize) -> Result<Vec<Metadata>, String> {
+    let query = format!(
+        "({}) AND submittedDate:[{} TO {}]",
+        query,
+        since.format("%Y%m%d%H%M"),
+        Utc::now().format("%Y%m%d%H%M")
+    );
+    search(client, &query, limit)
+}
+
+fn parse_metadata(entry: &str) -> Option<Metadata> {
+    let id_caps = ENTRY_ID_REGEX.captures(entry)?;
+    let id = id_caps[1].to_string();
+    let version = id_caps.get(2).and_then(|v| v.as_str().parse().ok()).unwrap_or(0);
+    let capture = |regex: &Regex| regex.captures(entry).map(|c| unescape_xml(&c[1]));
+    Some(Metadata {
+        id,
+        title: capture(&ENTRY_TITLE_REGEX).unwrap_or_default(),
+        authors: ENTRY_AUTHOR_REGEX.captures_iter(entry).map(|c| unescape_xml(&c[1])).collect(),
+        published: capture(&ENTRY_PUBLISHED_REGEX).unwrap_or_default(),
+        summary: capture(&ENTRY_SUMMARY_REGEX).unwrap_or_default(),
+        primary_category: capture(&ENTRY_CATEGORY_REGEX).unwrap_or_default(),
+        doi: capture(&ENTRY_DOI_REGEX),
+        journal_ref: capture(&ENTRY_JOURNAL_REGEX),
+        version,
+    })
+}
+
+pub fn fetch_abstracts(client: &Client, ids: &[String]) -> HashMap<String, String> {
+    fetch_metadata(client, ids)
+        .into_iter()
+        .filter(|(_, meta)| !meta.summary.is_empty())
+        .map(|(id, meta)| (id, meta.summary))
+        .collect()
+}
+
+pub fn unescape_xml(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+pub fn fetch_arxiv_papers(client: &Client, category: &str, limit: usize) -> Result<Vec<Paper>, String> {
+    let mut all_papers = Vec::new();
+    let base_url = format!("https://arxiv.org/list/{}/recent", category);
+
+    let html = cache::get_text(client, &base_url).map_err(|e| format!("Failed to fetch arXiv page: {}", e))?;
+    collect_papers(&html, &mut all_papers, limit);
+
+    if all_papers.len() < limit {
+        let show_url = format!("{}?skip=0&show={}", base_url, limit);
+        if let Ok(html) = cache::get_text(client, &show_url) {
+            collect_papers(&html, &mut all_papers, limit);
+        }
+    }
+
+    Ok(all_papers)
+}
+
+fn collect_papers(html: &str, all_papers: &mut Vec<Paper>, limit: usize) {
+    let document = Html::parse_document(html);
+
+    let dt_selector = Selector::parse("dt").unwrap();
+    let dd_selector = Selector::parse("dd").unwrap();
+
+    let dts: Vec<_> = document.select(&dt_selector).collect();
+    let dds: Vec<_> = document.select(&dd_selector).collect();
+
+    for (dt, dd) in dts.iter().zip(dds.iter()) {
+        if all_papers.len() >= limit {
+            break;
+        }
+
+        let Some(paper) = parse_entry(dt, dd) else {
+            continue;
+        };
+
+        if all_papers.iter().any(|p| p.id == paper.id) {
+            continue;
+        }
+
+        all_papers.push(paper);
+    }
+}
+
+fn parse_entry(dt: &ElementRef, dd: &ElementRef) -> Option<Paper> {
+    let a_selector = Selector::parse("a").unwrap();
+    let title_selector = Selector::parse("div.list-title").unwrap();
+    let subject_selector = Selector::parse("span.primary-subject").unwrap();
+
+    let paper_id = dt
+        .select(&a_selector)
+        .filter_map(|a| a.value().attr("href"))
+        .find_map(|href| ID_REGEX.captures(href).map(|caps| caps[1].to_string()))?;
+
+    let mut title = dd
+        .select(&title_selector)
+        .next()
+        .map(|div| div.text().collect::<String>().replace("Title:", "").trim().to_string())
+        .unwrap_or_default();
+
+    if title.is_empty() {
+        title = format!("Paper-{}", paper_id);
+    }
+
+    let category = dd
+        .select(&subject_selector)
+        .next()
+        .map(|span| span.text().collect::<String>())
+        .and_then(|subject| CATEGORY_REGEX.captures(&subject).map(|caps| caps[1].to_string()))
+        .unwrap_or_else(|| "cs.AI".to_string());
+
+    Some(Paper {
+        pdf_url: format!("https://arxiv.org/pdf/{}.pdf", paper_id),
+        id: paper_id,
+        title,
+        category,
+        scholar: None,
+        code: None,
+        sources: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_ids_and_urls() {
+        assert_eq!(normalize_id("2501.01234").as_deref(), Some("2501.01234"));
+        assert_eq!(normalize_id(" arXiv:2501.01234v2 ").as_deref(), Some("2501.01234"));
+        assert_eq!(normalize_id("https://arxiv.org/abs/2501.01234v3").as_deref(), Some("2501.01234"));
+        assert_eq!(normalize_id("https://arxiv.org/pdf/2501.01234.pdf").as_deref(), Some("2501.01234"));
+        assert_eq!(normalize_id("0704.0001").as_deref(), Some("0704.0001"));
+    }
+
+    #[test]
+    fn normalizes_old_style_ids() {
+        assert_eq!(normalize_id("hep-th/9901001").as_deref(), Some("hep-th/9901001"));
+        assert_eq!(normalize_id("https://arxiv.org/abs/math.GT/0309136v1").as_deref(), Some("math.GT/0309136"));
+    }
+
+    #[test]
+    fn encodes_search_queries() {
+        assert_eq!(
+            search_url("all:C++ AND ti:\"a&b\"", 0, 10),
+            "https://export.arxiv.org/api/query?search_query=all%3AC%2B%2B+AND+ti%3A%22a%26b%22&sortBy=submittedDate&sortOrder=descending&start=0&max_results=10"
+        );
+        assert!(search_url("100% #1 café", 200, 5).contains("search_query=100%25+%231+caf%C3%A9&"));
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        assert_eq!(normalize_id(""), None);
+        assert_eq!(normalize_id("2501.123"), None);
+        assert_eq!(normalize_id("attention is all you need"), None);
+        assert_eq!(normalize_id("https://example.com/paper.pdf"), None);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::cli::Args;
+use crate::db::Db;
+use crate::embeddings::{self, EmbeddingStore};
+use crate::openai::{self, Message};
+use crate::pdf::extract_text_from_pdf;
+use reqwest::blocking::Client;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const MAX_SUMMARY_CHARS: usize = 6000;
+const MAX_PDF_CHARS: usize = 12000;
+const HISTORY_TURNS: usize = 4;
+
+struct AskContext {
+    client: Client,
+    api_key: String,
+    store: EmbeddingStore,
+    summary_dir: PathBuf,
+    papers_dir: PathBuf,
+    pdf_files: HashMap<String, String>,
+    top_k: usize,
+    with_pdf: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let ras_dir = crate::ras_dir()?;
+    let client = crate::build_client()?;
+    let api_key = openai::require_api_key()?;
+    let store = embeddings::load_updated(&client, &api_key)?;
+
+    if store.entries.is_empty() {
+        return Err("No summaries indexed yet, run the summarizer first".to_string());
+    }
+
+    let ctx = AskContext {
+        client,
+        api_key,
+        store,
+        summary_dir: ras_dir.join("summary"),
+        papers_dir: ras_dir.join("papers"),
+        pdf_files: Db::load()?.papers.into_values().map(|r| (r.summary_file, r.pdf_file)).collect(),
+        top_k: args.parsed("top-k", 5)?,
+        with_pdf: args.flag("with-pdf"),
+    };
+
+    if args.positional.is_empty() {
+        return repl(&ctx);
+    }
+
+    let question = args.positional.join(" ");
+    let mut history = Vec::new();
+    let answer = answer(&ctx, &question, &mut history)?;
+    println!("{}", answer);
+    Ok(())
+}
+
+fn repl(ctx: &AskContext) -> Result<(), String> {
+    println!("Ask questions about your summaries. Type 'exit' to quit.");
+    let stdin = io::stdin();
+    let mut history = Vec::new();
+
+    loop {
+        print!("\nras> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            break;
+        }
+
+        let question = line.trim();
+        if question.is_empty() {
+            continue;
+        }
+        if question == "exit" || question == "quit" {
+            break;
+        }
+
+        match answer(ctx, question, &mut history) {
+            Ok(answer) => println!("\n{}", answer),
+            Err(e) => println!("  Failed to answer: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+fn answer(ctx: &AskContext, question: &str, history: &mut Vec<Message>) -> Result<String, String> {
+    let query = openai::embed(&ctx.client, &ctx.api_key, &[question.to_string()])?
+        .pop()
+        .ok_or("No embedding returned for question")?;
+    let hits = ctx.store.search(&query, ctx.top_k);
+
+    let mut sources = String::new();
+    for (name, _) in &hits {
+        let content = fs::read_to_string(ctx.summary_dir.join(name)).unwrap_or_default();
+        let content: String = content.chars().take(MAX_SUMMARY_CHARS).collect();
+        sources.push_str(&format!("[{}]\n{}\n\n", name, content));
+
+        if ctx.with_pdf
+            && let Some(pdf_file) = ctx.pdf_files.get(name).filter(|f| !f.is_empty())
+            && let Ok(text) = extract_text_from_pdf(&ctx.papers_dir.join(pdf_file))
+        {
+            let text: String = text.chars().take(MAX_PDF_CHARS).collect();
+            sources.push_str(&format!("[{}] (full text excerpt)\n{}\n\n", name, text));
+        }
+    }
+
+    let prompt = format!(
+        r#"Answer the question using only the research paper summaries below.
+        Cite every claim with the source file name in square brackets, e.g. [Some_Paper-summary.md].
+        If the sources do not contain the answer, say so instead of guessing.
+
+        Sources:
+        {}
+
+        Question: {}"#,
+        sources, question
+    );
+
+    let mut messages = vec![Message::new(
+        "system",
+        "You are a research assistant answering questions over a personal library of arXiv paper summaries.",
+    )];
+    messages.extend(history.iter().cloned());
+    messages.push(Message::new("user", &prompt));
+
+    let reply = openai::chat_messages(&ctx.client, &ctx.api_key, messages, 1500)?;
+
+    history.push(Message::new("user", question));
+    history.push(Message::new("assistant", &reply));
+    if history.len() > HISTORY_TURNS * 2 {
+        history.drain(..2);
+    }
+
+    let mut output = reply;
+    output.push_str("\n\nSources:\n");
+    for (name, score) in &hits {
+        output.push_str(&format!("  {:.3}  {}\n", score, ctx.summary_dir.join(name).display()));
+    }
+    Ok(output)
+}
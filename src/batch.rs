@@ -0,0 +1,286 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::config::{Config, ProviderConfig};
+use crate::error::RasError;
+use crate::openai::{self, Usage};
+use crate::pipeline::{self, Extracted, RunContext, RunOptions, sanitize_filename};
+use crate::{cleaning, runs, storage, survey};
+use chrono::Utc;
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+const API_URL: &str = "https://api.openai.com/v1";
+const FINISHED: &[&str] = &["completed", "failed", "expired", "cancelled"];
+
+#[derive(Serialize, Deserialize)]
+struct BatchState {
+    id: String,
+    batch_id: String,
+    model: String,
+    created: String,
+    status: String,
+    papers: Vec<Paper>,
+    #[serde(default)]
+    collected: bool,
+}
+
+#[derive(Deserialize)]
+struct Batch {
+    id: String,
+    status: String,
+    #[serde(default)]
+    output_file_id: Option<String>,
+    #[serde(default)]
+    error_file_id: Option<String>,
+    #[serde(default)]
+    request_counts: Option<RequestCounts>,
+}
+
+#[derive(Deserialize)]
+struct RequestCounts {
+    total: usize,
+    completed: usize,
+    failed: usize,
+}
+
+#[derive(Deserialize)]
+struct UploadedFile {
+    id: String,
+}
+
+fn batches_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("batches"))
+}
+
+fn extracted_path(id: &str, paper: &Paper) -> Result<PathBuf, RasError> {
+    Ok(batches_dir()?.join(id).join(format!("{}.json", sanitize_filename(&paper.id))))
+}
+
+fn save(state: &BatchState) -> Result<(), String> {
+    let dir = batches_dir()?.join(&state.id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(dir.join("batch.json"), json).map_err(|e| e.to_string())
+}
+
+fn load_all() -> Result<Vec<BatchState>, RasError> {
+    let mut states: Vec<BatchState> = fs::read_dir(batches_dir()?)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| fs::read_to_string(e.path().join("batch.json")).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    states.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(states)
+}
+
+fn send<T: for<'de> Deserialize<'de>>(request: RequestBuilder, api_key: &str) -> Result<T, String> {
+    let response = request.bearer_auth(api_key).send().map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body = response.text().map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("API error {}: {}", status, body));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Parse error: {} - Body: {}", e, body))
+}
+
+fn file_content(client: &Client, api_key: &str, file_id: &str) -> Result<String, String> {
+    let response = client
+        .get(format!("{}/files/{}/content", API_URL, file_id))
+        .bearer_auth(api_key)
+        .send()
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body = response.text().map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("API error {}: {}", status, body));
+    }
+    Ok(body)
+}
+
+fn batch_provider(config: &Config) -> ProviderConfig {
+    config.llm.providers.iter().find(|p| p.provider == "openai").cloned().unwrap_or_default()
+}
+
+fn batch_model(config: &Config) -> String {
+    batch_provider(config).model
+}
+
+fn submit(args: &Args) -> Result<(), String> {
+    let api_key = openai::require_api_key()?;
+    let config = Config::load()?;
+    let model = batch_model(&config);
+    let mut ctx = RunContext::new(config, RunOptions::from_args(args)?)?;
+    ctx.openai_key = api_key;
+    storage::check_free_space(&ctx.config.storage, &ctx.papers_dir)?;
+
+    let pending: HashSet<String> = load_all()?
+        .into_iter()
+        .filter(|s| !s.collected)
+        .flat_map(|s| s.papers.into_iter().map(|p| p.id))
+        .collect();
+    let (_, papers) = pipeline::select_papers(&ctx);
+    let papers: Vec<Paper> = papers.into_iter().filter(|p| !pending.contains(&p.id)).collect();
+    if papers.is_empty() {
+        println!("Nothing to submit");
+        return Ok(());
+    }
+
+    let id = runs::run_id(Utc::now());
+    fs::create_dir_all(batches_dir()?.join(&id)).map_err(|e| e.to_string())?;
+    let mut lines = Vec::new();
+    let mut batched = Vec::new();
+    for paper in papers {
+        let extracted = match pipeline::extract_paper(&paper, &ctx) {
+            Ok(extracted) => extracted,
+            Err(outcome) => {
+                pipeline::store_outcome(&paper, &outcome, &ctx);
+                continue;
+            }
+        };
+        if pipeline::needs_chunking(&ctx, &extracted.text) || survey::is_survey(&ctx.config.survey, &paper, &extracted.text) {
+            println!("  Summarizing now, too long for a single batch request: {}", paper.title);
+            let outcome = pipeline::summarize_extracted(&paper, extracted, &ctx);
+            pipeline::store_outcome(&paper, &outcome, &ctx);
+            continue;
+        }
+        let request = json!({
+            "custom_id": paper.id,
+            "method": "POST",
+            "url": "/v1/chat/completions",
+            "body": {
+                "model": model,
+                "messages": [{"role": "user", "content": pipeline::draft_prompt(&ctx, &paper, &cleaning::clean(&extracted.text, &ctx.config.cleaning))}],
+                "max_completion_tokens": ctx.options.depth.max_tokens(),
+            },
+        });
+        lines.push(request.to_string());
+        let json = serde_json::to_string(&extracted).map_err(|e| e.to_string())?;
+        fs::write(extracted_path(&id, &paper)?, json).map_err(|e| e.to_string())?;
+        batched.push(paper);
+    }
+    if batched.is_empty() {
+        println!("No papers left to batch");
+        return Ok(());
+    }
+
+    println!("Uploading {} summarization requests...", batched.len());
+    let part = Part::bytes(lines.join("\n").into_bytes()).file_name(format!("ras-{}.jsonl", id));
+    let form = Form::new().text("purpose", "batch").part("file", part);
+    let file: UploadedFile = send(ctx.client.post(format!("{}/files", API_URL)).multipart(form), &ctx.openai_key)?;
+    let request = json!({"input_file_id": file.id, "endpoint": "/v1/chat/completions", "completion_window": "24h"});
+    let batch: Batch = send(ctx.client.post(format!("{}/batches", API_URL)).json(&request), &ctx.openai_key)?;
+
+    let state = BatchState { id, batch_id: batch.id, model, created: Utc::now().to_rfc3339(), status: batch.status, papers: batched, collected: false };
+    save(&state)?;
+    println!("Submitted batch {} with {} papers. Collect the summaries with `ras batch poll`", state.batch_id, state.papers.len());
+    Ok(())
+}
+
+fn parse_output(ctx: &RunContext, content: &str, errors: &mut HashMap<String, String>) {
+    for line in content.lines().filter_map(|line| serde_json::from_str::<Value>(line).ok()) {
+        let Some(id) = line["custom_id"].as_str().map(str::to_string) else {
+            continue;
+        };
+        let body = &line["response"]["body"];
+        match body["choices"][0]["message"]["content"].as_str() {
+            Some(draft) => {
+                let usage: Usage = serde_json::from_value(body["usage"].clone()).unwrap_or_default();
+                let (input_price, output_price) = batch_provider(&ctx.config).prices().unwrap_or_default();
+                // The Batch API bills half the regular price
+                openai::record_usage("batch", Usage::priced(usage.prompt_tokens, usage.completion_tokens, (input_price / 2.0, output_price / 2.0)));
+                ctx.drafts.lock().unwrap().insert(id, draft.to_string());
+            }
+            None => {
+                let error = line["error"]["message"].as_str().or(body["error"]["message"].as_str()).unwrap_or("no response");
+                errors.insert(id, error.to_string());
+            }
+        }
+    }
+}
+
+fn collect(ctx: &RunContext, state: &mut BatchState, batch: &Batch) -> Result<(), String> {
+    let mut errors = HashMap::new();
+    for file_id in [&batch.output_file_id, &batch.error_file_id].into_iter().flatten() {
+        parse_output(ctx, &file_content(&ctx.client, &ctx.openai_key, file_id)?, &mut errors);
+    }
+
+    for paper in &state.papers {
+        let path = extracted_path(&state.id, paper)?;
+        let extracted: Option<Extracted> = fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str(&c).ok());
+        let has_draft = ctx.drafts.lock().unwrap().contains_key(&paper.id);
+        match extracted {
+            Some(extracted) if has_draft => {
+                println!("Writing summary: {}", paper.title);
+                openai::set_last_model(&format!("openai/{} (batch)", state.model));
+                let outcome = pipeline::summarize_extracted(paper, extracted, ctx);
+                pipeline::store_outcome(paper, &outcome, ctx);
+            }
+            _ => {
+                let error = errors.remove(&paper.id).unwrap_or_else(|| format!("batch {} returned no result", batch.status));
+                println!("  No batch summary for {}: {}", paper.title, error);
+                pipeline::record_failure(ctx, paper, &error);
+            }
+        }
+        let _ = fs::remove_file(path);
+    }
+    pipeline::finish_run(ctx, state.papers.len(), &state.papers);
+    state.collected = true;
+    Ok(())
+}
+
+fn poll() -> Result<(), String> {
+    let api_key = openai::require_api_key()?;
+    let mut states: Vec<BatchState> = load_all()?.into_iter().filter(|s| !s.collected).collect();
+    if states.is_empty() {
+        println!("No pending batches");
+        return Ok(());
+    }
+    let mut ctx = RunContext::new(Config::load()?, RunOptions::default())?;
+    ctx.openai_key = api_key;
+    openai::take_usage();
+    for state in states.iter_mut() {
+        let batch: Batch = send(ctx.client.get(format!("{}/batches/{}", API_URL, state.batch_id)), &ctx.openai_key)?;
+        state.status = batch.status.clone();
+        match &batch.request_counts {
+            Some(counts) => println!("Batch {}: {} ({}/{} done, {} failed)", state.batch_id, batch.status, counts.completed, counts.total, counts.failed),
+            None => println!("Batch {}: {}", state.batch_id, batch.status),
+        }
+        if FINISHED.contains(&batch.status.as_str()) {
+            collect(&ctx, state, &batch)?;
+        }
+        save(state)?;
+    }
+    for (pass, usage) in openai::take_usage() {
+        println!("  {}: {} prompt + {} completion tokens (${:.4})", pass, usage.prompt_tokens, usage.completion_tokens, usage.cost);
+    }
+    Ok(())
+}
+
+fn list() -> Result<(), String> {
+    let states = load_all()?;
+    if states.is_empty() {
+        println!("No batches submitted yet");
+        return Ok(());
+    }
+    for state in states {
+        let status = if state.collected { "collected" } else { state.status.as_str() };
+        println!("  {}  {:<28} {:<12} {} papers ({})", state.id, state.batch_id, status, state.papers.len(), state.model);
+    }
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    match args.positional.first().map(String::as_str) {
+        Some("submit") => submit(args),
+        Some("poll") => poll(),
+        None | Some("list") => list(),
+        Some(other) => Err(format!("Unknown batch command: {} (expected submit, poll or list)", other)),
+    }
+}
@@ -0,0 +1,201 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::db::Db;
+use crate::error::RasError;
+use crate::openai;
+use crate::pdf::extract_text_from_pdf;
+use crate::pipeline::sanitize_filename;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+static TABLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?i)table\s+[A-Z]?\d{1,3}\b").unwrap());
+static NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|[\s(|±])[-+]?\d{1,4}(?:\.\d+)?%?(?:$|[\s)|,±])").unwrap());
+static VALUE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[-+]?\d+(?:\.\d+)?").unwrap());
+
+const MIN_NUMBERS: usize = 3;
+const MAX_CONTEXT_CHARS: usize = 15000;
+const MAX_ROWS: usize = 200;
+const HEADER: &str = "benchmark,model,metric,value";
+
+static BENCHMARKS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Row {
+    pub benchmark: String,
+    pub model: String,
+    pub metric: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct PaperResults {
+    title: String,
+    rows: Vec<Row>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct BenchmarksLog {
+    mined: Vec<String>,
+    papers: BTreeMap<String, PaperResults>,
+}
+
+fn log_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("benchmarks.json"))
+}
+
+pub fn csv_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("benchmarks.csv"))
+}
+
+fn papers_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("benchmarks"))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) { format!("\"{}\"", value.replace('"', "\"\"")) } else { value.to_string() }
+}
+
+fn csv_line(fields: &[&str]) -> String {
+    format!("{}\n", fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","))
+}
+
+impl BenchmarksLog {
+    fn load() -> BenchmarksLog {
+        log_path().ok().and_then(|path| fs::read_to_string(path).ok()).and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(log_path()?, json).map_err(|e| e.to_string())?;
+        let mut merged = format!("paper_id,title,{}\n", HEADER);
+        for (id, paper) in &self.papers {
+            for row in &paper.rows {
+                merged.push_str(&csv_line(&[id, &paper.title, &row.benchmark, &row.model, &row.metric, &row.value]));
+            }
+        }
+        fs::write(csv_path()?, merged).map_err(|e| e.to_string())
+    }
+}
+
+pub fn tables(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_table = false;
+    for line in text.lines().map(str::trim) {
+        let numbers = NUMBER_REGEX.find_iter(line).count();
+        if TABLE_REGEX.is_match(line) {
+            out.push_str(&format!("\n{}\n", line));
+            in_table = true;
+        } else if numbers >= MIN_NUMBERS || (in_table && !line.is_empty() && line.len() < 200) {
+            out.push_str(line);
+            out.push('\n');
+            in_table = (in_table && numbers > 0) || numbers >= MIN_NUMBERS;
+        } else {
+            in_table = false;
+        }
+        if out.len() >= MAX_CONTEXT_CHARS {
+            break;
+        }
+    }
+    out.chars().take(MAX_CONTEXT_CHARS).collect()
+}
+
+fn parse(response: &str, text: &str) -> Result<Vec<Row>, String> {
+    let Some(json) = response.find('[').zip(response.rfind(']')).filter(|(start, end)| start < end).map(|(start, end)| &response[start..=end]) else {
+        return Err("Model did not return a JSON array".to_string());
+    };
+    let mut rows: Vec<Row> = serde_json::from_str(json).map_err(|e| format!("Invalid results JSON: {}", e))?;
+    for row in rows.iter_mut() {
+        for field in [&mut row.benchmark, &mut row.model, &mut row.metric, &mut row.value] {
+            *field = field.trim().to_string();
+        }
+    }
+    let mut seen = HashSet::new();
+    Ok(rows
+        .into_iter()
+        .filter(|r| !r.benchmark.is_empty() && !r.model.is_empty())
+        .filter(|r| VALUE_REGEX.find(&r.value).is_some_and(|m| text.contains(m.as_str())))
+        .filter(|r| seen.insert((r.benchmark.clone(), r.model.clone(), r.metric.clone())))
+        .take(MAX_ROWS)
+        .collect())
+}
+
+pub fn extract(client: &Client, api_key: &str, paper: &Paper, text: &str) -> Result<Vec<Row>, String> {
+    let tables = tables(text);
+    if tables.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let prompt = format!(
+        r#"Below are the results tables and number-heavy lines from the paper "{}".
+        Extract every reported result as a JSON array of objects with the string fields "benchmark", "model", "metric" and "value".
+        Use the dataset or benchmark name as "benchmark", the method or model name as "model" (including the paper's own method), the metric name as "metric" (e.g. "accuracy", "ROUGE-L", "BLEU") and the number exactly as printed as "value".
+        Skip ablations without a named model, hyperparameters and dataset statistics. Answer with the JSON array only, or [] if there are no results.
+
+        Tables:
+        {}"#,
+        paper.title, tables
+    );
+    let response = openai::in_pass("benchmarks", || openai::chat(client, api_key, &prompt, 4000))?;
+    parse(&response, text)
+}
+
+pub fn record(paper: &Paper, rows: &[Row]) -> Result<usize, String> {
+    let _guard = BENCHMARKS_LOCK.lock().unwrap();
+    let mut log = BenchmarksLog::load();
+    if !rows.is_empty() {
+        fs::create_dir_all(papers_dir()?).map_err(|e| e.to_string())?;
+        let mut csv = format!("{}\n", HEADER);
+        for row in rows {
+            csv.push_str(&csv_line(&[&row.benchmark, &row.model, &row.metric, &row.value]));
+        }
+        fs::write(papers_dir()?.join(format!("{}.csv", sanitize_filename(&paper.id))), csv).map_err(|e| e.to_string())?;
+        log.papers.insert(paper.id.clone(), PaperResults { title: paper.title.clone(), rows: rows.to_vec() });
+    }
+    if !log.mined.contains(&paper.id) {
+        log.mined.push(paper.id.clone());
+    }
+    log.save()?;
+    Ok(rows.len())
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let limit: usize = args.parsed("limit", usize::MAX)?;
+    let client = crate::build_client()?;
+    let api_key = openai::chat_key()?;
+    let papers_dir = crate::ras_dir()?.join("papers");
+
+    let mined: HashSet<String> = BenchmarksLog::load().mined.into_iter().collect();
+    let pending: Vec<(Paper, String)> = Db::load()?
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && !r.pdf_file.is_empty() && !mined.contains(&r.id))
+        .map(|r| (r.to_paper(), r.pdf_file.clone()))
+        .take(limit)
+        .collect();
+
+    println!("Extracting results tables from {} papers", pending.len());
+    for (paper, pdf_file) in pending {
+        let text = match extract_text_from_pdf(&papers_dir.join(pdf_file)) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("  Skipping {}: {}", paper.title, e);
+                continue;
+            }
+        };
+        match extract(&client, &api_key, &paper, &text) {
+            Ok(rows) => println!("  {}: {} results", paper.title, record(&paper, &rows)?),
+            Err(e) => println!("  Failed to extract results from {}: {}", paper.title, e),
+        }
+    }
+
+    println!("Results table: {}", csv_path()?.display());
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::arxiv::Paper;
+use chrono::{Duration, Local};
+use reqwest::blocking::Client;
+use serde_json::Value;
+
+const API_URL: &str = "https://api.biorxiv.org/details";
+const DOI_PREFIX: &str = "10.1101/";
+
+pub const SERVERS: &[&str] = &["biorxiv", "medrxiv"];
+
+pub fn split_id(id: &str) -> Option<(&str, &str)> {
+    let (server, suffix) = id.split_once(':')?;
+    SERVERS.contains(&server).then_some((server, suffix))
+}
+
+pub fn doi(id: &str) -> Option<String> {
+    split_id(id).map(|(_, suffix)| format!("{}{}", DOI_PREFIX, suffix))
+}
+
+pub fn abs_url(id: &str) -> Option<String> {
+    split_id(id).map(|(server, suffix)| format!("https://www.{}.org/content/{}{}", server, DOI_PREFIX, suffix))
+}
+
+pub fn pdf_url(id: &str, version: &str) -> Option<String> {
+    let version = if version.is_empty() { String::new() } else { format!("v{}", version) };
+    abs_url(id).map(|url| format!("{}{}.full.pdf", url, version))
+}
+
+pub fn fetch(client: &Client, server: &str, days: i64, categories: &[String], limit: usize) -> Result<Vec<Paper>, String> {
+    let to = Local::now().date_naive();
+    let from = to - Duration::days(days.max(1));
+    let mut papers: Vec<(Paper, u32)> = Vec::new();
+    let mut cursor = 0;
+
+    loop {
+        let url = format!("{}/{}/{}/{}/{}", API_URL, server, from, to, cursor);
+        let body: Value = client
+            .get(&url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| e.to_string())?;
+        let collection = body["collection"].as_array().cloned().unwrap_or_default();
+        for entry in &collection {
+            let Some((paper, version)) = parse_entry(server, entry) else {
+                continue;
+            };
+            if !categories.is_empty() && !categories.iter().any(|c| c.eq_ignore_ascii_case(&paper.category)) {
+                continue;
+            }
+            match papers.iter_mut().find(|(p, _)| p.id == paper.id) {
+                Some(existing) if existing.1 < version => *existing = (paper, version),
+                Some(_) => {}
+                None => papers.push((paper, version)),
+            }
+        }
+
+        let total = body["messages"][0]["total"]
+            .as_u64()
+            .or_else(|| body["messages"][0]["total"].as_str().and_then(|t| t.parse().ok()))
+            .unwrap_or(0) as usize;
+        cursor += collection.len();
+        if collection.is_empty() || cursor >= total || papers.len() >= limit {
+            break;
+        }
+    }
+
+    papers.truncate(limit);
+    Ok(papers.into_iter().map(|(paper, _)| paper).collect())
+}
+
+fn parse_entry(server: &str, entry: &Value) -> Option<(Paper, u32)> {
+    let suffix = entry["doi"].as_str()?.strip_prefix(DOI_PREFIX)?;
+    let title = entry["title"].as_str()?.split_whitespace().collect::<Vec<_>>().join(" ");
+    let version = entry["version"].as_str().unwrap_or("1");
+    let id = format!("{}:{}", server, suffix);
+    Some((
+        Paper {
+            pdf_url: pdf_url(&id, version)?,
+            id,
+            title,
+            category: entry["category"].as_str().unwrap_or(server).trim().to_string(),
+            scholar: None,
+            code: None,
+            sources: Vec::new(),
+        },
+        version.parse().unwrap_or(1),
+    ))
+}
@@ -0,0 +1,222 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::html::{escape_html, render_markdown};
+use crate::pipeline::parse_window;
+use crate::summary::{self, strip_frontmatter};
+use chrono::{DateTime, Local, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zip::CompressionMethod;
+use zip::write::{SimpleFileOptions, ZipWriter};
+
+const STYLE: &str = r#"body { font-family: Georgia, serif; line-height: 1.5; margin: 0 1em; }
+h1, h2, h3 { font-family: -apple-system, "Segoe UI", sans-serif; }
+.meta { color: #666; font-size: 0.9em; }
+.paper { page-break-before: always; }
+table { border-collapse: collapse; }
+td, th { padding: 4px 6px; border-bottom: 1px solid #ddd; }"#;
+
+const PDF_TOOLS: &[&str] = &["wkhtmltopdf", "weasyprint"];
+
+struct Chapter<'a> {
+    topic: String,
+    papers: Vec<&'a PaperRecord>,
+}
+
+fn books_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("books"))
+}
+
+fn chapters(records: Vec<&PaperRecord>) -> Vec<Chapter<'_>> {
+    let mut topics: BTreeMap<String, Vec<&PaperRecord>> = BTreeMap::new();
+    for record in records {
+        let topic = record.tags.first().or(Some(&record.category)).filter(|t| !t.is_empty()).cloned().unwrap_or_else(|| "Other".to_string());
+        topics.entry(topic).or_default().push(record);
+    }
+    topics.into_iter().map(|(topic, papers)| Chapter { topic, papers }).collect()
+}
+
+fn paper_html(record: &PaperRecord, anchor: &str, summary_dir: &Path) -> String {
+    let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+    let content = summary::with_notes(&content, &record.notes);
+    let body = strip_frontmatter(&content);
+    let body = body.split_once("\n---\n").map(|(_, b)| b).unwrap_or(body);
+    format!(
+        "<div class=\"paper\">\n<h2 id=\"{}\">{}</h2>\n<p class=\"meta\">arXiv {} · {}</p>\n{}</div>\n",
+        anchor,
+        escape_html(&record.title),
+        escape_html(&record.id),
+        escape_html(&record.category),
+        render_markdown(body)
+    )
+}
+
+fn xhtml(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n<head><title>{}</title><link rel=\"stylesheet\" href=\"style.css\"/></head>\n<body>\n{}\n</body></html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+fn write_epub(path: &Path, title: &str, chapters: &[Chapter], summary_dir: &Path) -> Result<(), String> {
+    let mut zip = ZipWriter::new(fs::File::create(path).map_err(|e| e.to_string())?);
+    let mut add = |name: &str, content: &str, options: SimpleFileOptions| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(content.as_bytes()).map_err(|e| e.to_string())
+    };
+    let deflated = SimpleFileOptions::default();
+    add("mimetype", "application/epub+zip", SimpleFileOptions::default().compression_method(CompressionMethod::Stored))?;
+    add(
+        "META-INF/container.xml",
+        r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>
+"#,
+        deflated,
+    )?;
+    add("OEBPS/style.css", STYLE, deflated)?;
+
+    let mut nav = format!("<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n<ol>\n", escape_html(title));
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        let file = format!("chapter{}.xhtml", i + 1);
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&chapter.topic));
+        nav.push_str(&format!("<li><a href=\"{}\">{}</a>\n<ol>\n", file, escape_html(&chapter.topic)));
+        for (j, record) in chapter.papers.iter().enumerate() {
+            let anchor = format!("p{}", j + 1);
+            body.push_str(&paper_html(record, &anchor, summary_dir));
+            nav.push_str(&format!("<li><a href=\"{}#{}\">{}</a></li>\n", file, anchor, escape_html(&record.title)));
+        }
+        nav.push_str("</ol></li>\n");
+        add(&format!("OEBPS/{}", file), &xhtml(&chapter.topic, &body), deflated)?;
+        manifest.push_str(&format!("<item id=\"c{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n", i + 1, file));
+        spine.push_str(&format!("<itemref idref=\"c{}\"/>\n", i + 1));
+    }
+    nav.push_str("</ol>\n</nav>");
+    add("OEBPS/nav.xhtml", &xhtml(title, &nav), deflated)?;
+
+    let opf = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="id">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:identifier id="id">urn:ras:{}</dc:identifier>
+<dc:title>{}</dc:title>
+<dc:creator>ras</dc:creator>
+<dc:language>en</dc:language>
+<meta property="dcterms:modified">{}</meta>
+</metadata>
+<manifest>
+<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+<item id="style" href="style.css" media-type="text/css"/>
+{}</manifest>
+<spine>
+<itemref idref="nav"/>
+{}</spine>
+</package>
+"#,
+        Utc::now().timestamp(),
+        escape_html(title),
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+        manifest,
+        spine
+    );
+    add("OEBPS/content.opf", &opf, deflated)?;
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn single_html(title: &str, chapters: &[Chapter], summary_dir: &Path) -> String {
+    let mut toc = format!("<h1>{}</h1>\n<ol>\n", escape_html(title));
+    let mut body = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        toc.push_str(&format!("<li>{}\n<ol>\n", escape_html(&chapter.topic)));
+        body.push_str(&format!("<h1 class=\"paper\">{}</h1>\n", escape_html(&chapter.topic)));
+        for (j, record) in chapter.papers.iter().enumerate() {
+            let anchor = format!("c{}p{}", i + 1, j + 1);
+            toc.push_str(&format!("<li><a href=\"#{}\">{}</a></li>\n", anchor, escape_html(&record.title)));
+            body.push_str(&paper_html(record, &anchor, summary_dir));
+        }
+        toc.push_str("</ol></li>\n");
+    }
+    toc.push_str("</ol>\n");
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head>\n<body>\n{}{}</body></html>\n",
+        escape_html(title),
+        STYLE,
+        toc,
+        body
+    )
+}
+
+fn write_pdf(path: &Path, html: &str) -> Result<(), String> {
+    let source = path.with_extension("html");
+    fs::write(&source, html).map_err(|e| e.to_string())?;
+    let mut result = Err(format!("PDF output needs one of {} installed, or use --format epub|html", PDF_TOOLS.join(", ")));
+    for tool in PDF_TOOLS {
+        match Command::new(tool).arg(&source).arg(path).output() {
+            Ok(output) if output.status.success() => {
+                result = Ok(());
+                break;
+            }
+            Ok(output) => {
+                result = Err(format!("{} failed: {}", tool, String::from_utf8_lossy(&output.stderr).trim()));
+                break;
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                result = Err(e.to_string());
+                break;
+            }
+        }
+    }
+    let _ = fs::remove_file(&source);
+    result
+}
+
+pub fn export(args: &Args) -> Result<(), String> {
+    let window = args.value("last").unwrap_or("7d");
+    let since = Utc::now() - parse_window(window)?;
+    let format = args.value("format").unwrap_or("epub");
+    if !matches!(format, "epub" | "pdf" | "html") {
+        return Err(format!("Unknown book format: {} (expected epub, pdf or html)", format));
+    }
+
+    let db = Db::load()?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut records: Vec<&PaperRecord> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && DateTime::parse_from_rfc3339(&r.added_at).is_ok_and(|t| t.with_timezone(&Utc) >= since))
+        .collect();
+    records.sort_by(|a, b| a.added_at.cmp(&b.added_at));
+    if records.is_empty() {
+        println!("No summaries from the last {}", window);
+        return Ok(());
+    }
+    let count = records.len();
+    let chapters = chapters(records);
+
+    let date = Local::now().format("%Y-%m-%d");
+    let title = format!("Research Summaries — {} (last {})", date, window);
+    let path = match args.value("output") {
+        Some(output) => PathBuf::from(output),
+        None => {
+            fs::create_dir_all(books_dir()?).map_err(|e| e.to_string())?;
+            books_dir()?.join(format!("ras-{}.{}", date, format))
+        }
+    };
+    match format {
+        "epub" => write_epub(&path, &title, &chapters, &summary_dir)?,
+        "pdf" => write_pdf(&path, &single_html(&title, &chapters, &summary_dir))?,
+        _ => fs::write(&path, single_html(&title, &chapters, &summary_dir)).map_err(|e| e.to_string())?,
+    }
+    println!("Compiled {} summaries in {} topics: {}", count, chapters.len(), path.display());
+    Ok(())
+}
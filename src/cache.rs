@@ -0,0 +1,330 @@
+use crate::cli::Args;
+use crate::config::CacheConfig;
+use crate::error::RasError;
+use crate::openai::{Message, Usage};
+use crate::pipeline::PROMPT_VERSION;
+use crate::retry;
+use chrono::{DateTime, Duration, Utc};
+use md5::{Digest, Md5};
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+static TTL_MINUTES: AtomicI64 = AtomicI64::new(60);
+static DISABLED: AtomicBool = AtomicBool::new(false);
+static LLM_ENABLED: AtomicBool = AtomicBool::new(true);
+static NO_STORE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    fetched: String,
+    body: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedCompletion {
+    model: String,
+    created: String,
+    content: String,
+    usage: Usage,
+    #[serde(default)]
+    hits: u64,
+}
+
+pub fn configure(config: &CacheConfig) {
+    TTL_MINUTES.store(config.listing_ttl_minutes, Ordering::SeqCst);
+    LLM_ENABLED.store(config.llm, Ordering::SeqCst);
+}
+
+pub fn disable() {
+    DISABLED.store(true, Ordering::SeqCst);
+}
+
+// Dry runs still read cached responses but never write new ones
+pub fn no_store() {
+    NO_STORE.store(true, Ordering::SeqCst);
+}
+
+fn http_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("cache").join("http"))
+}
+
+fn llm_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("cache").join("llm"))
+}
+
+fn entry_path(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{:x}.json", Md5::digest(url.as_bytes())))
+}
+
+fn load(dir: &Path, url: &str) -> Option<CachedResponse> {
+    let content = fs::read_to_string(entry_path(dir, url)).ok()?;
+    serde_json::from_str::<CachedResponse>(&content).ok().filter(|entry| entry.url == url)
+}
+
+fn store(dir: &Path, entry: &CachedResponse) {
+    if NO_STORE.load(Ordering::SeqCst) {
+        return;
+    }
+    let json = match serde_json::to_string(entry) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    if fs::create_dir_all(dir).is_ok() {
+        let _ = fs::write(entry_path(dir, &entry.url), json);
+    }
+}
+
+pub fn get_text(client: &Client, url: &str) -> Result<String, String> {
+    let ttl = (!DISABLED.load(Ordering::SeqCst)).then(|| Duration::minutes(TTL_MINUTES.load(Ordering::SeqCst)));
+    get_cached(&http_dir()?, client, url, ttl)
+}
+
+fn get_cached(dir: &Path, client: &Client, url: &str, ttl: Option<Duration>) -> Result<String, String> {
+    let cached = ttl.and_then(|_| load(dir, url));
+    if let (Some(entry), Some(ttl)) = (&cached, ttl)
+        && let Ok(fetched) = DateTime::parse_from_rfc3339(&entry.fetched)
+        && Utc::now() - fetched.with_timezone(&Utc) < ttl
+    {
+        return Ok(entry.body.clone());
+    }
+
+    let response = retry::send(url, || {
+        let mut request = client.get(url);
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, modified);
+            }
+        }
+        request
+    })?;
+    if response.status() == StatusCode::NOT_MODIFIED
+        && let Some(mut entry) = cached
+    {
+        entry.fetched = Utc::now().to_rfc3339();
+        store(dir, &entry);
+        return Ok(entry.body);
+    }
+
+    let response = response.error_for_status().map_err(|e| e.to_string())?;
+    let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let body = response.text().map_err(|e| e.to_string())?;
+    store(dir, &CachedResponse { url: url.to_string(), etag, last_modified, fetched: Utc::now().to_rfc3339(), body: body.clone() });
+    Ok(body)
+}
+
+pub fn completion_key(model: &str, messages: &[Message], max_tokens: u32) -> String {
+    let mut hasher = Md5::new();
+    hasher.update(format!("{}\n{}\n{}", model, PROMPT_VERSION, max_tokens));
+    for message in messages {
+        hasher.update(&message.role);
+        hasher.update(Md5::digest(message.content.as_bytes()));
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn completion_path(key: &str) -> Result<PathBuf, RasError> {
+    Ok(llm_dir()?.join(format!("{}.json", key)))
+}
+
+pub fn completion(key: &str) -> Option<String> {
+    if DISABLED.load(Ordering::SeqCst) || !LLM_ENABLED.load(Ordering::SeqCst) {
+        return None;
+    }
+    let path = completion_path(key).ok()?;
+    let mut entry: CachedCompletion = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+    entry.hits += 1;
+    if !NO_STORE.load(Ordering::SeqCst)
+        && let Ok(json) = serde_json::to_string(&entry)
+    {
+        let _ = fs::write(&path, json);
+    }
+    Some(entry.content)
+}
+
+pub fn store_completion(key: &str, model: &str, content: &str, usage: Usage) {
+    if !LLM_ENABLED.load(Ordering::SeqCst) || NO_STORE.load(Ordering::SeqCst) || content.trim().is_empty() {
+        return;
+    }
+    let entry = CachedCompletion { model: model.to_string(), created: Utc::now().to_rfc3339(), content: content.to_string(), usage, hits: 0 };
+    if let Ok(json) = serde_json::to_string(&entry)
+        && let (Ok(dir), Ok(path)) = (llm_dir(), completion_path(key))
+        && fs::create_dir_all(dir).is_ok()
+    {
+        let _ = fs::write(path, json);
+    }
+}
+
+fn files(dir: &PathBuf) -> Vec<(PathBuf, u64)> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.metadata().ok().filter(|m| m.is_file()).map(|m| (e.path(), m.len())))
+        .collect()
+}
+
+fn stats() -> Result<(), String> {
+    let http = files(&http_dir()?);
+    let size = |files: &[(PathBuf, u64)]| files.iter().map(|(_, len)| len).sum::<u64>() as f64 / 1_048_576.0;
+    println!("HTTP responses: {} ({:.1} MB)", http.len(), size(&http));
+
+    let llm = files(&llm_dir()?);
+    let entries: Vec<CachedCompletion> = llm
+        .iter()
+        .filter_map(|(path, _)| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    let hits: u64 = entries.iter().map(|e| e.hits).sum();
+    let saved: f64 = entries.iter().map(|e| e.usage.cost * e.hits as f64).sum();
+    println!("LLM completions: {} ({:.1} MB), reused {} times, saving about ${:.4}", llm.len(), size(&llm), hits, saved);
+    let mut models: Vec<(&str, usize)> = Vec::new();
+    for entry in &entries {
+        match models.iter_mut().find(|(model, _)| *model == entry.model) {
+            Some((_, count)) => *count += 1,
+            None => models.push((&entry.model, 1)),
+        }
+    }
+    for (model, count) in models {
+        println!("  {:<40} {}", model, count);
+    }
+    Ok(())
+}
+
+fn clear(target: Option<&str>) -> Result<(), String> {
+    let dirs = match target {
+        None | Some("all") => vec![http_dir()?, llm_dir()?],
+        Some("http") => vec![http_dir()?],
+        Some("llm") => vec![llm_dir()?],
+        Some(other) => return Err(format!("Unknown cache: {} (expected http, llm or all)", other)),
+    };
+    for dir in dirs {
+        let removed = files(&dir).len();
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+        }
+        println!("Removed {} entries from {}", removed, dir.display());
+    }
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    match args.positional.first().map(String::as_str) {
+        None | Some("stats") => stats(),
+        Some("clear") => clear(args.positional.get(1).map(String::as_str)),
+        Some(other) => Err(format!("Unknown cache command: {} (expected stats or clear)", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    fn listing_server(body: Arc<Mutex<&'static str>>) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/list/cs.AI/recent", server.server_addr().to_ip().unwrap());
+        let seen: Arc<Mutex<Vec<Option<String>>>> = Arc::default();
+        let requests = Arc::clone(&seen);
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let body = *body.lock().unwrap();
+                let etag = format!("\"{:x}\"", Md5::digest(body.as_bytes()));
+                let sent = request.headers().iter().find(|h| h.field.equiv("If-None-Match")).map(|h| h.value.to_string());
+                requests.lock().unwrap().push(sent.clone());
+                let response = if sent.as_deref() == Some(etag.as_str()) {
+                    tiny_http::Response::from_string("").with_status_code(304)
+                } else {
+                    tiny_http::Response::from_string(body)
+                };
+                let _ = request.respond(response.with_header(tiny_http::Header::from_bytes("ETag", etag).unwrap()));
+            }
+        });
+        (url, seen)
+    }
+
+    fn messages(parts: &[(&str, &str)]) -> Vec<Message> {
+        parts.iter().map(|(role, content)| Message::new(role, content)).collect()
+    }
+
+    #[test]
+    fn keys_completions_by_model_limit_and_every_message() {
+        let prompt = messages(&[("system", "Summarize"), ("user", "paper text")]);
+        let key = completion_key("openai/gpt-4o-mini", &prompt, 2000);
+        assert_eq!(key, completion_key("openai/gpt-4o-mini", &prompt, 2000));
+        assert_eq!(key.len(), 32);
+        assert_ne!(key, completion_key("openai/gpt-4o", &prompt, 2000));
+        assert_ne!(key, completion_key("openai/gpt-4o-mini", &prompt, 4000));
+        assert_ne!(key, completion_key("openai/gpt-4o-mini", &messages(&[("system", "Summarize"), ("user", "paper text!")]), 2000));
+        assert_ne!(key, completion_key("openai/gpt-4o-mini", &messages(&[("user", "Summarize"), ("user", "paper text")]), 2000));
+    }
+
+    #[test]
+    fn keeps_message_boundaries_in_the_key() {
+        let split = |a: &str, b: &str| completion_key("m", &messages(&[("user", a), ("user", b)]), 100);
+        assert_ne!(split("ab", "c"), split("a", "bc"));
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ras-cache-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn serves_fresh_entries_without_asking_the_server() {
+        let (url, seen) = listing_server(Arc::new(Mutex::new("papers v1")));
+        let (dir, client) = (cache_dir("fresh"), Client::new());
+        assert_eq!(get_cached(&dir, &client, &url, Some(Duration::minutes(60))).unwrap(), "papers v1");
+        assert_eq!(get_cached(&dir, &client, &url, Some(Duration::minutes(60))).unwrap(), "papers v1");
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn revalidates_stale_entries_with_their_etag() {
+        let body = Arc::new(Mutex::new("papers v1"));
+        let (url, seen) = listing_server(Arc::clone(&body));
+        let (dir, client) = (cache_dir("stale"), Client::new());
+        assert_eq!(get_cached(&dir, &client, &url, Some(Duration::zero())).unwrap(), "papers v1");
+        assert_eq!(get_cached(&dir, &client, &url, Some(Duration::zero())).unwrap(), "papers v1");
+        *body.lock().unwrap() = "papers v2";
+        assert_eq!(get_cached(&dir, &client, &url, Some(Duration::zero())).unwrap(), "papers v2");
+
+        let seen = seen.lock().unwrap();
+        let v1 = format!("\"{:x}\"", Md5::digest(b"papers v1"));
+        assert_eq!(*seen, vec![None, Some(v1.clone()), Some(v1)]);
+        assert_eq!(load(&dir, &url).unwrap().body, "papers v2");
+    }
+
+    #[test]
+    fn skips_the_cache_when_disabled() {
+        let (url, seen) = listing_server(Arc::new(Mutex::new("papers v1")));
+        let (dir, client) = (cache_dir("disabled"), Client::new());
+        get_cached(&dir, &client, &url, Some(Duration::minutes(60))).unwrap();
+        get_cached(&dir, &client, &url, None).unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![None, None]);
+    }
+
+    #[test]
+    fn ignores_entries_for_another_url() {
+        let dir = cache_dir("collision");
+        let entry = CachedResponse { url: "https://a".to_string(), etag: None, last_modified: None, fetched: Utc::now().to_rfc3339(), body: "a".to_string() };
+        store(&dir, &entry);
+        fs::rename(entry_path(&dir, "https://a"), entry_path(&dir, "https://b")).unwrap();
+        assert!(load(&dir, "https://b").is_none());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config::Config;
+use crate::openai;
+use once_cell::sync::Lazy;
+use reqwest::blocking::Client;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    Embeddings,
+    SemanticScholar,
+    PapersWithCode,
+}
+
+const ALL: &[Capability] = &[Capability::Embeddings, Capability::SemanticScholar, Capability::PapersWithCode];
+
+static DISABLED: Lazy<Mutex<HashMap<Capability, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+impl Capability {
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Embeddings => "embedding provider",
+            Capability::SemanticScholar => "Semantic Scholar",
+            Capability::PapersWithCode => "Papers with Code",
+        }
+    }
+
+    fn features(self) -> &'static str {
+        match self {
+            Capability::Embeddings => "embedding-based relevance scoring",
+            Capability::SemanticScholar => "citation counts and citation-weighted relevance",
+            Capability::PapersWithCode => "code links, --require-code filtering and leaderboard context",
+        }
+    }
+
+    fn configured(self, config: &Config) -> bool {
+        match self {
+            Capability::Embeddings => true,
+            Capability::SemanticScholar => config.semantic_scholar.enabled,
+            Capability::PapersWithCode => {
+                let pwc = &config.papers_with_code;
+                pwc.enabled || pwc.require_code || pwc.leaderboard
+            }
+        }
+    }
+
+    fn probe(self, client: &Client) -> Result<(), String> {
+        let url = match self {
+            Capability::Embeddings => {
+                return openai::embed(client, &openai::require_api_key()?, &["ping".to_string()]).map(|_| ());
+            }
+            Capability::SemanticScholar => "https://api.semanticscholar.org/graph/v1/paper/arXiv:1706.03762?fields=title",
+            Capability::PapersWithCode => "https://paperswithcode.com/api/v1/papers/?arxiv_id=1706.03762",
+        };
+        client.get(url).send().and_then(|r| r.error_for_status()).map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+pub fn available(capability: Capability) -> bool {
+    !DISABLED.lock().unwrap().contains_key(&capability)
+}
+
+pub fn disable(capability: Capability, reason: &str) {
+    let mut disabled = DISABLED.lock().unwrap();
+    if disabled.contains_key(&capability) {
+        return;
+    }
+    println!("Notice: {} unavailable ({}), continuing without {}", capability.name(), reason, capability.features());
+    disabled.insert(capability, reason.to_string());
+}
+
+pub fn attempt<T>(capability: Capability, work: impl FnOnce() -> Result<T, String>) -> Option<T> {
+    if !available(capability) {
+        return None;
+    }
+    work().map_err(|e| disable(capability, &e)).ok()
+}
+
+pub fn reset() {
+    DISABLED.lock().unwrap().clear();
+}
+
+pub fn run() -> Result<(), String> {
+    let config = Config::load()?;
+    let client = crate::build_client()?;
+    for capability in ALL {
+        let status = if !capability.configured(&config) {
+            "not configured".to_string()
+        } else {
+            match capability.probe(&client) {
+                Ok(()) => "available".to_string(),
+                Err(e) => format!("unavailable: {}", e),
+            }
+        };
+        println!("{:<20} {:<62} {}", capability.name(), capability.features(), status);
+    }
+    Ok(())
+}
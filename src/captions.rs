@@ -0,0 +1,63 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static CAPTION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?i)(figure|fig\.|table)\s+([A-Z]?\d{1,3}(?:\.\d{1,2})?)\s*[:.|]\s*(\S.*)$").unwrap());
+
+const MAX_CAPTION_CHARS: usize = 400;
+const MAX_CAPTIONS: usize = 40;
+
+pub struct Caption {
+    pub label: String,
+    pub text: String,
+}
+
+fn label(kind: &str, number: &str) -> String {
+    let kind = if kind.eq_ignore_ascii_case("table") { "Table" } else { "Figure" };
+    format!("{} {}", kind, number)
+}
+
+pub fn extract(text: &str) -> Vec<Caption> {
+    let mut captions: Vec<Caption> = Vec::new();
+    let mut current: Option<Caption> = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(caps) = CAPTION_REGEX.captures(line) {
+            captions.extend(current.take());
+            let label = label(&caps[1], &caps[2]);
+            if !captions.iter().any(|c| c.label == label) {
+                current = Some(Caption { label, text: caps[3].to_string() });
+            }
+            continue;
+        }
+        let Some(caption) = current.as_mut() else {
+            continue;
+        };
+        if line.is_empty() || caption.text.chars().count() >= MAX_CAPTION_CHARS || caption.text.ends_with('.') {
+            captions.extend(current.take());
+        } else {
+            caption.text.push(' ');
+            caption.text.push_str(line);
+        }
+    }
+    captions.extend(current);
+    captions.truncate(MAX_CAPTIONS);
+    for caption in captions.iter_mut() {
+        caption.text = caption.text.chars().take(MAX_CAPTION_CHARS).collect();
+    }
+    captions
+}
+
+pub fn prompt_block(text: &str) -> String {
+    let captions = extract(text);
+    if captions.is_empty() {
+        return String::new();
+    }
+    let mut block = "\n        Figure and Table Captions:\n".to_string();
+    for caption in captions {
+        block.push_str(&format!("        - {}: {}\n", caption.label, caption.text));
+    }
+    block.push_str(
+        "\n        Also add a **Key Figures/Tables** section: for the most important figures and tables above, state what each one shows and the headline numbers it reports, referring to them by label (e.g., \"Table 2\").\n",
+    );
+    block
+}
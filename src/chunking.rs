@@ -0,0 +1,213 @@
+use crate::arxiv::Paper;
+use crate::cancel::CancellationToken;
+use crate::config::SummaryConfig;
+use crate::openai;
+use crate::depth::Depth;
+use crate::pipeline::{self, MAX_PROMPT_CHARS};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::blocking::Client;
+
+static NUMBERED_HEADING_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^((?:\d{1,2}|[A-H])(?:\.\d{1,2}){0,3})\.?\s+([A-Z][A-Za-z][^.]{1,80})$").unwrap());
+static NAMED_HEADING_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(abstract|introduction|related work|background|method|methods|methodology|experiments|results|discussion|conclusions?|limitations|references|appendix)$").unwrap()
+});
+
+const SECTION_PRIORITY: &[&str] = &[
+    "front matter",
+    "abstract",
+    "result",
+    "conclusion",
+    "discussion",
+    "experiment",
+    "evaluation",
+    "method",
+    "approach",
+    "introduction",
+    "limitation",
+];
+const LOW_VALUE_SECTIONS: &[&str] = &["references", "bibliography", "acknowledg", "appendix"];
+
+pub struct Section {
+    pub anchor: String,
+    pub heading: String,
+    pub text: String,
+}
+
+pub fn should_chunk(config: &SummaryConfig, text: &str) -> bool {
+    match config.chunked.as_str() {
+        "always" => true,
+        "never" => false,
+        _ => text.chars().count() > MAX_PROMPT_CHARS,
+    }
+}
+
+fn heading_of(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.split_whitespace().count() > 12 {
+        return None;
+    }
+    if let Some(caps) = NUMBERED_HEADING_REGEX.captures(line) {
+        return Some((format!("§{}", &caps[1]), caps[2].trim().to_string()));
+    }
+    if NAMED_HEADING_REGEX.is_match(line) {
+        return Some((format!("§{}", line), line.to_string()));
+    }
+    None
+}
+
+pub fn split_sections(text: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        anchor: "§0".to_string(),
+        heading: "Front matter".to_string(),
+        text: String::new(),
+    }];
+
+    for line in text.lines() {
+        if let Some((anchor, heading)) = heading_of(line) {
+            sections.push(Section { anchor, heading, text: String::new() });
+        }
+        let current = sections.last_mut().unwrap();
+        current.text.push_str(line);
+        current.text.push('\n');
+    }
+
+    sections.retain(|s| !s.text.trim().is_empty());
+    if sections.len() <= 1 {
+        return Vec::new();
+    }
+    sections
+}
+
+fn section_priority(heading: &str) -> usize {
+    let heading = heading.to_lowercase();
+    if LOW_VALUE_SECTIONS.iter().any(|k| heading.starts_with(k)) {
+        return usize::MAX;
+    }
+    SECTION_PRIORITY.iter().position(|k| heading.contains(k)).unwrap_or(SECTION_PRIORITY.len())
+}
+
+pub fn prompt_text(text: &str, budget: usize) -> String {
+    let sections = split_sections(text);
+    if sections.is_empty() {
+        return text.chars().take(budget).collect();
+    }
+
+    let mut order: Vec<usize> = (0..sections.len()).collect();
+    order.sort_by_key(|&i| section_priority(&sections[i].heading));
+    let mut prompt = String::new();
+    let mut remaining = budget;
+    for i in order {
+        let section = &sections[i];
+        if remaining == 0 || (section_priority(&section.heading) == usize::MAX && !prompt.is_empty()) {
+            break;
+        }
+        let block = format!("{}\n\n", section.text.trim());
+        let part: String = block.chars().take(remaining).collect();
+        remaining -= part.chars().count();
+        prompt.push_str(&part);
+    }
+    prompt
+}
+
+pub fn build_chunks(text: &str, chunk_chars: usize) -> (Vec<String>, Vec<(String, String)>) {
+    let sections = split_sections(text);
+    let mut chunks: Vec<String> = Vec::new();
+    let mut anchors: Vec<(String, String)> = Vec::new();
+
+    if sections.is_empty() {
+        let chars: Vec<char> = text.chars().collect();
+        for (i, part) in chars.chunks(chunk_chars.max(1)).enumerate() {
+            let anchor = format!("§c{}", i + 1);
+            chunks.push(format!("[{}]\n{}", anchor, part.iter().collect::<String>()));
+            anchors.push((anchor, format!("Text chunk {}", i + 1)));
+        }
+        return (chunks, anchors);
+    }
+
+    let mut current = String::new();
+    for section in sections {
+        anchors.push((section.anchor.clone(), section.heading.clone()));
+        let chars: Vec<char> = section.text.chars().collect();
+        for (i, part) in chars.chunks(chunk_chars.max(1)).enumerate() {
+            let marker = if i == 0 {
+                format!("[{} {}]", section.anchor, section.heading)
+            } else {
+                format!("[{} {} (cont.)]", section.anchor, section.heading)
+            };
+            let block = format!("{}\n{}\n", marker, part.iter().collect::<String>());
+            if !current.is_empty() && current.chars().count() + block.chars().count() > chunk_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            current.push_str(&block);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    (chunks, anchors)
+}
+
+pub fn summarize_chunked(
+    client: &Client,
+    api_key: &str,
+    config: &SummaryConfig,
+    depth: Depth,
+    paper: &Paper,
+    text: &str,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let (mut chunks, anchors) = build_chunks(text, config.chunk_chars);
+    if chunks.len() > config.max_chunks {
+        println!("  Paper has {} chunks, summarizing the first {}: {}", chunks.len(), config.max_chunks, paper.title);
+        chunks.truncate(config.max_chunks);
+    }
+
+    let mut notes = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        cancel.check()?;
+        let prompt = format!(
+            r#"You are reading part {} of {} of the academic paper "{}".
+        The text contains section markers in square brackets such as [§4.2 Results].
+
+        Extract the key claims, quantitative results, methods, datasets and limitations from this part as concise bullet points.
+        End every bullet with the anchor of the section it came from, e.g. [§4.2]. Use only anchors that appear in the text.
+        Do not hallucinate. Use only the text below.
+
+        Text:
+        {}"#,
+            i + 1,
+            chunks.len(),
+            paper.title,
+            chunk
+        );
+        let part_notes = openai::chat(client, api_key, &prompt, 1200)?;
+        notes.push_str(&format!("### Part {}\n{}\n\n", i + 1, part_notes));
+    }
+
+    cancel.check()?;
+    let prompt = format!(
+        r#"Please provide a comprehensive, evidence-based summary of the following academic paper based on the notes extracted from each part of it.
+        Title: {}
+        arXiv ID: {}
+        PDF URL: {}
+
+        Notes (each bullet ends with the section anchor it came from):
+        {}
+
+        {}
+        {}
+        **Citation anchors:** End every claim with the anchor(s) of the notes that support it, e.g. [§4.2] or [§3.1][§5]. Never invent anchors that are not in the notes.
+        **Constraint:** Do not hallucinate. Base the summary *strictly* on the provided notes."#,
+        paper.title, paper.id, paper.pdf_url, notes, depth.sections(), pipeline::caption_block(config, text)
+    );
+    let mut summary = openai::chat(client, api_key, &prompt, depth.max_tokens() + 500)?;
+
+    summary.push_str("\n\n## Source Anchors\n\n");
+    for (anchor, heading) in anchors {
+        summary.push_str(&format!("- [{}] {}\n", anchor, heading));
+    }
+    Ok(summary)
+}
@@ -0,0 +1,152 @@
+use crate::arxiv::{self, Paper};
+use crate::cli::Args;
+use crate::config::{Config, ScholarConfig};
+use crate::db::Db;
+use crate::pipeline::{self, RunOptions};
+use crate::scholar::ScholarInfo;
+use crate::sources;
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::fs;
+
+const SEARCH_URL: &str = "https://api.semanticscholar.org/graph/v1/paper/search";
+const FIELDS: &str = "title,year,externalIds,citationCount,influentialCitationCount,fieldsOfStudy";
+const PAGE_SIZE: usize = 100;
+const MAX_CANDIDATES: usize = 500;
+
+struct Classic {
+    paper: Paper,
+    year: u32,
+}
+
+fn search(client: &Client, config: &ScholarConfig, topic: &str, top: usize) -> Result<Vec<Classic>, String> {
+    let api_key = config.api_key();
+    let mut classics: Vec<Classic> = Vec::new();
+    let mut offset = 0;
+
+    while offset < MAX_CANDIDATES {
+        let mut request = client.get(SEARCH_URL).query(&[
+            ("query", topic.to_string()),
+            ("fields", FIELDS.to_string()),
+            ("offset", offset.to_string()),
+            ("limit", PAGE_SIZE.to_string()),
+        ]);
+        if !api_key.is_empty() {
+            request = request.header("x-api-key", &api_key);
+        }
+        let body: Value = request
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Semantic Scholar search failed: {}", e))?;
+        let results = body["data"].as_array().cloned().unwrap_or_default();
+
+        for result in &results {
+            let Some(id) = result["externalIds"]["ArXiv"].as_str() else {
+                continue;
+            };
+            if classics.iter().any(|c| c.paper.id == id) {
+                continue;
+            }
+            classics.push(Classic {
+                paper: Paper {
+                    id: id.to_string(),
+                    title: result["title"].as_str().unwrap_or(id).to_string(),
+                    pdf_url: format!("https://arxiv.org/pdf/{}.pdf", id),
+                    category: "cs.AI".to_string(),
+                    scholar: Some(ScholarInfo::from_json(result)),
+                    code: None,
+                    sources: vec!["classics".to_string()],
+                },
+                year: result["year"].as_u64().unwrap_or(0) as u32,
+            });
+        }
+
+        match body["next"].as_u64() {
+            Some(next) if !results.is_empty() => offset = next as usize,
+            _ => break,
+        }
+    }
+
+    classics.sort_by_key(|c| std::cmp::Reverse(citations(&c.paper)));
+    classics.truncate(top);
+    Ok(classics)
+}
+
+fn citations(paper: &Paper) -> u32 {
+    paper.scholar.as_ref().map_or(0, |s| s.citations)
+}
+
+fn slug(topic: &str) -> String {
+    topic
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn reading_path(topic: &str, classics: &[Classic], db: &Db) -> String {
+    let mut out = format!(
+        "# Reading path: {}\n\nThe {} most-cited papers on this topic according to Semantic Scholar, ordered from foundational to recent.\n\n",
+        topic,
+        classics.len()
+    );
+    for (i, classic) in classics.iter().enumerate() {
+        let paper = &classic.paper;
+        let summary = match db.papers.get(&paper.id) {
+            Some(record) if record.status == "summarized" => format!("[summary](../summary/{})", record.summary_file.replace(' ', "%20")),
+            _ => "not summarized".to_string(),
+        };
+        out.push_str(&format!(
+            "{}. **{}** ({}, {} citations) — {} · [abstract]({})\n",
+            i + 1,
+            paper.title,
+            if classic.year == 0 { "n.d.".to_string() } else { classic.year.to_string() },
+            citations(paper),
+            summary,
+            sources::abs_url(&paper.id)
+        ));
+    }
+    out
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let topic = args.value("topic").ok_or("Usage: ras classics --topic \"topic\" [--top 30]")?;
+    let top: usize = args.parsed("top", 30)?;
+    let config = Config::load()?;
+    let client = crate::build_client()?;
+
+    println!("Searching Semantic Scholar for the most-cited papers on \"{}\"...", topic);
+    let mut classics = search(&client, &config.semantic_scholar, topic, top)?;
+    if classics.is_empty() {
+        return Err(format!("No arXiv papers found on Semantic Scholar for \"{}\"", topic));
+    }
+
+    let ids: Vec<String> = classics.iter().map(|c| c.paper.id.clone()).collect();
+    let metadata = arxiv::fetch_metadata(&client, &ids);
+    for classic in classics.iter_mut() {
+        if let Some(meta) = metadata.get(&classic.paper.id) {
+            classic.paper.title = meta.title.clone();
+            classic.paper.category = meta.to_paper().category;
+        }
+    }
+    classics.sort_by_key(|c| (c.year, std::cmp::Reverse(citations(&c.paper))));
+
+    println!("Found {} classics, queueing them for processing", classics.len());
+    pipeline::execute(RunOptions::for_papers(classics.iter().map(|c| c.paper.clone()).collect())).map_err(|e| e.to_string())?;
+
+    let tag = format!("classics/{}", slug(topic));
+    let mut db = Db::load()?;
+    for classic in &classics {
+        db.add_tag(&classic.paper.id, &tag);
+    }
+    db.save()?;
+
+    let dir = crate::ras_dir()?.join("classics");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.md", slug(topic)));
+    fs::write(&path, reading_path(topic, &classics, &db)).map_err(|e| e.to_string())?;
+    println!("Reading path written to {} (tag: {})", path.display(), tag);
+    Ok(())
+}
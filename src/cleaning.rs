@@ -0,0 +1,211 @@
+use crate::config::CleaningConfig;
+use crate::extraction::PAGE_BREAK;
+use crate::references;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
+
+static HYPHEN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\S*\p{L})[-\u{00AD}\u{2010}][ \t]*\n[ \t]*(\p{Ll}\p{L}*)").unwrap());
+static PAGE_NUMBER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:[Pp]age\s+)?(\d{1,5}|[ivxlc]{1,4}|[IVXLC]{1,4})(?:\s*(?:/|of)\s*\d{1,5})?$").unwrap());
+static ROMAN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?i)(?:xc|xl|l?x{0,3})(?:ix|iv|v?i{0,3})$").unwrap());
+static WORD_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\p{L}+(?:-\p{L}+)*").unwrap());
+static DIGITS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+static SPACES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[ \t\u{00A0}\u{2009}\u{202F}]+").unwrap());
+static BLANK_LINES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n{3,}").unwrap());
+
+const EDGE_LINES: usize = 3;
+const MAX_HEADER_CHARS: usize = 120;
+const MIN_PAGES: usize = 3;
+
+const COMPOUND_PREFIXES: &[&str] = &[
+    "self", "non", "multi", "cross", "fine", "large", "small", "well", "state", "long", "short", "low", "high", "zero", "few", "end", "real", "open",
+    "semi", "half", "full", "top", "human", "task", "domain", "data",
+];
+
+const LIGATURES: &[(char, &str)] = &[('\u{FB00}', "ff"), ('\u{FB01}', "fi"), ('\u{FB02}', "fl"), ('\u{FB03}', "ffi"), ('\u{FB04}', "ffl")];
+
+pub fn clean(text: &str, config: &CleaningConfig) -> String {
+    if !config.enabled {
+        return text.replace(PAGE_BREAK, "\n");
+    }
+    let mut text = if config.headers { strip_page_furniture(text) } else { text.replace(PAGE_BREAK, "\n") };
+    if config.dehyphenate {
+        text = dehyphenate(&text);
+    }
+    if config.strip_references {
+        text = references::strip(&text);
+    }
+    normalize_whitespace(&text)
+}
+
+// A line-end hyphen is kept when the document shows the word is a compound ("self-attention",
+// "state-of-the-art") and dropped when it splits a single word ("summa-rization").
+fn dehyphenate(text: &str) -> String {
+    let unbroken = HYPHEN_REGEX.replace_all(text, " ");
+    let mut words: HashSet<String> = HashSet::new();
+    for word in WORD_REGEX.find_iter(&unbroken) {
+        let word = word.as_str().to_lowercase();
+        words.extend(word.split('-').map(str::to_string));
+        words.insert(word);
+    }
+    HYPHEN_REGEX
+        .replace_all(text, |caps: &Captures| {
+            let (left, right) = (&caps[1], &caps[2]);
+            let head = left.rsplit(|c: char| !c.is_alphanumeric()).next().unwrap_or(left).to_lowercase();
+            let right_lower = right.to_lowercase();
+            let keep = if left.contains('-') || words.contains(&format!("{}-{}", head, right_lower)) {
+                true
+            } else if words.contains(&format!("{}{}", head, right_lower)) {
+                false
+            } else {
+                COMPOUND_PREFIXES.contains(&head.as_str()) || (head.len() > 1 && words.contains(&head) && words.contains(&right_lower))
+            };
+            format!("{}{}{}", left, if keep { "-" } else { "" }, right)
+        })
+        .to_string()
+}
+
+fn is_page_number(line: &str) -> bool {
+    PAGE_NUMBER_REGEX.captures(line).is_some_and(|caps| caps[1].chars().all(|c| c.is_ascii_digit()) || ROMAN_REGEX.is_match(&caps[1]))
+}
+
+fn edge_key(line: &str) -> String {
+    DIGITS_REGEX.replace_all(&line.trim().to_lowercase(), "#").to_string()
+}
+
+fn edges(lines: &[&str]) -> Vec<usize> {
+    let content: Vec<usize> = (0..lines.len()).filter(|&i| !lines[i].trim().is_empty()).collect();
+    let top = content.iter().take(EDGE_LINES);
+    let bottom = content.iter().skip(EDGE_LINES).rev().take(EDGE_LINES);
+    top.chain(bottom).copied().collect()
+}
+
+fn strip_page_furniture(text: &str) -> String {
+    let pages: Vec<Vec<&str>> = text.split(PAGE_BREAK).filter(|p| !p.trim().is_empty()).map(|p| p.lines().collect()).collect();
+    if pages.len() < MIN_PAGES {
+        return text.replace(PAGE_BREAK, "\n");
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for lines in &pages {
+        let keys: HashSet<String> =
+            edges(lines).into_iter().map(|i| lines[i]).filter(|l| l.chars().count() <= MAX_HEADER_CHARS).map(edge_key).collect();
+        for key in keys {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+    let threshold = (pages.len() / 2).max(MIN_PAGES);
+    let repeated: HashSet<String> = counts.into_iter().filter(|(_, count)| *count >= threshold).map(|(key, _)| key).collect();
+
+    let mut out = String::new();
+    for lines in &pages {
+        let furniture: HashSet<usize> = edges(lines)
+            .into_iter()
+            .filter(|&i| is_page_number(lines[i].trim()) || repeated.contains(&edge_key(lines[i])))
+            .collect();
+        for (i, line) in lines.iter().enumerate() {
+            if !furniture.contains(&i) {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    let mut text = text.replace(['\r', PAGE_BREAK], "\n").replace(['\u{200B}', '\u{FEFF}'], "");
+    for (ligature, letters) in LIGATURES {
+        text = text.replace(*ligature, letters);
+    }
+    let lines: Vec<String> = text.lines().map(|line| SPACES_REGEX.replace_all(line, " ").trim().to_string()).collect();
+    BLANK_LINES_REGEX.replace_all(&lines.join("\n"), "\n\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancel::CancellationToken;
+    use crate::extraction::{Backend, PdfExtract};
+    use std::path::Path;
+
+    fn fixture(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        PdfExtract.extract(&path, &CancellationToken::new()).unwrap()
+    }
+
+    fn enabled() -> CleaningConfig {
+        CleaningConfig { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn leaves_text_alone_by_default() {
+        let raw = fixture("hyphenation.pdf");
+        assert_eq!(clean(&raw, &CleaningConfig::default()), raw.replace(PAGE_BREAK, "\n"));
+    }
+
+    fn lines(text: &str) -> Vec<&str> {
+        text.lines().collect()
+    }
+
+    #[test]
+    fn joins_words_split_across_lines() {
+        let text = clean(&fixture("hyphenation.pdf"), &enabled());
+        assert!(text.contains("hard to summarize because"), "{}", text);
+        assert!(text.contains("prior summarization systems"), "{}", text);
+    }
+
+    #[test]
+    fn keeps_hyphens_of_compounds_split_across_lines() {
+        let text = clean(&fixture("hyphenation.pdf"), &enabled());
+        assert!(text.contains("full self-attention with"), "{}", text);
+        assert!(text.contains("state-of-the-art ROUGE"), "{}", text);
+        assert!(text.contains("our in-context examples"), "{}", text);
+    }
+
+    #[test]
+    fn removes_running_headers_and_footers() {
+        let text = clean(&fixture("running-headers.pdf"), &enabled());
+        assert!(!text.contains("Preprint. Under review."), "{}", text);
+        assert!(!text.contains("Sparse Attention Windows (2024)"), "{}", text);
+        assert!(text.contains("Transformers dominate abstractive summarization."));
+        assert!(text.contains("Code will be released."));
+    }
+
+    #[test]
+    fn removes_page_numbers_but_not_words() {
+        let text = clean(&fixture("running-headers.pdf"), &enabled());
+        let lines = lines(&text);
+        for number in ["1", "2", "Page 3 of 4", "iv"] {
+            assert!(!lines.contains(&number), "{} left in {}", number, text);
+        }
+        for word in ["civil", "ill", "civic", "mix"] {
+            assert!(lines.contains(&word), "{} removed from {}", word, text);
+        }
+    }
+
+    #[test]
+    fn recognizes_page_numbers() {
+        for line in ["7", "12 / 30", "Page 3 of 10", "xii", "XIV", "ix"] {
+            assert!(is_page_number(line), "{}", line);
+        }
+        for line in ["civil", "civic", "ill", "mix", "Xii", "iiii", "2024 results"] {
+            assert!(!is_page_number(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn strips_references_only_when_enabled() {
+        let raw = fixture("references.pdf");
+        let kept = clean(&raw, &enabled());
+        assert!(kept.contains("Longformer"));
+
+        let stripped = clean(&raw, &CleaningConfig { strip_references: true, ..enabled() });
+        assert!(!stripped.contains("Longformer"), "{}", stripped);
+        assert!(!stripped.contains("Big Bird"), "{}", stripped);
+        assert!(stripped.contains("Sparse windows make long inputs tractable."));
+        assert!(stripped.contains("Hyperparameters are listed in Table 5."), "{}", stripped);
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::{HashMap, HashSet};
+
+const SWITCHES: &[&str] = &["with-pdf", "append", "combine", "now", "require-code", "refine", "week", "json", "update", "dry-run", "dashboard", "file", "no-cache", "offline", "extract-only", "unread", "starred", "llm", "force", "extract", "primary-only"];
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub command: Option<String>,
+    pub positional: Vec<String>,
+    options: HashMap<String, String>,
+    switches: HashSet<String>,
+}
+
+impl Args {
+    pub fn parse() -> Args {
+        Args::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from<I: IntoIterator<Item = String>>(iter: I) -> Args {
+        let mut args = Args::default();
+        let mut iter = iter.into_iter().peekable();
+
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if let Some((key, value)) = name.split_once('=') {
+                    args.options.insert(key.to_string(), value.to_string());
+                } else if SWITCHES.contains(&name) {
+                    args.switches.insert(name.to_string());
+                } else if let Some(value) = iter.next_if(|v| !v.starts_with("--")) {
+                    args.options.insert(name.to_string(), value);
+                } else {
+                    args.switches.insert(name.to_string());
+                }
+            } else if args.command.is_none() {
+                args.command = Some(arg);
+            } else {
+                args.positional.push(arg);
+            }
+        }
+
+        args
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|s| s.as_str())
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.switches.contains(name)
+    }
+
+    pub fn parsed<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.value(name) {
+            Some(v) => v.parse().map_err(|_| format!("Invalid value for --{}: {}", name, v)),
+            None => Ok(default),
+        }
+    }
+}
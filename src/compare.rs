@@ -0,0 +1,106 @@
+use crate::chunking;
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::openai;
+use crate::pdf::extract_text_from_pdf;
+use crate::pipeline::{MAX_PROMPT_CHARS, sanitize_filename};
+use crate::summary::strip_frontmatter;
+use std::fs;
+use std::path::PathBuf;
+
+fn comparisons_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("comparisons"))
+}
+
+fn find(db: &Db, query: &str) -> Result<PaperRecord, String> {
+    if let Some(record) = db.papers.get(query) {
+        return Ok(record.clone());
+    }
+    match db.find(query).as_slice() {
+        [] => Err(format!("No paper found for: {}", query)),
+        [record] => Ok((*record).clone()),
+        matches => Err(format!(
+            "Multiple papers match \"{}\": {}",
+            query,
+            matches.iter().map(|r| r.id.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+fn source(record: &PaperRecord, share: usize) -> Result<String, RasError> {
+    let ras_dir = crate::ras_dir()?;
+    let summary = fs::read_to_string(ras_dir.join("summary").join(&record.summary_file)).unwrap_or_default();
+    let summary = strip_frontmatter(&summary);
+    let summary = summary.split_once("\n---\n").map(|(_, b)| b).unwrap_or(summary).trim();
+    let summary: String = summary.chars().take(share / 3).collect();
+
+    let pdf_path = ras_dir.join("papers").join(&record.pdf_file);
+    let text = if record.pdf_file.is_empty() || !pdf_path.exists() {
+        String::new()
+    } else {
+        match extract_text_from_pdf(&pdf_path) {
+            Ok(text) => chunking::prompt_text(&text, share.saturating_sub(summary.chars().count())),
+            Err(e) => {
+                println!("  Failed to read {}: {}", record.pdf_file, e);
+                String::new()
+            }
+        }
+    };
+    Ok(format!("Summary:\n{}\n\nPaper text:\n{}", summary, if text.is_empty() { "(not available)" } else { &text }))
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    if args.positional.len() < 2 {
+        return Err("Usage: compare <arxiv-id or title> <arxiv-id or title> [...]".to_string());
+    }
+    let db = Db::load()?;
+    let mut records: Vec<PaperRecord> = args.positional.iter().map(|q| find(&db, q)).collect::<Result<_, _>>()?;
+    records.dedup_by(|a, b| a.id == b.id);
+    if records.len() < 2 {
+        return Err("Pick at least two different papers to compare".to_string());
+    }
+    if let Some(record) = records.iter().find(|r| r.status != "summarized") {
+        return Err(format!("{} has not been summarized yet", record.title));
+    }
+
+    let share = MAX_PROMPT_CHARS / records.len();
+    let mut papers = String::new();
+    for (i, record) in records.iter().enumerate() {
+        papers.push_str(&format!("=== Paper {}: {} (arXiv {}) ===\n{}\n\n", i + 1, record.title, record.id, source(record, share)?));
+    }
+
+    println!("Comparing {} papers...", records.len());
+    let prompt = format!(
+        r#"Compare the following {} academic papers, which work on related problems. Refer to them as Paper 1, Paper 2 and so on, and name them by title in the first mention.
+
+        Structure the comparison with these sections:
+        1. **Shared Problem Framing**: The problem the papers have in common and how each one frames it.
+        2. **Methodological Differences**: How the approaches, data and evaluation setups differ.
+        3. **Head-to-Head Results**: A Markdown table of results on benchmarks the papers share, with exact numbers, followed by caveats about comparability. Say so explicitly if they share no benchmark.
+        4. **Conflicting Claims**: Claims in one paper that contradict or weaken claims in another.
+        5. **Verdict**: Which paper to read first for which purpose.
+
+        **Constraint:** Do not hallucinate. Base the comparison *strictly* on the provided text.
+
+        {}"#,
+        records.len(),
+        papers
+    );
+    let api_key = openai::chat_key()?;
+    let client = crate::build_client()?;
+    let comparison = openai::chat(&client, &api_key, &prompt, 3000)?;
+
+    let mut content = "# Comparison\n\n".to_string();
+    for (i, record) in records.iter().enumerate() {
+        content.push_str(&format!("{}. [{}](../summary/{}) (arXiv {})\n", i + 1, record.title, record.summary_file.replace(' ', "%20"), record.id));
+    }
+    content.push_str(&format!("\n---\n\n{}\n", comparison.trim()));
+
+    fs::create_dir_all(comparisons_dir()?).map_err(|e| e.to_string())?;
+    let name = records.iter().map(|r| sanitize_filename(&r.id)).collect::<Vec<_>>().join("_vs_");
+    let path = comparisons_dir()?.join(format!("{}.md", name));
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    println!("Comparison saved: {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,810 @@
+use crate::error::RasError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+static UNPRICED_WARNED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    pub relevance: RelevanceConfig,
+    pub email: EmailConfig,
+    pub summary: SummaryConfig,
+    pub notifications: NotificationsConfig,
+    pub telegram: TelegramConfig,
+    pub ideas: IdeasConfig,
+    pub glossary: GlossaryConfig,
+    pub benchmarks: BenchmarksConfig,
+    pub entities: EntitiesConfig,
+    pub dedup: DedupConfig,
+    pub watchlist: WatchlistConfig,
+    pub tagging: TaggingConfig,
+    pub zotero: ZoteroConfig,
+    pub readwise: ReadwiseConfig,
+    pub survey: SurveyConfig,
+    pub semantic_scholar: ScholarConfig,
+    pub delivery: DeliveryConfig,
+    pub papers_with_code: PapersWithCodeConfig,
+    pub sources: SourcesConfig,
+    pub pipeline: PipelineConfig,
+    pub extraction: ExtractionConfig,
+    pub cleaning: CleaningConfig,
+    pub versions: VersionsConfig,
+    pub files: FilesConfig,
+    pub storage: StorageConfig,
+    pub llm: LlmConfig,
+    pub network: NetworkConfig,
+    pub cache: CacheConfig,
+    pub obsidian: ObsidianConfig,
+    pub git: GitConfig,
+    pub sync: SyncConfig,
+    pub telemetry: TelemetryConfig,
+    pub language: LanguageConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct RelevanceConfig {
+    pub enabled: bool,
+    pub min_score: f32,
+    pub min_feedback: usize,
+    pub retrain_hours: i64,
+    pub skip_after_days: i64,
+    pub citation_weight: f32,
+    pub watchlist_weight: f32,
+    pub prioritize: bool,
+}
+
+impl Default for RelevanceConfig {
+    fn default() -> Self {
+        RelevanceConfig {
+            enabled: false,
+            min_score: 0.0,
+            min_feedback: 10,
+            retrain_hours: 24,
+            skip_after_days: 7,
+            citation_weight: 0.05,
+            watchlist_weight: 1.0,
+            prioritize: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    pub progressive: bool,
+    pub chunked: String,
+    pub chunk_chars: usize,
+    pub max_chunks: usize,
+    pub combine_related: bool,
+    pub html_fallback: bool,
+    pub prioritize_sections: bool,
+    pub captions: bool,
+    pub verify_numbers: bool,
+    pub refine: bool,
+    pub languages: Vec<String>,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        SummaryConfig {
+            progressive: false,
+            chunked: "never".to_string(),
+            chunk_chars: 24000,
+            max_chunks: 12,
+            combine_related: false,
+            html_fallback: false,
+            prioritize_sections: false,
+            captions: false,
+            verify_numbers: false,
+            refine: false,
+            languages: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SurveyConfig {
+    pub enabled: bool,
+    pub min_chars: usize,
+    pub min_references: usize,
+    pub max_topics: usize,
+}
+
+impl Default for SurveyConfig {
+    fn default() -> Self {
+        SurveyConfig {
+            enabled: false,
+            min_chars: 60000,
+            min_references: 80,
+            max_topics: 10,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub starttls: bool,
+    pub username: String,
+    pub password: String,
+    pub password_env: String,
+    pub from: String,
+    pub to: Vec<String>,
+    pub skip_if_empty: bool,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        EmailConfig {
+            enabled: false,
+            smtp_host: String::new(),
+            smtp_port: 587,
+            starttls: true,
+            username: String::new(),
+            password: String::new(),
+            password_env: "RAS_SMTP_PASSWORD".to_string(),
+            from: String::new(),
+            to: Vec::new(),
+            skip_if_empty: true,
+        }
+    }
+}
+
+impl EmailConfig {
+    pub fn password(&self) -> String {
+        crate::credentials::get(&self.password_env).unwrap_or_else(|| self.password.clone())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub slack_webhook: String,
+    pub discord_webhook: String,
+    pub top_n: usize,
+    pub notify_empty: bool,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        NotificationsConfig {
+            slack_webhook: String::new(),
+            discord_webhook: String::new(),
+            top_n: 5,
+            notify_empty: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+    pub mode: String,
+    pub include_full: bool,
+}
+
+impl Default for TelegramConfig {
+    fn default() -> Self {
+        TelegramConfig {
+            bot_token: String::new(),
+            chat_id: String::new(),
+            mode: "each".to_string(),
+            include_full: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct IdeasConfig {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct GlossaryConfig {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct BenchmarksConfig {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct EntitiesConfig {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct DedupConfig {
+    pub enabled: bool,
+    pub similarity: f32,
+    pub min_overlap: f32,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig { enabled: false, similarity: 0.92, min_overlap: 0.5 }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct TaggingConfig {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct WatchlistConfig {
+    pub authors: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ZoteroConfig {
+    pub library_type: String,
+    pub library_id: String,
+    pub api_key: String,
+    pub api_key_env: String,
+    pub collection: String,
+    pub attach_pdf: bool,
+}
+
+impl Default for ZoteroConfig {
+    fn default() -> Self {
+        ZoteroConfig {
+            library_type: "user".to_string(),
+            library_id: String::new(),
+            api_key: String::new(),
+            api_key_env: "ZOTERO_API_KEY".to_string(),
+            collection: String::new(),
+            attach_pdf: true,
+        }
+    }
+}
+
+impl ZoteroConfig {
+    pub fn api_key(&self) -> String {
+        crate::credentials::get(&self.api_key_env).unwrap_or_else(|| self.api_key.clone())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ReadwiseConfig {
+    pub api_key: String,
+    pub api_key_env: String,
+    pub location: String,
+    pub tags: Vec<String>,
+    pub document: String,
+}
+
+impl Default for ReadwiseConfig {
+    fn default() -> Self {
+        ReadwiseConfig {
+            api_key: String::new(),
+            api_key_env: "READWISE_TOKEN".to_string(),
+            location: "later".to_string(),
+            tags: vec!["ras".to_string()],
+            document: "summary".to_string(),
+        }
+    }
+}
+
+impl ReadwiseConfig {
+    pub fn api_key(&self) -> String {
+        crate::credentials::get(&self.api_key_env).unwrap_or_else(|| self.api_key.clone())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ScholarConfig {
+    pub enabled: bool,
+    pub api_key: String,
+    pub api_key_env: String,
+}
+
+impl Default for ScholarConfig {
+    fn default() -> Self {
+        ScholarConfig {
+            enabled: false,
+            api_key: String::new(),
+            api_key_env: "S2_API_KEY".to_string(),
+        }
+    }
+}
+
+impl ScholarConfig {
+    pub fn api_key(&self) -> String {
+        crate::credentials::get(&self.api_key_env).unwrap_or_else(|| self.api_key.clone())
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct DeliveryConfig {
+    pub quiet_hours: String,
+    pub email: ChannelWindow,
+    pub slack: ChannelWindow,
+    pub discord: ChannelWindow,
+    pub telegram: ChannelWindow,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ChannelWindow {
+    pub schedule: String,
+    pub quiet_hours: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct PapersWithCodeConfig {
+    pub enabled: bool,
+    pub require_code: bool,
+    pub leaderboard: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SourcesConfig {
+    pub enabled: Vec<String>,
+    pub limit: usize,
+    pub since_last_run: bool,
+    pub arxiv: ArxivSourceConfig,
+    pub arxiv_api: ArxivApiSourceConfig,
+    pub id_file: IdFileSourceConfig,
+    pub biorxiv: PreprintSourceConfig,
+    pub medrxiv: PreprintSourceConfig,
+    pub openreview: OpenReviewSourceConfig,
+}
+
+impl Default for SourcesConfig {
+    fn default() -> Self {
+        SourcesConfig {
+            enabled: vec!["arxiv".to_string()],
+            limit: 100,
+            since_last_run: false,
+            arxiv: ArxivSourceConfig::default(),
+            arxiv_api: ArxivApiSourceConfig::default(),
+            id_file: IdFileSourceConfig::default(),
+            biorxiv: PreprintSourceConfig::default(),
+            medrxiv: PreprintSourceConfig::default(),
+            openreview: OpenReviewSourceConfig::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ArxivSourceConfig {
+    pub categories: Vec<String>,
+    pub request_delay_secs: f64,
+    pub mirrors: Vec<String>,
+    pub primary_only: bool,
+    pub max_per_category: BTreeMap<String, usize>,
+}
+
+impl Default for ArxivSourceConfig {
+    fn default() -> Self {
+        ArxivSourceConfig {
+            categories: vec!["cs.AI".to_string()],
+            request_delay_secs: 3.0,
+            mirrors: vec!["https://arxiv.org".to_string(), "https://export.arxiv.org".to_string()],
+            primary_only: false,
+            max_per_category: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ArxivApiSourceConfig {
+    pub query: String,
+}
+
+impl Default for ArxivApiSourceConfig {
+    fn default() -> Self {
+        ArxivApiSourceConfig {
+            query: "cat:cs.AI".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct IdFileSourceConfig {
+    pub path: String,
+}
+
+impl Default for IdFileSourceConfig {
+    fn default() -> Self {
+        IdFileSourceConfig {
+            path: "~/ras/ids.txt".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PreprintSourceConfig {
+    pub days: i64,
+    pub categories: Vec<String>,
+}
+
+impl Default for PreprintSourceConfig {
+    fn default() -> Self {
+        PreprintSourceConfig {
+            days: 1,
+            categories: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct OpenReviewSourceConfig {
+    pub venue: String,
+    pub invitation: String,
+    pub reviews: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    pub extract_threads: usize,
+    pub summarize_threads: usize,
+    pub queue_memory_mb: usize,
+    pub downloads: usize,
+    pub extractions: usize,
+    pub llm_calls: usize,
+    pub max_attempts: u32,
+    pub dashboard: bool,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        PipelineConfig {
+            extract_threads: 6,
+            summarize_threads: 10,
+            queue_memory_mb: 64,
+            downloads: 4,
+            extractions: 2,
+            llm_calls: 10,
+            max_attempts: 3,
+            dashboard: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ExtractionConfig {
+    pub backends: Vec<String>,
+    pub max_replacement_ratio: f64,
+    pub min_words_per_page: usize,
+    pub ocr: bool,
+    pub ocr_language: String,
+    pub ocr_max_pages: usize,
+}
+
+impl Default for ExtractionConfig {
+    fn default() -> Self {
+        ExtractionConfig {
+            backends: vec!["lopdf".to_string(), "pdf-extract".to_string(), "pdftotext".to_string()],
+            max_replacement_ratio: 0.05,
+            min_words_per_page: 50,
+            ocr: false,
+            ocr_language: "eng".to_string(),
+            ocr_max_pages: 30,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct CleaningConfig {
+    pub enabled: bool,
+    pub dehyphenate: bool,
+    pub headers: bool,
+    pub strip_references: bool,
+}
+
+impl Default for CleaningConfig {
+    fn default() -> Self {
+        CleaningConfig { enabled: false, dehyphenate: true, headers: true, strip_references: false }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct VersionsConfig {
+    pub resummarize: bool,
+    pub max_age_days: i64,
+}
+
+impl Default for VersionsConfig {
+    fn default() -> Self {
+        VersionsConfig {
+            resummarize: false,
+            max_age_days: 90,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct FilesConfig {
+    pub pattern: String,
+    pub layout: String,
+    pub format: String,
+    pub slug: SlugConfig,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        FilesConfig {
+            pattern: "{id}-{title}".to_string(),
+            layout: String::new(),
+            format: "markdown".to_string(),
+            slug: SlugConfig::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SlugConfig {
+    pub lowercase: bool,
+    pub separator: String,
+    pub max_length: usize,
+}
+
+impl Default for SlugConfig {
+    fn default() -> Self {
+        SlugConfig { lowercase: true, separator: "-".to_string(), max_length: 60 }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub keep_pdfs: String,
+    pub delete_pdf_after_summary: bool,
+    pub min_free_mb: u64,
+    pub abort_below_min_free: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            keep_pdfs: String::new(),
+            delete_pdf_after_summary: false,
+            min_free_mb: 1024,
+            abort_below_min_free: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ProviderConfig {
+    pub provider: String,
+    pub model: String,
+    pub url: String,
+    pub api_key_env: String,
+    pub max_cost: f64,
+    pub input_price: Option<f64>,
+    pub output_price: Option<f64>,
+}
+
+impl ProviderConfig {
+    pub fn prices(&self) -> Option<(f64, f64)> {
+        match (self.input_price, self.output_price) {
+            (Some(input), Some(output)) => Some((input, output)),
+            _ if self.provider == "ollama" => Some((0.0, 0.0)),
+            _ if self.provider == "openai" && self.model == crate::openai::CHAT_MODEL => Some((0.15, 0.60)),
+            _ => None,
+        }
+    }
+
+    pub fn warn_if_unpriced(&self) {
+        if self.prices().is_some() {
+            return;
+        }
+        let key = format!("{} {}", self.provider, self.model);
+        if UNPRICED_WARNED.lock().unwrap().get_or_insert_with(HashSet::new).insert(key.clone()) {
+            println!("No prices configured for {}, its cost is not counted and max_cost doesn't apply to it", key);
+        }
+    }
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        ProviderConfig {
+            provider: "openai".to_string(),
+            model: crate::openai::CHAT_MODEL.to_string(),
+            url: String::new(),
+            api_key_env: String::new(),
+            max_cost: 0.0,
+            input_price: None,
+            output_price: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct LlmConfig {
+    pub providers: Vec<ProviderConfig>,
+    pub max_failures: u32,
+    pub stream: bool,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        LlmConfig {
+            providers: Vec::new(),
+            max_failures: 3,
+            stream: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub http_proxy: String,
+    pub https_proxy: String,
+    pub no_proxy: String,
+    pub ca_certs: Vec<String>,
+    pub connect_timeout: u64,
+    pub timeout: u64,
+    pub llm_connect_timeout: u64,
+    pub llm_timeout: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            http_proxy: String::new(),
+            https_proxy: String::new(),
+            no_proxy: String::new(),
+            ca_certs: Vec::new(),
+            connect_timeout: 10,
+            timeout: 120,
+            llm_connect_timeout: 10,
+            llm_timeout: 120,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub listing_ttl_minutes: i64,
+    pub llm: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig { listing_ttl_minutes: 60, llm: true }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ObsidianConfig {
+    pub vault: String,
+    pub folder: String,
+    pub similar: usize,
+}
+
+impl Default for ObsidianConfig {
+    fn default() -> Self {
+        ObsidianConfig { vault: String::new(), folder: "ras".to_string(), similar: 3 }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    pub enabled: bool,
+    pub push: bool,
+    pub remote: String,
+    pub branch: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig { enabled: false, push: false, remote: "origin".to_string(), branch: String::new() }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub target: String,
+    pub pdfs: bool,
+    pub endpoint: String,
+    pub region: String,
+    pub access_key_env: String,
+    pub secret_key_env: String,
+    pub username: String,
+    pub password_env: String,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig {
+            target: String::new(),
+            pdfs: false,
+            endpoint: String::new(),
+            region: "us-east-1".to_string(),
+            access_key_env: "AWS_ACCESS_KEY_ID".to_string(),
+            secret_key_env: "AWS_SECRET_ACCESS_KEY".to_string(),
+            username: String::new(),
+            password_env: "RAS_WEBDAV_PASSWORD".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub endpoint: String,
+    pub service_name: String,
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        TelemetryConfig { endpoint: String::new(), service_name: "ras".to_string(), headers: BTreeMap::new() }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct LanguageConfig {
+    pub policy: String,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        LanguageConfig { policy: "instruct".to_string() }
+    }
+}
+
+pub fn config_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("config.toml"))
+}
+
+impl Config {
+    pub fn load() -> Result<Config, String> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let config: Config = toml::from_str(&content).map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+        config.llm.providers.iter().for_each(ProviderConfig::warn_if_unpriced);
+        Ok(config)
+    }
+}
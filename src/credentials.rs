@@ -0,0 +1,225 @@
+use crate::cli::Args;
+use inquire::Password;
+use keyring::Entry;
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SERVICE: &str = "ras";
+
+const PROVIDERS: &[(&str, &str)] = &[
+    ("openai", "OPEN_AI_API_KEY"),
+    ("anthropic", "ANTHROPIC_API_KEY"),
+    ("zotero", "ZOTERO_API_KEY"),
+    ("readwise", "READWISE_TOKEN"),
+    ("semantic-scholar", "S2_API_KEY"),
+    ("smtp", "RAS_SMTP_PASSWORD"),
+    ("worker", "RAS_WORKER_TOKEN"),
+    ("webdav", "RAS_WEBDAV_PASSWORD"),
+];
+
+#[derive(Clone)]
+struct Credential {
+    value: String,
+    source: String,
+}
+
+static CACHE: Lazy<Mutex<HashMap<String, Option<Credential>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn credentials_path() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config").join("ras").join("credentials")
+}
+
+fn read_file(path: &Path) -> BTreeMap<String, String> {
+    dotenvy::from_path_iter(path).map(|iter| iter.flatten().collect()).unwrap_or_default()
+}
+
+fn env_files() -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(".env")];
+    files.extend(crate::ras_dir().ok().map(|dir| dir.join(".env")));
+    files
+}
+
+#[cfg(unix)]
+fn warn_if_shared(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Once;
+    static PERMISSIONS_WARNING: Once = Once::new();
+    if fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o077 != 0) {
+        PERMISSIONS_WARNING.call_once(|| println!("Warning: {} is readable by other users, run `chmod 600 {}`", path.display(), path.display()));
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_shared(_path: &Path) {}
+
+fn from_credentials_file(name: &str) -> Option<String> {
+    let path = credentials_path();
+    if !path.exists() {
+        return None;
+    }
+    warn_if_shared(&path);
+    read_file(&path).remove(name)
+}
+
+fn from_keyring(name: &str) -> Option<String> {
+    Entry::new(SERVICE, name).and_then(|entry| entry.get_password()).ok()
+}
+
+fn lookup(name: &str) -> Option<Credential> {
+    let found = |value: String, source: &str| Some(Credential { value, source: source.to_string() });
+    if let Ok(value) = std::env::var(name)
+        && !value.is_empty()
+    {
+        return found(value, "environment");
+    }
+    for path in env_files() {
+        if let Some(value) = read_file(&path).remove(name) {
+            return found(value, &path.display().to_string());
+        }
+    }
+    if let Some(value) = from_credentials_file(name) {
+        return found(value, &credentials_path().display().to_string());
+    }
+    from_keyring(name).and_then(|value| found(value, "OS keyring"))
+}
+
+fn cached(name: &str) -> Option<Credential> {
+    CACHE.lock().unwrap().entry(name.to_string()).or_insert_with(|| lookup(name)).clone()
+}
+
+pub fn get(name: &str) -> Option<String> {
+    cached(name).map(|c| c.value).filter(|value| !value.is_empty())
+}
+
+fn variable(provider: &str) -> Result<String, String> {
+    if let Some((_, name)) = PROVIDERS.iter().find(|(p, _)| *p == provider) {
+        return Ok(name.to_string());
+    }
+    if !provider.is_empty() && provider.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+        return Ok(provider.to_string());
+    }
+    let known: Vec<&str> = PROVIDERS.iter().map(|(p, _)| *p).collect();
+    Err(format!("Unknown provider: {} (expected one of {} or a variable name like MY_API_KEY)", provider, known.join(", ")))
+}
+
+fn read_secret(name: &str) -> Result<String, String> {
+    let secret = if io::stdin().is_terminal() {
+        Password::new(&format!("{}:", name)).without_confirmation().prompt().map_err(|e| e.to_string())?
+    } else {
+        let mut secret = String::new();
+        io::stdin().read_to_string(&mut secret).map_err(|e| e.to_string())?;
+        secret
+    };
+    let secret = secret.trim().to_string();
+    if secret.is_empty() {
+        return Err("No value given".to_string());
+    }
+    Ok(secret)
+}
+
+fn write_credentials_file(entries: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = credentials_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content: String = entries.iter().map(|(name, value)| format!("{}=\"{}\"\n", name, value.replace('\\', "\\\\").replace('"', "\\\""))).collect();
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&path).map_err(|e| e.to_string())?;
+    file.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn store_in_keyring(name: &str, secret: &str) -> Result<(), String> {
+    let entry = Entry::new(SERVICE, name).map_err(|e| e.to_string())?;
+    entry.set_password(secret).map_err(|e| e.to_string())?;
+    match entry.get_password() {
+        Ok(stored) if stored == secret => Ok(()),
+        Ok(_) => Err("the keyring returned a different value".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn set(name: &str, use_file: bool) -> Result<(), String> {
+    let secret = read_secret(name)?;
+    if !use_file {
+        match store_in_keyring(name, &secret) {
+            Ok(()) => {
+                println!("Stored {} in the OS keyring", name);
+                return Ok(());
+            }
+            Err(e) => println!("OS keyring unavailable ({}), using the credentials file", e),
+        }
+    }
+    let mut entries = read_file(&credentials_path());
+    entries.insert(name.to_string(), secret);
+    write_credentials_file(&entries)?;
+    println!("Stored {} in {}", name, credentials_path().display());
+    Ok(())
+}
+
+fn remove(name: &str) -> Result<(), String> {
+    let mut removed = false;
+    if let Ok(entry) = Entry::new(SERVICE, name)
+        && entry.delete_credential().is_ok()
+    {
+        println!("Removed {} from the OS keyring", name);
+        removed = true;
+    }
+    let mut entries = read_file(&credentials_path());
+    if entries.remove(name).is_some() {
+        write_credentials_file(&entries)?;
+        println!("Removed {} from {}", name, credentials_path().display());
+        removed = true;
+    }
+    if !removed {
+        println!("No stored value for {}", name);
+    }
+    Ok(())
+}
+
+fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    format!("{}...{}", chars[..4].iter().collect::<String>(), chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+fn status() {
+    for (provider, name) in PROVIDERS {
+        match cached(name) {
+            Some(credential) => println!("  {:<18} {:<20} {}  ({})", provider, name, mask(&credential.value), credential.source),
+            None => println!("  {:<18} {:<20} not set", provider, name),
+        }
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    match args.positional.first().map(String::as_str) {
+        Some("set") => {
+            let provider = args.positional.get(1).ok_or("Usage: ras auth set <provider> [--file]")?;
+            set(&variable(provider)?, args.flag("file"))
+        }
+        Some("remove") => {
+            let provider = args.positional.get(1).ok_or("Usage: ras auth remove <provider>")?;
+            remove(&variable(provider)?)
+        }
+        None | Some("status") => {
+            status();
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown auth command: {} (expected set, remove or status)", other)),
+    }
+}
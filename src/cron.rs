@@ -0,0 +1,166 @@
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Timelike};
+
+pub struct Schedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl Schedule {
+    pub fn parse(expr: &str) -> Result<Schedule, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Cron expression must have 5 fields, got {}: {}", fields.len(), expr));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+
+        Ok(Schedule {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            dom_restricted: !fields[2].starts_with('*'),
+            dow_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut t = start.naive_local();
+        let limit = t + Duration::days(366 * 5);
+
+        while t < limit {
+            if !self.months[t.month() as usize] || !self.day_matches(t.day(), t.weekday().num_days_from_sunday()) {
+                t = (t.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours[t.hour() as usize] {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes[t.minute() as usize] {
+                t += Duration::minutes(1);
+                continue;
+            }
+            if let Some(local) = Local.from_local_datetime(&t).earliest() {
+                return Some(local);
+            }
+            t += Duration::minutes(1);
+        }
+
+        None
+    }
+
+    fn day_matches(&self, day: u32, weekday: u32) -> bool {
+        let dom = self.days_of_month[day as usize];
+        let dow = self.days_of_week[weekday as usize];
+        // Like cron, either day field may match only when neither starts with '*'
+        if self.dom_restricted && self.dow_restricted { dom || dow } else { dom && dow }
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut allowed = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("Invalid cron step: {}", part))?;
+                if step == 0 {
+                    return Err(format!("Invalid cron step: {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, max)?, parse_value(b, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            if part.contains('/') { (value, max) } else { (value, value) }
+        };
+
+        if start > end {
+            return Err(format!("Invalid cron range: {}", part));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+
+    Ok(allowed)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    let parsed: u32 = value.parse().map_err(|_| format!("Invalid cron value: {}", value))?;
+    if parsed < min || parsed > max {
+        return Err(format!("Cron value {} out of range {}-{}", parsed, min, max));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: DateTime<Local>) -> DateTime<Local> {
+        Schedule::parse(expr).unwrap().next_after(after).unwrap()
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(Schedule::parse("0 7 * *").is_err());
+        assert!(Schedule::parse("60 7 * * *").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+        assert!(Schedule::parse("0 9-5 * * *").is_err());
+        assert!(Schedule::parse("0 7 0 * *").is_err());
+        assert!(Schedule::parse("x 7 * * *").is_err());
+    }
+
+    #[test]
+    fn finds_the_next_daily_run() {
+        assert_eq!(next("0 7 * * *", at(2025, 3, 1, 6, 30)), at(2025, 3, 1, 7, 0));
+        assert_eq!(next("0 7 * * *", at(2025, 3, 1, 7, 0)), at(2025, 3, 2, 7, 0));
+        assert_eq!(next("0 7 * * *", at(2025, 12, 31, 8, 0)), at(2026, 1, 1, 7, 0));
+    }
+
+    #[test]
+    fn handles_steps_lists_and_ranges() {
+        assert_eq!(next("*/15 * * * *", at(2025, 3, 1, 10, 1)), at(2025, 3, 1, 10, 15));
+        assert_eq!(next("0 8,20 * * *", at(2025, 3, 1, 9, 0)), at(2025, 3, 1, 20, 0));
+        assert_eq!(next("30 9 * * 1-5", at(2025, 3, 1, 0, 0)), at(2025, 3, 3, 9, 30));
+    }
+
+    #[test]
+    fn matches_either_day_field_when_both_are_set() {
+        // 2025-03-07 is a Friday, before the 13th
+        assert_eq!(next("0 0 13 * 5", at(2025, 3, 1, 0, 0)), at(2025, 3, 7, 0, 0));
+        assert_eq!(next("0 0 13 * 7", at(2025, 3, 1, 12, 0)), at(2025, 3, 2, 0, 0));
+        assert_eq!(next("0 0 29 2 *", at(2025, 3, 1, 0, 0)), at(2028, 2, 29, 0, 0));
+    }
+
+    #[test]
+    fn treats_stepped_day_fields_as_unrestricted() {
+        // Mondays in March 2025 fall on the 3rd, 10th, 17th, 24th and 31st
+        assert_eq!(next("0 7 */2 * 1", at(2025, 3, 1, 0, 0)), at(2025, 3, 3, 7, 0));
+        assert_eq!(next("0 7 */2 * 1", at(2025, 3, 4, 0, 0)), at(2025, 3, 17, 7, 0));
+        assert_eq!(next("0 7 1 * */3", at(2025, 3, 2, 0, 0)), at(2025, 6, 1, 7, 0));
+    }
+}
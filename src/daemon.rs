@@ -0,0 +1,129 @@
+use crate::cli::Args;
+use crate::cron::Schedule;
+use crate::delivery;
+use crate::error::RasError;
+use crate::lock::{self, RunLock};
+use crate::metrics;
+use crate::pipeline;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+const MAX_SLEEP_SECS: i64 = 60;
+
+#[derive(Serialize, Deserialize, Default)]
+struct DaemonState {
+    schedule: String,
+    runs: u64,
+    last_started: Option<String>,
+    last_finished: Option<String>,
+    last_status: Option<String>,
+}
+
+fn state_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("daemon.json"))
+}
+
+fn load_state(path: &Path) -> DaemonState {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(path: &Path, state: &DaemonState) {
+    if let Ok(json) = serde_json::to_string_pretty(state) {
+        let _ = fs::write(path, json);
+    }
+}
+
+pub fn log_info(message: &str) {
+    log("<6>", message);
+}
+
+pub fn log_error(message: &str) {
+    log("<3>", message);
+}
+
+fn log(priority: &str, message: &str) {
+    if std::env::var_os("JOURNAL_STREAM").is_some() {
+        println!("{}{}", priority, message);
+    } else {
+        println!("[{}] {}", Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let expr = args.value("schedule").unwrap_or("0 7 * * *");
+    let schedule = Schedule::parse(expr)?;
+
+    if let Some(port) = args.value("metrics") {
+        let port: u16 = port.parse().map_err(|_| format!("Invalid metrics port: {}", port))?;
+        let host = args.value("metrics-host").unwrap_or("127.0.0.1");
+        metrics::serve(host, port)?;
+        log_info(&format!("Serving metrics on http://{}:{}/metrics", host, port));
+    }
+
+    let path = state_path()?;
+    let mut state = load_state(&path);
+    state.schedule = expr.to_string();
+
+    log_info(&format!("Daemon started with schedule \"{}\"", expr));
+    if let (Some(finished), Some(status)) = (&state.last_finished, &state.last_status) {
+        log_info(&format!("Previous run finished at {}: {}", finished, status));
+    }
+
+    loop {
+        let next = schedule
+            .next_after(Local::now())
+            .ok_or_else(|| format!("Schedule \"{}\" never fires", expr))?;
+        log_info(&format!("Next run at {}", next.format("%Y-%m-%d %H:%M")));
+
+        loop {
+            let remaining = (next - Local::now()).num_seconds();
+            if remaining <= 0 {
+                break;
+            }
+            thread::sleep(Duration::from_secs(remaining.min(MAX_SLEEP_SECS) as u64));
+            delivery::flush_pending();
+        }
+
+        let lock = match RunLock::acquire_breaking_stale(&lock::lock_path()?) {
+            Ok(lock) => lock,
+            Err(e) => {
+                log_error(&format!("Skipping scheduled run: {}", e));
+                continue;
+            }
+        };
+
+        state.runs += 1;
+        state.last_started = Some(Local::now().to_rfc3339());
+        save_state(&path, &state);
+        log_info(&format!("Starting run #{}", state.runs));
+
+        let status = match pipeline::RunOptions::from_args(args).map_err(RasError::from).and_then(pipeline::execute) {
+            Ok(()) => "success".to_string(),
+            Err(e) => format!("failed: {}", e),
+        };
+        drop(lock);
+
+        if status == "success" {
+            log_info(&format!("Run #{} finished", state.runs));
+        } else {
+            log_error(&format!("Run #{} {}", state.runs, status));
+        }
+
+        let finished = Local::now();
+        metrics::add("ras_runs_total", if status == "success" { "status=\"success\"" } else { "status=\"failed\"" }, 1.0);
+        metrics::set("ras_last_run_timestamp_seconds", "", finished.timestamp() as f64);
+        if status == "success" {
+            metrics::set("ras_last_success_timestamp_seconds", "", finished.timestamp() as f64);
+        }
+        state.last_finished = Some(finished.to_rfc3339());
+        state.last_status = Some(status);
+        save_state(&path, &state);
+    }
+}
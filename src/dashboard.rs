@@ -0,0 +1,251 @@
+use crate::arxiv::Paper;
+use crate::openai;
+use crate::pipeline::RunContext;
+use crate::runs::PaperRun;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+use ratatui::Frame;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const LOG_LINES: u16 = 12;
+const REFRESH: Duration = Duration::from_millis(250);
+
+pub struct Dashboard {
+    stop: Arc<AtomicBool>,
+    draw: Option<JoinHandle<()>>,
+    reader: Option<JoinHandle<()>>,
+    log: Arc<Mutex<Vec<String>>>,
+    capture: Option<Capture>,
+}
+
+#[cfg(unix)]
+struct Capture {
+    saved: [i32; 2],
+}
+
+#[cfg(unix)]
+impl Capture {
+    fn start() -> Result<(Capture, File), String> {
+        use std::os::fd::FromRawFd;
+        let os_error = || io::Error::last_os_error().to_string();
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        io::stderr().flush().map_err(|e| e.to_string())?;
+        let mut fds = [0; 2];
+        unsafe {
+            if libc::pipe(fds.as_mut_ptr()) != 0 {
+                return Err(os_error());
+            }
+            let pipe = File::from_raw_fd(fds[0]);
+            let saved = [libc::dup(1), libc::dup(2)];
+            if saved.contains(&-1) {
+                let error = os_error();
+                saved.iter().filter(|fd| **fd != -1).for_each(|fd| {
+                    libc::close(*fd);
+                });
+                libc::close(fds[1]);
+                return Err(error);
+            }
+            let capture = Capture { saved };
+            if libc::dup2(fds[1], 1) == -1 || libc::dup2(fds[1], 2) == -1 {
+                let error = os_error();
+                libc::close(fds[1]);
+                return Err(error);
+            }
+            libc::close(fds[1]);
+            Ok((capture, pipe))
+        }
+    }
+
+    fn terminal(&self) -> Result<File, String> {
+        use std::os::fd::FromRawFd;
+        let fd = unsafe { libc::dup(self.saved[0]) };
+        if fd == -1 {
+            return Err(io::Error::last_os_error().to_string());
+        }
+        Ok(unsafe { File::from_raw_fd(fd) })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Capture {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        let _ = io::stderr().flush();
+        unsafe {
+            libc::dup2(self.saved[0], 1);
+            libc::dup2(self.saved[1], 2);
+            libc::close(self.saved[0]);
+            libc::close(self.saved[1]);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct Capture;
+
+#[cfg(not(unix))]
+impl Capture {
+    fn start() -> Result<(Capture, File), String> {
+        Err("the dashboard is only available on Unix terminals".to_string())
+    }
+
+    fn terminal(&self) -> Result<File, String> {
+        Err("the dashboard is only available on Unix terminals".to_string())
+    }
+}
+
+pub fn start(ctx: Arc<RunContext>, papers: &[Paper]) -> Result<Dashboard, String> {
+    let (capture, pipe) = Capture::start()?;
+    let terminal = capture.terminal()?;
+    let mut terminal = match enable_raw_mode()
+        .and_then(|_| Terminal::new(CrosstermBackend::new(terminal)))
+        .and_then(|mut t| execute!(t.backend_mut(), EnterAlternateScreen).map(|_| t))
+    {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            let _ = disable_raw_mode();
+            return Err(e.to_string());
+        }
+    };
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let lines = log.clone();
+    let reader = thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            lines.lock().unwrap().push(line);
+        }
+    });
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = stop.clone();
+    let lines = log.clone();
+    let papers = papers.to_vec();
+    let draw = thread::spawn(move || {
+        let started = Instant::now();
+        while !stopped.load(Ordering::SeqCst) {
+            let _ = terminal.draw(|frame| render(frame, &ctx, &papers, &lines.lock().unwrap(), started));
+            if event::poll(REFRESH).unwrap_or(false)
+                && let Ok(Event::Key(key)) = event::read()
+                && (key.code == KeyCode::Char('q') || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)))
+            {
+                ctx.options.cancel.cancel();
+            }
+        }
+        let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+        let _ = terminal.show_cursor();
+    });
+
+    Ok(Dashboard { stop, draw: Some(draw), reader: Some(reader), log, capture: Some(capture) })
+}
+
+impl Dashboard {
+    pub fn finish(self) -> Vec<String> {
+        let log = self.log.clone();
+        drop(self);
+        std::mem::take(&mut *log.lock().unwrap())
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(draw) = self.draw.take() {
+            let _ = draw.join();
+        }
+        drop(self.capture.take());
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+fn state(run: Option<&PaperRun>) -> (&'static str, Color) {
+    let Some(run) = run else {
+        return ("queued", Color::DarkGray);
+    };
+    match (run.outcome.as_str(), run.active.as_str()) {
+        ("summarized", _) => ("done", Color::Green),
+        ("failed", _) => ("failed", Color::Red),
+        ("skipped", _) => ("skipped", Color::Yellow),
+        (_, "download") => ("downloading", Color::Cyan),
+        (_, "extract" | "html") => ("extracting", Color::Blue),
+        (_, "summarize") => ("summarizing", Color::Magenta),
+        _ if run.extracted => ("extracted", Color::Blue),
+        _ if run.downloaded => ("downloaded", Color::Cyan),
+        _ => ("queued", Color::DarkGray),
+    }
+}
+
+fn rank(state: &str) -> u8 {
+    match state {
+        "downloading" | "extracting" | "summarizing" => 0,
+        "downloaded" | "extracted" => 1,
+        "queued" => 2,
+        "failed" => 3,
+        _ => 4,
+    }
+}
+
+fn render(frame: &mut Frame, ctx: &RunContext, papers: &[Paper], log: &[String], started: Instant) {
+    let snapshot = ctx.tracker.snapshot();
+    let mut rows: Vec<(&'static str, Color, &Paper, Option<&PaperRun>)> = papers
+        .iter()
+        .map(|paper| {
+            let run = snapshot.get(&paper.id);
+            let (state, color) = state(run);
+            (state, color, paper, run)
+        })
+        .collect();
+    rows.sort_by_key(|(state, ..)| rank(state));
+
+    let count = |name: &str| rows.iter().filter(|(state, ..)| *state == name).count();
+    let finished = count("done") + count("failed") + count("skipped");
+    let elapsed = started.elapsed().as_secs_f64();
+    let usage = openai::total_usage();
+    let header = format!(
+        "{}/{} papers  done {}  failed {}  skipped {}  |  {:.0}s  {:.1} papers/min  |  {} tokens  ${:.4}  |  {}",
+        finished,
+        papers.len(),
+        count("done"),
+        count("failed"),
+        count("skipped"),
+        elapsed,
+        finished as f64 * 60.0 / elapsed.max(1.0),
+        usage.prompt_tokens + usage.completion_tokens,
+        usage.cost,
+        if ctx.options.cancel.is_cancelled() { "cancelling..." } else { "q to cancel" }
+    );
+
+    let [top, middle, bottom] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(5), Constraint::Length(LOG_LINES + 2)]).areas(frame.area());
+    frame.render_widget(Paragraph::new(header).block(Block::default().borders(Borders::ALL).title(" ras ")), top);
+
+    let table_rows = rows.iter().map(|(state, color, paper, run)| {
+        let stages = run
+            .map(|r| r.stages.iter().map(|(stage, seconds)| format!("{} {:.1}s", stage, seconds)).collect::<Vec<_>>().join(", "))
+            .unwrap_or_default();
+        Row::new(vec![state.to_string(), paper.id.clone(), paper.title.clone(), stages]).style(Style::default().fg(*color))
+    });
+    let table = Table::new(
+        table_rows,
+        [Constraint::Length(12), Constraint::Length(12), Constraint::Percentage(60), Constraint::Percentage(40)],
+    )
+    .header(Row::new(vec!["state", "id", "title", "stages"]).style(Style::default().fg(Color::White)))
+    .block(Block::default().borders(Borders::ALL).title(" papers "));
+    frame.render_widget(table, middle);
+
+    let tail = log[log.len().saturating_sub(LOG_LINES as usize)..].join("\n");
+    frame.render_widget(Paragraph::new(tail).block(Block::default().borders(Borders::ALL).title(" log ")), bottom);
+}
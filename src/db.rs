@@ -0,0 +1,348 @@
+use crate::arxiv::Paper;
+use crate::entities::Entities;
+use crate::history::{self, Change};
+use crate::pwc::CodeInfo;
+use crate::scholar::ScholarInfo;
+use crate::references::Reference;
+use crate::regenerate::SummaryVersion;
+use crate::series::Relation;
+use crate::error::RasError;
+use crate::lock::FileLock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub type SharedDb = Arc<Mutex<Db>>;
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Db {
+    pub papers: BTreeMap<String, PaperRecord>,
+    pub last_success: Option<String>,
+    pub watchlist: BTreeMap<String, Vec<String>>,
+    pub aliases: BTreeMap<String, String>,
+    #[serde(skip)]
+    base: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PaperRecord {
+    pub id: String,
+    pub title: String,
+    pub pdf_url: String,
+    pub category: String,
+    pub summary_file: String,
+    pub pdf_file: String,
+    pub pdf_sha256: String,
+    pub pdf_mirror: String,
+    pub status: String,
+    pub stage: String,
+    pub tags: Vec<String>,
+    pub related: Vec<Relation>,
+    pub references: Vec<Reference>,
+    pub grounding: Option<f64>,
+    pub version: u32,
+    pub attempts: u32,
+    pub last_error: String,
+    pub zotero_key: String,
+    pub readwise_id: String,
+    pub language: String,
+    pub entities: Entities,
+    pub scholar: Option<ScholarInfo>,
+    pub code: Option<CodeInfo>,
+    pub sources: Vec<String>,
+    pub added_at: String,
+    pub opens: u32,
+    pub last_opened: Option<String>,
+    pub rating: i8,
+    pub read: Option<bool>,
+    pub starred: bool,
+    pub notes: String,
+    pub summary_versions: Vec<SummaryVersion>,
+}
+
+impl PaperRecord {
+    pub fn is_read(&self) -> bool {
+        self.read.unwrap_or(self.opens > 0)
+    }
+
+    pub fn to_paper(&self) -> Paper {
+        Paper {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            pdf_url: self.pdf_url.clone(),
+            category: self.category.clone(),
+            scholar: self.scholar.clone(),
+            code: self.code.clone(),
+            sources: self.sources.clone(),
+        }
+    }
+}
+
+pub fn db_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("ras.json"))
+}
+
+fn read_db_file(path: &Path) -> Result<Option<Value>, RasError> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(RasError::Database(format!("Failed to read {}: {}", path.display(), e))),
+    };
+    serde_json::from_str(&json).map(Some).map_err(|e| {
+        RasError::Database(format!("{} could not be parsed ({}). Nothing was changed; repair or move the file before running ras again.", path.display(), e))
+    })
+}
+
+fn from_value(value: &Value, path: &Path) -> Result<Db, RasError> {
+    Db::deserialize(value).map_err(|e| {
+        RasError::Database(format!("{} does not match the expected format ({}). Nothing was changed; repair or move the file before running ras again.", path.display(), e))
+    })
+}
+
+// Three-way merge of the in-memory copy with the file on disk, so that a long run saving its
+// copy does not overwrite records other commands changed since it was loaded.
+fn merge(base: Option<&Value>, mine: &Value, theirs: &Value) -> Value {
+    let (Value::Object(mine), Value::Object(theirs)) = (mine, theirs) else {
+        return if base == Some(mine) { theirs.clone() } else { mine.clone() };
+    };
+    let base = base.and_then(Value::as_object);
+    let mut merged = Map::new();
+    for key in mine.keys().chain(theirs.keys().filter(|k| !mine.contains_key(*k))) {
+        let old = base.and_then(|b| b.get(key));
+        let value = match (mine.get(key), theirs.get(key)) {
+            (Some(m), Some(t)) => Some(merge(old, m, t)),
+            (Some(m), None) => (old.is_none() || old != Some(m)).then(|| m.clone()),
+            (None, Some(t)) => (old.is_none() || old != Some(t)).then(|| t.clone()),
+            (None, None) => None,
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    Value::Object(merged)
+}
+
+impl Db {
+    pub fn load() -> Result<Db, RasError> {
+        let path = db_path()?;
+        let Some(value) = read_db_file(&path)? else {
+            return Ok(Db::default());
+        };
+        let mut db = from_value(&value, &path)?;
+        db.base = serde_json::to_value(&db).ok();
+        Ok(db)
+    }
+
+    pub fn shared() -> Result<SharedDb, RasError> {
+        Ok(Arc::new(Mutex::new(Db::load()?)))
+    }
+
+    pub fn save(&mut self) -> Result<(), String> {
+        let path = db_path()?;
+        let _lock = FileLock::exclusive(&path.with_extension("json.lock"))?;
+        let mine = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let merged = match read_db_file(&path)? {
+            Some(theirs) => merge(self.base.as_ref(), &mine, &theirs),
+            None => mine,
+        };
+        let mut db = from_value(&merged, &path)?;
+        let merged = serde_json::to_value(&db).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(&merged).map_err(|e| e.to_string())?;
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+        db.base = Some(merged);
+        *self = db;
+        Ok(())
+    }
+
+    pub fn upsert_paper(&mut self, paper: &Paper, summary_file: &str, status: &str) {
+        match self.papers.get(&paper.id) {
+            None => history::record(
+                &paper.id,
+                Change::Added {
+                    title: paper.title.clone(),
+                    category: paper.category.clone(),
+                    status: status.to_string(),
+                },
+            ),
+            Some(existing) if existing.status != status => {
+                history::record(&paper.id, Change::Status { status: status.to_string() })
+            }
+            Some(_) => {}
+        }
+        let record = self.papers.entry(paper.id.clone()).or_insert_with(|| PaperRecord {
+            id: paper.id.clone(),
+            added_at: Utc::now().to_rfc3339(),
+            ..Default::default()
+        });
+        record.title = paper.title.clone();
+        record.pdf_url = paper.pdf_url.clone();
+        record.category = paper.category.clone();
+        record.summary_file = summary_file.to_string();
+        record.status = status.to_string();
+        if paper.scholar.is_some() {
+            record.scholar = paper.scholar.clone();
+        }
+        if paper.code.is_some() {
+            record.code = paper.code.clone();
+        }
+        for source in &paper.sources {
+            if !record.sources.contains(source) {
+                record.sources.push(source.clone());
+            }
+        }
+    }
+
+    pub fn record_failure(&mut self, paper: &Paper, error: &str) {
+        if !self.papers.contains_key(&paper.id) {
+            self.upsert_paper(paper, "", "failed");
+        }
+        if let Some(record) = self.papers.get_mut(&paper.id) {
+            record.attempts += 1;
+            record.last_error = error.to_string();
+        }
+    }
+
+    pub fn watermark(&self) -> Option<DateTime<Utc>> {
+        self.last_success
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    pub fn set_watermark(&mut self, at: DateTime<Utc>) {
+        self.last_success = Some(at.to_rfc3339());
+    }
+
+    pub fn record_watch(&mut self, author: &str, id: &str) {
+        let ids = self.watchlist.entry(author.to_string()).or_default();
+        if !ids.iter().any(|i| i == id) {
+            ids.push(id.to_string());
+        }
+    }
+
+    pub fn set_stage(&mut self, id: &str, stage: &str) {
+        if let Some(record) = self.papers.get_mut(id) {
+            record.stage = stage.to_string();
+        }
+    }
+
+    pub fn add_tag(&mut self, id: &str, tag: &str) {
+        if let Some(record) = self.papers.get_mut(id)
+            && !record.tags.iter().any(|t| t == tag) {
+                record.tags.push(tag.to_string());
+                history::record(id, Change::Tags { tags: record.tags.clone() });
+            }
+    }
+
+    pub fn record_open(&mut self, id: &str) {
+        if let Some(record) = self.papers.get_mut(id) {
+            record.opens += 1;
+            record.last_opened = Some(Utc::now().to_rfc3339());
+            record.read = Some(true);
+            history::record(id, Change::Opened);
+        }
+    }
+
+    pub fn rate(&mut self, id: &str, rating: i8) {
+        if let Some(record) = self.papers.get_mut(id) {
+            record.rating = rating;
+            history::record(id, Change::Rated { rating });
+        }
+    }
+
+    pub fn set_read(&mut self, id: &str, read: bool) {
+        if let Some(record) = self.papers.get_mut(id) {
+            record.read = Some(read);
+            history::record(id, Change::Read { read });
+        }
+    }
+
+    pub fn set_starred(&mut self, id: &str, starred: bool) {
+        if let Some(record) = self.papers.get_mut(id) {
+            record.starred = starred;
+            history::record(id, Change::Starred { starred });
+        }
+    }
+
+    pub fn set_notes(&mut self, id: &str, notes: &str) {
+        if let Some(record) = self.papers.get_mut(id) {
+            record.notes = notes.to_string();
+        }
+    }
+
+    pub fn find(&self, query: &str) -> Vec<&PaperRecord> {
+        if let Some(record) = self.papers.get(query) {
+            return vec![record];
+        }
+        let query = query.to_lowercase();
+        self.papers
+            .values()
+            .filter(|r| r.title.to_lowercase().contains(&query) || r.summary_file.to_lowercase().contains(&query))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_edits_to_different_fields() {
+        let base = json!({ "papers": { "1": { "read": null, "starred": false, "tags": [] } } });
+        let mine = json!({ "papers": { "1": { "read": true, "starred": false, "tags": [] } } });
+        let theirs = json!({ "papers": { "1": { "read": null, "starred": true, "tags": ["rl"] } } });
+        let merged = merge(Some(&base), &mine, &theirs);
+        assert_eq!(merged, json!({ "papers": { "1": { "read": true, "starred": true, "tags": ["rl"] } } }));
+    }
+
+    #[test]
+    fn prefers_mine_when_both_edit_a_field() {
+        let base = json!({ "rating": 0 });
+        let merged = merge(Some(&base), &json!({ "rating": 5 }), &json!({ "rating": 3 }));
+        assert_eq!(merged, json!({ "rating": 5 }));
+    }
+
+    #[test]
+    fn keeps_deletes_from_either_side() {
+        let base = json!({ "papers": { "1": {}, "2": {}, "3": {} } });
+        let mine = json!({ "papers": { "1": {}, "3": {} } });
+        let theirs = json!({ "papers": { "1": {}, "2": {} } });
+        assert_eq!(merge(Some(&base), &mine, &theirs), json!({ "papers": { "1": {} } }));
+    }
+
+    #[test]
+    fn keeps_a_deleted_entry_the_other_side_edited() {
+        let base = json!({ "papers": { "1": { "read": null } } });
+        let mine = json!({ "papers": { "1": { "read": true } } });
+        let theirs = json!({ "papers": {} });
+        assert_eq!(merge(Some(&base), &mine, &theirs), json!({ "papers": { "1": { "read": true } } }));
+    }
+
+    #[test]
+    fn keeps_new_keys_from_both_sides() {
+        let base = json!({ "papers": { "1": {} } });
+        let mine = json!({ "papers": { "1": {}, "2": { "title": "mine" } } });
+        let theirs = json!({ "papers": { "1": {}, "3": { "title": "theirs" } }, "last_success": "2025-03-01T00:00:00Z" });
+        let merged = merge(Some(&base), &mine, &theirs);
+        assert_eq!(
+            merged,
+            json!({ "papers": { "1": {}, "2": { "title": "mine" }, "3": { "title": "theirs" } }, "last_success": "2025-03-01T00:00:00Z" })
+        );
+    }
+
+    #[test]
+    fn keeps_everything_without_a_base() {
+        let merged = merge(None, &json!({ "a": 1 }), &json!({ "b": 2 }));
+        assert_eq!(merged, json!({ "a": 1, "b": 2 }));
+    }
+}
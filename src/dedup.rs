@@ -0,0 +1,128 @@
+use crate::arxiv::{self, Paper};
+use crate::capabilities::{self, Capability};
+use crate::config::DedupConfig;
+use crate::db::Db;
+use crate::embeddings::cosine_similarity;
+use crate::openai;
+use reqwest::blocking::Client;
+use std::collections::{HashMap, HashSet};
+
+const MAX_EMBEDDED_TITLES: usize = 200;
+
+pub fn normalize(title: &str) -> String {
+    title.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(|w| w.to_lowercase()).collect::<Vec<_>>().join(" ")
+}
+
+fn overlap(a: &str, b: &str) -> f32 {
+    let a: HashSet<&str> = a.split(' ').filter(|w| !w.is_empty()).collect();
+    let b: HashSet<&str> = b.split(' ').filter(|w| !w.is_empty()).collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / a.union(&b).count() as f32
+}
+
+struct Matcher<'a> {
+    config: &'a DedupConfig,
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+// Two different arXiv papers are never duplicates, even with the same title (errata,
+// companion papers, "Technical Report"); only versions of one paper or a paper listed
+// under another source's id are merged.
+fn mergeable(a: &str, b: &str) -> bool {
+    match (arxiv::normalize_id(a), arxiv::normalize_id(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+impl Matcher<'_> {
+    fn same(&self, (a, a_id): (&str, &str), (b, b_id): (&str, &str)) -> bool {
+        if !mergeable(a_id, b_id) {
+            return false;
+        }
+        if a == b {
+            return true;
+        }
+        if overlap(a, b) < self.config.min_overlap {
+            return false;
+        }
+        match (self.vectors.get(a), self.vectors.get(b)) {
+            (Some(a), Some(b)) => cosine_similarity(a, b) >= self.config.similarity,
+            _ => false,
+        }
+    }
+}
+
+fn embed_candidates(client: &Client, api_key: &str, config: &DedupConfig, fetched: &[(String, String)], known: &[(String, String)]) -> HashMap<String, Vec<f32>> {
+    let mut titles: Vec<String> = Vec::new();
+    for (i, (title, id)) in fetched.iter().enumerate() {
+        let others = fetched[..i].iter().chain(known);
+        for (other, _) in others.filter(|(o, other_id)| o != title && mergeable(id, other_id) && overlap(title, o) >= config.min_overlap) {
+            for t in [title, other] {
+                if !titles.contains(t) {
+                    titles.push(t.clone());
+                }
+            }
+        }
+    }
+    if titles.is_empty() {
+        return HashMap::new();
+    }
+    if titles.len() > MAX_EMBEDDED_TITLES {
+        println!("Comparing only the first {} of {} similar titles for duplicates", MAX_EMBEDDED_TITLES, titles.len());
+        titles.truncate(MAX_EMBEDDED_TITLES);
+    }
+    capabilities::attempt(Capability::Embeddings, || openai::embed(client, api_key, &titles))
+        .map(|vectors| titles.into_iter().zip(vectors).collect())
+        .unwrap_or_default()
+}
+
+fn preferred(a: &Paper, b: &Paper) -> bool {
+    arxiv::normalize_id(&a.id).is_some() || arxiv::normalize_id(&b.id).is_none()
+}
+
+pub fn merge(client: &Client, api_key: &str, config: &DedupConfig, db: &mut Db, papers: Vec<Paper>) -> Vec<Paper> {
+    let fetched: Vec<(String, String)> = papers.iter().filter(|p| !db.papers.contains_key(&p.id)).map(|p| (normalize(&p.title), p.id.clone())).collect();
+    let known: Vec<(String, String)> = db.papers.values().map(|r| (normalize(&r.title), r.id.clone())).filter(|(t, _)| !t.is_empty()).collect();
+    let matcher = Matcher { config, vectors: embed_candidates(client, api_key, config, &fetched, &known) };
+
+    let mut kept: Vec<Paper> = Vec::new();
+    for paper in papers {
+        let canonical = db.aliases.get(&paper.id).filter(|id| db.papers.contains_key(*id) && mergeable(&paper.id, id)).cloned().or_else(|| {
+            if db.papers.contains_key(&paper.id) {
+                return None;
+            }
+            let title = normalize(&paper.title);
+            known.iter().find(|(t, id)| *id != paper.id && matcher.same((&title, &paper.id), (t, id))).map(|(_, id)| id.clone())
+        });
+        if let Some(canonical) = canonical {
+            if let Some(record) = db.papers.get_mut(&canonical) {
+                println!("Duplicate of {} already in the library: {} ({})", canonical, paper.title, paper.id);
+                for source in &paper.sources {
+                    if !record.sources.contains(source) {
+                        record.sources.push(source.clone());
+                    }
+                }
+            }
+            db.aliases.insert(paper.id.clone(), canonical);
+            continue;
+        }
+
+        let title = normalize(&paper.title);
+        let Some(existing) = kept.iter_mut().find(|k| k.id != paper.id && matcher.same((&title, &paper.id), (&normalize(&k.title), &k.id))) else {
+            kept.push(paper);
+            continue;
+        };
+        println!("Merged duplicate papers: {} ({}) and {} ({})", existing.title, existing.id, paper.title, paper.id);
+        let duplicate = if preferred(existing, &paper) { paper } else { std::mem::replace(existing, paper) };
+        for source in duplicate.sources {
+            if !existing.sources.contains(&source) {
+                existing.sources.push(source);
+            }
+        }
+        db.aliases.insert(duplicate.id, existing.id.clone());
+    }
+    kept
+}
@@ -0,0 +1,257 @@
+use crate::cli::Args;
+use crate::config::{ChannelWindow, Config};
+use crate::cron::Schedule;
+use crate::db::Db;
+use crate::email;
+use crate::error::RasError;
+use crate::notifications::{self, RunReport, Style, WatchAlert};
+use crate::summary::SummaryInfo;
+use crate::telegram;
+use chrono::{DateTime, Local, NaiveTime};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const CHANNELS: &[&str] = &["email", "slack", "discord", "telegram"];
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Pending {
+    since: String,
+    fetched: usize,
+    processed: usize,
+    failed: usize,
+    ids: Vec<String>,
+    failures: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Outbox {
+    channels: BTreeMap<String, Pending>,
+}
+
+fn outbox_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("outbox.json"))
+}
+
+impl Outbox {
+    fn load() -> Outbox {
+        outbox_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let (Ok(json), Ok(path)) = (serde_json::to_string_pretty(self), outbox_path()) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    fn pending(&mut self, channel: &str) -> &mut Pending {
+        self.channels.entry(channel.to_string()).or_insert_with(|| Pending {
+            since: Local::now().to_rfc3339(),
+            ..Default::default()
+        })
+    }
+}
+
+fn window<'a>(config: &'a Config, channel: &str) -> &'a ChannelWindow {
+    match channel {
+        "email" => &config.delivery.email,
+        "slack" => &config.delivery.slack,
+        "discord" => &config.delivery.discord,
+        _ => &config.delivery.telegram,
+    }
+}
+
+fn configured(config: &Config, channel: &str) -> bool {
+    match channel {
+        "email" => config.email.enabled,
+        "slack" => !config.notifications.slack_webhook.is_empty(),
+        "discord" => !config.notifications.discord_webhook.is_empty(),
+        _ => !config.telegram.bot_token.is_empty() && !config.telegram.chat_id.is_empty(),
+    }
+}
+
+fn parse_clock(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("Invalid time \"{}\" (use HH:MM)", value))
+}
+
+fn in_quiet_hours(range: &str, now: DateTime<Local>) -> Result<bool, String> {
+    if range.is_empty() {
+        return Ok(false);
+    }
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid quiet hours \"{}\" (use HH:MM-HH:MM)", range))?;
+    let (start, end, time) = (parse_clock(start)?, parse_clock(end)?, now.time());
+    Ok(if start <= end { time >= start && time < end } else { time >= start || time < end })
+}
+
+fn quiet_hours<'a>(config: &'a Config, channel: &str) -> &'a str {
+    window(config, channel).quiet_hours.as_deref().unwrap_or(&config.delivery.quiet_hours)
+}
+
+fn is_open(config: &Config, channel: &str, now: DateTime<Local>) -> Result<bool, String> {
+    Ok(window(config, channel).schedule.is_empty() && !in_quiet_hours(quiet_hours(config, channel), now)?)
+}
+
+fn is_due(config: &Config, channel: &str, pending: &Pending, now: DateTime<Local>) -> Result<bool, String> {
+    let schedule = &window(config, channel).schedule;
+    if schedule.is_empty() {
+        return Ok(!in_quiet_hours(quiet_hours(config, channel), now)?);
+    }
+    let since = DateTime::parse_from_rfc3339(&pending.since)
+        .map(|t| t.with_timezone(&Local))
+        .unwrap_or(now);
+    Ok(Schedule::parse(schedule)?.next_after(since).is_some_and(|next| next <= now))
+}
+
+fn send_report(config: &Config, client: &Client, channel: &str, report: &RunReport) {
+    match channel {
+        "email" => {
+            if let Err(e) = email::send_digest(&config.email, report.summaries) {
+                println!("Failed to send email digest: {}", e);
+            }
+        }
+        "slack" => notifications::notify_run(&config.notifications, client, report, Style::Slack),
+        "discord" => notifications::notify_run(&config.notifications, client, report, Style::Discord),
+        _ => telegram::deliver(&config.telegram, client, report.summaries),
+    }
+}
+
+fn send_failure(config: &Config, client: &Client, channel: &str, error: &str) {
+    match channel {
+        "slack" => notifications::notify_failure(&config.notifications, client, error, Style::Slack),
+        "discord" => notifications::notify_failure(&config.notifications, client, error, Style::Discord),
+        _ => {}
+    }
+}
+
+pub fn deliver_run(config: &Config, client: &Client, report: &RunReport) {
+    let now = Local::now();
+    let mut outbox = Outbox::load();
+    for channel in CHANNELS.iter().filter(|c| configured(config, c)) {
+        let open = is_open(config, channel, now).unwrap_or_else(|e| {
+            println!("Invalid delivery window for {}: {}", channel, e);
+            true
+        });
+        if open && !outbox.channels.contains_key(*channel) {
+            send_report(config, client, channel, report);
+            continue;
+        }
+        let pending = outbox.pending(channel);
+        pending.fetched += report.fetched;
+        pending.processed += report.processed;
+        pending.failed += report.failed;
+        pending.ids.extend(report.summaries.iter().map(|s| s.id.clone()));
+        println!("Holding {} delivery until its delivery window", channel);
+    }
+    outbox.save();
+    flush_due(config, client);
+}
+
+pub fn deliver_watchlist(config: &Config, client: &Client, alerts: &[WatchAlert]) {
+    println!("Sending watchlist alerts for {} papers", alerts.len());
+    notifications::notify_watchlist(&config.notifications, client, alerts, Style::Slack);
+    notifications::notify_watchlist(&config.notifications, client, alerts, Style::Discord);
+    telegram::alert(&config.telegram, client, alerts);
+}
+
+pub fn deliver_failure(config: &Config, client: &Client, error: &str) {
+    let now = Local::now();
+    let mut outbox = Outbox::load();
+    for channel in ["slack", "discord"].iter().filter(|c| configured(config, c)) {
+        if is_open(config, channel, now).unwrap_or(true) {
+            send_failure(config, client, channel, error);
+        } else {
+            outbox.pending(channel).failures.push(error.to_string());
+        }
+    }
+    outbox.save();
+}
+
+pub fn flush_due(config: &Config, client: &Client) {
+    flush(config, client, false);
+}
+
+fn flush(config: &Config, client: &Client, force: bool) {
+    let mut outbox = Outbox::load();
+    if outbox.channels.is_empty() {
+        return;
+    }
+    let now = Local::now();
+    let due: Vec<String> = outbox
+        .channels
+        .iter()
+        .filter(|(channel, pending)| force || is_due(config, channel, pending, now).unwrap_or(true))
+        .map(|(channel, _)| channel.clone())
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    let (db, summary_dir) = match Db::load().and_then(|db| Ok((db, crate::ras_dir()?.join("summary")))) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            println!("Failed to deliver held updates: {}", e);
+            return;
+        }
+    };
+    for channel in due {
+        let Some(pending) = outbox.channels.remove(&channel) else {
+            continue;
+        };
+        println!("Delivering held {} updates", channel);
+        for error in &pending.failures {
+            send_failure(config, client, &channel, error);
+        }
+        if pending.processed == 0 && pending.ids.is_empty() {
+            continue;
+        }
+        let summaries: Vec<SummaryInfo> = pending
+            .ids
+            .iter()
+            .filter_map(|id| db.papers.get(id))
+            .filter(|r| r.status == "summarized")
+            .map(|r| SummaryInfo::from_record(r, &summary_dir))
+            .collect();
+        let report = RunReport {
+            fetched: pending.fetched,
+            processed: pending.processed,
+            failed: pending.failed,
+            summaries: &summaries,
+        };
+        send_report(config, client, &channel, &report);
+    }
+    outbox.save();
+}
+
+pub fn flush_pending() {
+    if !outbox_path().is_ok_and(|path| path.exists()) {
+        return;
+    }
+    if let (Ok(config), Ok(client)) = (Config::load(), crate::build_client()) {
+        flush_due(&config, &client);
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let config = Config::load()?;
+    let client = crate::build_client()?;
+    let outbox = Outbox::load();
+    if outbox.channels.is_empty() {
+        println!("Nothing waiting for delivery");
+        return Ok(());
+    }
+    for (channel, pending) in &outbox.channels {
+        println!("{}: {} summaries, {} failure alerts held since {}", channel, pending.ids.len(), pending.failures.len(), pending.since);
+    }
+    flush(&config, &client, args.flag("now"));
+    Ok(())
+}
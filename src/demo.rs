@@ -0,0 +1,48 @@
+use crate::arxiv::Paper;
+use crate::config::Config;
+use crate::db::Db;
+use crate::embeddings;
+use crate::openai;
+use crate::pipeline::{self, RunOptions};
+use chrono::Local;
+use std::fs;
+
+const SAMPLE_PDF: &[u8] = include_bytes!("../assets/demo-paper.pdf");
+
+pub fn run() -> Result<(), String> {
+    let dir = crate::ras_dir()?.join("demo");
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to reset {}: {}", dir.display(), e))?;
+    }
+    crate::set_ras_dir(dir.clone())?;
+    openai::use_mock();
+    println!("Running the demo pipeline in {} with an offline mock model", dir.display());
+
+    let paper = Paper {
+        id: "demo.00001".to_string(),
+        title: "Sparse Attention Windows for Long Document Summarization".to_string(),
+        pdf_url: "bundled sample PDF".to_string(),
+        category: "cs.CL".to_string(),
+        scholar: None,
+        code: None,
+        sources: vec!["demo".to_string()],
+    };
+    let files = Config::load()?.files;
+    let papers_dir = dir.join("papers");
+    fs::create_dir_all(&papers_dir).map_err(|e| e.to_string())?;
+    fs::write(papers_dir.join(pipeline::pdf_filename(&paper, &files, Local::now())), SAMPLE_PDF).map_err(|e| e.to_string())?;
+
+    pipeline::execute(RunOptions::for_papers(vec![paper.clone()])).map_err(|e| e.to_string())?;
+
+    let client = crate::build_client()?;
+    let store = embeddings::load_updated(&client, &openai::require_api_key()?)?;
+    println!("Embedding index contains {} summaries", store.entries.len());
+
+    let summary_file = Db::load()?.papers.get(&paper.id).map(|r| r.summary_file.clone()).unwrap_or_default();
+    let summary_path = dir.join("summary").join(summary_file);
+    let summary = fs::read_to_string(&summary_path).map_err(|e| format!("Demo summary was not written: {}", e))?;
+    println!("\n{}\n", summary);
+    println!("Sample summary: {}", summary_path.display());
+    println!("Everything works. Set OPEN_AI_API_KEY and run `ras` to summarize real papers.");
+    Ok(())
+}
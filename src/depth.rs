@@ -0,0 +1,97 @@
+use crate::cli::Args;
+use crate::config::Config;
+use crate::db::Db;
+use crate::openai;
+use crate::pdf::extract_text_from_pdf;
+use crate::pipeline::{self, RunContext, RunOptions, SUMMARY_SECTIONS};
+use crate::summary::set_frontmatter_field;
+use std::io::IsTerminal;
+
+pub const TLDR_CHARS: usize = 20000;
+
+const TLDR_SECTIONS: &str = r#"Write a TL;DR of exactly three sentences: what the paper introduces, its headline quantitative result, and why it matters. Do not use headings or bullet points."#;
+const DEEP_SECTIONS: &str = r#"
+        5. **Reproduction Notes**: Everything needed to reproduce the main results: datasets and splits, model sizes, hyperparameters, training compute, code and data availability, and the details the paper leaves out.
+        6. **Related Work Analysis**: How the paper positions itself against prior work, which baselines it compares to, and what is genuinely new compared to them."#;
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Depth {
+    Tldr,
+    #[default]
+    Standard,
+    Deep,
+}
+
+impl Depth {
+    pub fn parse(value: &str) -> Result<Depth, String> {
+        match value {
+            "tldr" => Ok(Depth::Tldr),
+            "standard" => Ok(Depth::Standard),
+            "deep" => Ok(Depth::Deep),
+            _ => Err(format!("Invalid depth: {} (expected tldr, standard or deep)", value)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Depth::Tldr => "tldr",
+            Depth::Standard => "standard",
+            Depth::Deep => "deep",
+        }
+    }
+
+    pub fn sections(&self) -> String {
+        match self {
+            Depth::Tldr => TLDR_SECTIONS.to_string(),
+            Depth::Standard => SUMMARY_SECTIONS.to_string(),
+            Depth::Deep => format!("{}{}", SUMMARY_SECTIONS, DEEP_SECTIONS),
+        }
+    }
+
+    pub fn max_tokens(&self) -> u32 {
+        match self {
+            Depth::Tldr => 300,
+            Depth::Standard => 2000,
+            Depth::Deep => 4000,
+        }
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let query = args.positional.join(" ");
+    let (Some(depth), false) = (args.value("depth"), query.is_empty()) else {
+        return Err("Usage: depth <arxiv-id or title> --depth tldr|standard|deep".to_string());
+    };
+    let depth = Depth::parse(depth)?;
+
+    let db = Db::load()?;
+    let record = match db.find(&query).as_slice() {
+        [] => return Err(format!("No paper found for: {}", query)),
+        [record] => (*record).clone(),
+        matches => {
+            println!("Multiple papers match \"{}\":", query);
+            for record in matches {
+                println!("  {}  {}", record.id, record.title);
+            }
+            return Ok(());
+        }
+    };
+
+    let mut ctx = RunContext::new(Config::load()?, RunOptions::default())?;
+    ctx.options.depth = depth;
+    let pdf_path = ctx.papers_dir.join(&record.pdf_file);
+    if record.pdf_file.is_empty() || !pdf_path.exists() {
+        return Err(format!("No local PDF for {}, run `ras fetch --ids {}` first", record.title, record.id));
+    }
+    let paper = record.to_paper();
+    let pdf_text = extract_text_from_pdf(&pdf_path)?;
+
+    println!("Generating {} summary: {}", depth.name(), paper.title);
+    openai::set_echo(std::io::stdout().is_terminal());
+    let summary = pipeline::generate_summary(&ctx, &paper, &pdf_text, &ctx.options.cancel)?;
+    let stem = ctx.summary_file(&paper).trim_end_matches("-summary.md").to_string();
+    let path = ctx.summary_dir.join(format!("{}-{}.md", stem, depth.name()));
+    pipeline::write_summary(&path, &set_frontmatter_field(&summary, "depth", depth.name())).map_err(|e| e.to_string())?;
+    println!("Summary saved: {}", path.display());
+    Ok(())
+}
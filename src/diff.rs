@@ -0,0 +1,188 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::grounding;
+use crate::openai;
+use crate::regenerate::SummaryVersion;
+use crate::summary::{self, strip_frontmatter};
+use std::fs;
+
+const CONTEXT: usize = 2;
+const NOTE_CHARS: usize = 20000;
+
+fn versions(record: &PaperRecord) -> Result<Vec<SummaryVersion>, RasError> {
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut versions = record.summary_versions.clone();
+    let current = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+    versions.push(SummaryVersion::describe(&current, versions.len() as u32 + 1, &record.summary_file));
+    Ok(versions)
+}
+
+fn parse_version(value: &str, latest: u32) -> Option<u32> {
+    match value {
+        "current" | "latest" => Some(latest),
+        _ => value.strip_prefix('v')?.parse().ok(),
+    }
+}
+
+fn body(content: &str) -> String {
+    let content = summary::with_notes(content, "");
+    let body = strip_frontmatter(&content);
+    body.split_once("\n---\n").map(|(_, b)| b).unwrap_or(body).trim().to_string()
+}
+
+fn label(version: &SummaryVersion) -> String {
+    let model = if version.model.is_empty() { "unknown model" } else { &version.model };
+    format!("v{} ({}, prompt {})", version.version, model, version.prompt)
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.lines().map(str::trim_end).filter(|l| !l.is_empty()).collect()
+}
+
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+fn print_diff(old: &str, new: &str) {
+    let diff = diff_lines(&lines(old), &lines(new));
+    let changed: Vec<usize> = diff.iter().enumerate().filter(|(_, (tag, _))| *tag != ' ').map(|(k, _)| k).collect();
+    if changed.is_empty() {
+        println!("The summaries are identical");
+        return;
+    }
+    let mut skipped = false;
+    for (k, (tag, line)) in diff.iter().enumerate() {
+        if !changed.iter().any(|&c| c.abs_diff(k) <= CONTEXT) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            println!("  ...");
+            skipped = false;
+        }
+        println!("{} {}", tag, line);
+    }
+    if skipped {
+        println!("  ...");
+    }
+}
+
+fn print_numbers(from: &SummaryVersion, to: &SummaryVersion, old: &str, new: &str) {
+    let (old, new) = (grounding::claims(old), grounding::claims(new));
+    let only = |a: &[String], b: &[String]| {
+        let only: Vec<&str> = a.iter().filter(|n| !b.contains(n)).map(String::as_str).collect();
+        if only.is_empty() { "none".to_string() } else { only.join(", ") }
+    };
+    println!("\nNumbers only in v{}: {}", from.version, only(&old, &new));
+    println!("Numbers only in v{}: {}", to.version, only(&new, &old));
+}
+
+fn what_changed(record: &PaperRecord, from: &SummaryVersion, to: &SummaryVersion, old: &str, new: &str) -> Result<String, String> {
+    let excerpt = |text: &str| text.chars().take(NOTE_CHARS).collect::<String>();
+    let prompt = format!(
+        r#"Below are two summaries of the same academic paper "{}", written by different models or prompts: {} and {}.
+        Write a short note on what changed between them as concise bullet points. Focus on reported metrics (give both values when they differ, and name numbers only one summary reports), conclusions and limitations that one summary states and the other omits or contradicts.
+        Ignore differences in wording that don't change the content. If nothing substantive changed, say so in one sentence.
+
+        Summary {}:
+        {}
+
+        Summary {}:
+        {}"#,
+        record.title,
+        label(from),
+        label(to),
+        label(from),
+        excerpt(old),
+        label(to),
+        excerpt(new)
+    );
+    let api_key = openai::chat_key()?;
+    let client = crate::build_client()?;
+    openai::chat(&client, &api_key, &prompt, 1000)
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let usage = "Usage: diff <arxiv-id or title> [v1 [v2]] [--llm]";
+    let db = Db::load()?;
+    let mut positional = args.positional.clone();
+    let mut specs = Vec::new();
+    while positional.len() > 1 && specs.len() < 2 {
+        let last = positional.last().map(String::as_str).unwrap_or("");
+        if parse_version(last, 0).is_none() {
+            break;
+        }
+        specs.insert(0, positional.pop().unwrap_or_default());
+    }
+    let query = positional.join(" ");
+    if query.is_empty() {
+        return Err(usage.to_string());
+    }
+    let record = match db.find(&query).as_slice() {
+        [] => return Err(format!("No summary found for: {}", query)),
+        [record] => (*record).clone(),
+        matches => {
+            println!("Multiple summaries match \"{}\":", query);
+            for record in matches {
+                println!("  {}  {}", record.id, record.title);
+            }
+            return Ok(());
+        }
+    };
+
+    let versions = versions(&record)?;
+    let latest = versions.len() as u32;
+    if specs.is_empty() {
+        println!("Summary versions of {}:", record.title);
+        for version in &versions {
+            let current = if version.version == latest { "  (current)" } else { "" };
+            println!("  {}  {}  {}{}", label(version), version.created, version.file, current);
+        }
+        if latest < 2 {
+            println!("Only one version so far, run `ras regenerate {}` to create another", record.id);
+        }
+        return Ok(());
+    }
+
+    let selected: Vec<u32> = specs.iter().map(|spec| parse_version(spec, latest)).collect::<Option<_>>().ok_or(usage)?;
+    let (from, to) = (selected[0], selected.get(1).copied().unwrap_or(latest));
+    let find = |number: u32| versions.iter().find(|v| v.version == number).ok_or(format!("{} has no summary v{} (latest is v{})", record.title, number, latest));
+    let (from, to) = (find(from)?, find(to)?);
+
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let read = |version: &SummaryVersion| fs::read_to_string(summary_dir.join(&version.file)).map(|content| body(&content)).map_err(|e| format!("Failed to read {}: {}", version.file, e));
+    let (old, new) = (read(from)?, read(to)?);
+
+    println!("--- {}\n+++ {}\n", label(from), label(to));
+    if args.flag("llm") {
+        println!("Comparing the summaries...");
+        println!("\n{}", what_changed(&record, from, to, &old, &new)?.trim());
+    } else {
+        print_diff(&old, &new);
+    }
+    print_numbers(from, to, &old, &new);
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::openai;
+use crate::pipeline::MAX_PROMPT_CHARS;
+use crate::summary::strip_frontmatter;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::fs;
+use std::path::PathBuf;
+
+const MIN_PAPER_CHARS: usize = 600;
+
+fn digests_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("digests"))
+}
+
+fn period(args: &Args) -> Result<(DateTime<Utc>, String), String> {
+    if let Some(date) = args.value("since") {
+        let since = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date for --since: {} (expected YYYY-MM-DD)", date))?;
+        return Ok((since.and_hms_opt(0, 0, 0).unwrap().and_utc(), format!("since {}", date)));
+    }
+    let days: i64 = if args.flag("week") { 7 } else { args.parsed("days", 7)? };
+    Ok((Utc::now() - Duration::days(days), format!("last {} days", days)))
+}
+
+fn added_since(record: &PaperRecord, since: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(&record.added_at).is_ok_and(|t| t.with_timezone(&Utc) >= since)
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let (since, label) = period(args)?;
+    let db = Db::load()?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut records: Vec<&PaperRecord> =
+        db.papers.values().filter(|r| r.status == "summarized" && added_since(r, since)).collect();
+    records.sort_by(|a, b| a.added_at.cmp(&b.added_at));
+    if records.is_empty() {
+        println!("No summaries from the {}", label);
+        return Ok(());
+    }
+
+    let share = (MAX_PROMPT_CHARS / records.len()).max(MIN_PAPER_CHARS);
+    let mut papers = String::new();
+    let mut index = String::new();
+    for (i, record) in records.iter().enumerate() {
+        let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+        let body = strip_frontmatter(&content);
+        let body = body.split_once("\n---\n").map(|(_, b)| b).unwrap_or(body);
+        let excerpt: String = body.trim().chars().take(share).collect();
+        papers.push_str(&format!("[{}] {} (arXiv {}, {})\n{}\n\n", i + 1, record.title, record.id, record.category, excerpt));
+        let marker = match (record.starred, record.is_read()) {
+            (true, _) => " ★",
+            (false, false) => " (unread)",
+            _ => "",
+        };
+        index.push_str(&format!("{}. [{}](../summary/{}){}\n", i + 1, record.title, record.summary_file.replace(' ', "%20"), marker));
+    }
+
+    println!("Synthesizing {} summaries from the {}...", records.len(), label);
+    let prompt = format!(
+        r#"You are writing a research digest covering {} paper summaries from the {}. Each summary is numbered like [3].
+
+        Write a single Markdown report with these sections:
+        1. **Dominant Themes**: The main research directions across the papers and how they connect.
+        2. **Notable Results**: The most important quantitative findings, with their numbers.
+        3. **Papers by Topic**: Group every paper under a topic heading, one bullet per paper with a one-line takeaway.
+        4. **Most Significant Papers**: Pick up to three papers and justify each pick.
+
+        Refer to papers by their number in square brackets, e.g. [3]. Do not hallucinate. Use only the summaries below.
+
+        Summaries:
+        {}"#,
+        records.len(),
+        label,
+        papers
+    );
+    let api_key = openai::chat_key()?;
+    let client = crate::build_client()?;
+    let report = openai::chat(&client, &api_key, &prompt, 4000)?;
+
+    let date = Local::now().format("%Y-%m-%d");
+    let unread = records.iter().filter(|r| !r.is_read()).count();
+    let content = format!(
+        "# Research Digest — {} ({})\n\n{}\n\n## Papers\n\n{} of {} papers unread.\n\n{}",
+        date,
+        label,
+        report.trim(),
+        unread,
+        records.len(),
+        index
+    );
+    fs::create_dir_all(digests_dir()?).map_err(|e| e.to_string())?;
+    let path = digests_dir()?.join(format!("digest-{}.md", date));
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    println!("Digest saved: {} ({} of {} papers unread)", path.display(), unread, records.len());
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::config::EmailConfig;
+use crate::html::escape_html;
+use crate::summary::{SummaryInfo, group_by_category};
+use chrono::Local;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+pub fn send_digest(config: &EmailConfig, summaries: &[SummaryInfo]) -> Result<(), String> {
+    if !config.enabled {
+        return Ok(());
+    }
+    if summaries.is_empty() && config.skip_if_empty {
+        println!("No new summaries, skipping email digest");
+        return Ok(());
+    }
+
+    let subject = format!("ras digest {}: {} new summaries", Local::now().format("%Y-%m-%d"), summaries.len());
+    let from: Mailbox = config.from.parse().map_err(|e| format!("Invalid from address {}: {}", config.from, e))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for to in &config.to {
+        let mailbox: Mailbox = to.parse().map_err(|e| format!("Invalid to address {}: {}", to, e))?;
+        builder = builder.to(mailbox);
+    }
+    let email = builder
+        .header(ContentType::TEXT_HTML)
+        .body(render_html(summaries))
+        .map_err(|e| e.to_string())?;
+
+    let relay = if config.starttls {
+        SmtpTransport::starttls_relay(&config.smtp_host)
+    } else {
+        SmtpTransport::relay(&config.smtp_host)
+    }
+    .map_err(|e| e.to_string())?;
+
+    let mut transport = relay.port(config.smtp_port);
+    if !config.username.is_empty() {
+        transport = transport.credentials(Credentials::new(config.username.clone(), config.password()));
+    }
+    transport.build().send(&email).map_err(|e| e.to_string())?;
+
+    println!("Email digest sent to {}", config.to.join(", "));
+    Ok(())
+}
+
+fn render_html(summaries: &[SummaryInfo]) -> String {
+    let mut html = String::from("<html><body style=\"font-family: sans-serif; max-width: 760px;\">\n");
+    html.push_str(&format!("<h1>ras digest &mdash; {}</h1>\n", Local::now().format("%Y-%m-%d")));
+
+    if summaries.is_empty() {
+        html.push_str("<p>Nothing new today.</p>\n");
+    }
+
+    for (category, group) in group_by_category(summaries) {
+        html.push_str(&format!("<h2>{} ({})</h2>\n<ul>\n", escape_html(category), group.len()));
+        for summary in group {
+            html.push_str(&format!(
+                "<li><p><a href=\"{}\"><b>{}</b></a><br>{}<br><small><a href=\"file://{}\">summary</a> &middot; <a href=\"{}\">PDF</a></small></p></li>\n",
+                summary.abs_url(),
+                escape_html(&summary.title),
+                escape_html(&summary.tldr),
+                escape_html(&summary.path.display().to_string()),
+                summary.pdf_url,
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</body></html>\n");
+    html
+}
@@ -0,0 +1,149 @@
+use crate::error::RasError;
+use crate::openai;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const MAX_EMBED_CHARS: usize = 8000;
+const EMBED_BATCH_SIZE: usize = 50;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct EmbeddingStore {
+    pub model: String,
+    pub entries: BTreeMap<String, Entry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub modified: u64,
+    pub vector: Vec<f32>,
+}
+
+pub fn store_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("embeddings.json"))
+}
+
+impl EmbeddingStore {
+    pub fn load(path: &Path) -> EmbeddingStore {
+        let store: EmbeddingStore = fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        if store.model != openai::EMBEDDING_MODEL {
+            return EmbeddingStore {
+                model: openai::EMBEDDING_MODEL.to_string(),
+                entries: BTreeMap::new(),
+            };
+        }
+        store
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn stale_files(&self, summary_dir: &Path) -> Vec<(String, u64)> {
+        summary_files(summary_dir)
+            .into_iter()
+            .filter(|(name, modified)| self.entries.get(name).is_none_or(|e| e.modified != *modified))
+            .collect()
+    }
+
+    pub fn update(&mut self, client: &Client, api_key: &str, summary_dir: &Path) -> Result<usize, String> {
+        let on_disk: BTreeMap<String, u64> = summary_files(summary_dir).into_iter().collect();
+        self.entries.retain(|name, _| on_disk.contains_key(name));
+
+        let stale = self.stale_files(summary_dir);
+        for batch in stale.chunks(EMBED_BATCH_SIZE) {
+            let inputs: Vec<String> = batch
+                .iter()
+                .map(|(name, _)| {
+                    let content = fs::read_to_string(summary_dir.join(name)).unwrap_or_default();
+                    content.chars().take(MAX_EMBED_CHARS).collect()
+                })
+                .collect();
+            let vectors = openai::embed(client, api_key, &inputs)?;
+            for ((name, modified), vector) in batch.iter().zip(vectors) {
+                self.entries.insert(name.clone(), Entry { modified: *modified, vector });
+            }
+        }
+
+        Ok(stale.len())
+    }
+
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<(String, f32)> {
+        let mut scored: Vec<(String, f32)> = self
+            .entries
+            .iter()
+            .map(|(name, entry)| (name.clone(), cosine_similarity(query, &entry.vector)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(top_k);
+        scored
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+fn summary_files(summary_dir: &Path) -> Vec<(String, u64)> {
+    let mut files = Vec::new();
+    collect_summary_files(summary_dir, "", &mut files);
+    files
+}
+
+fn collect_summary_files(dir: &Path, prefix: &str, files: &mut Vec<(String, u64)>) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let Some(name) = entry.file_name().to_str().map(|s| format!("{}{}", prefix, s)) else {
+                continue;
+            };
+            if entry.path().is_dir() {
+                collect_summary_files(&entry.path(), &format!("{}/", name), files);
+                continue;
+            }
+            if !name.ends_with("-summary.md") {
+                continue;
+            }
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            files.push((name, modified));
+        }
+    }
+}
+
+pub fn load_updated(client: &Client, api_key: &str) -> Result<EmbeddingStore, String> {
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let path = store_path()?;
+    let mut store = EmbeddingStore::load(&path);
+    let updated = store.update(client, api_key, &summary_dir)?;
+    if updated > 0 {
+        println!("Embedded {} new or changed summaries", updated);
+        store.save(&path)?;
+    }
+    Ok(store)
+}
+
+pub fn run_index() -> Result<(), String> {
+    let client = crate::build_client()?;
+    let api_key = openai::require_api_key()?;
+    let store = load_updated(&client, &api_key)?;
+    println!("Embedding index contains {} summaries", store.entries.len());
+    Ok(())
+}
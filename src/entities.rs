@@ -0,0 +1,225 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::history::print_record;
+use crate::openai;
+use crate::pdf::extract_text_from_pdf;
+use crate::summary::strip_frontmatter;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+const KINDS: &[(&str, &str)] = &[("model", "Models"), ("dataset", "Datasets"), ("benchmark", "Benchmarks"), ("institution", "Institutions")];
+const VOCABULARY_SIZE: usize = 40;
+const MAX_PER_KIND: usize = 15;
+const SUMMARY_CHARS: usize = 6000;
+const FIRST_PAGE_CHARS: usize = 3000;
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Entities {
+    pub models: Vec<String>,
+    pub datasets: Vec<String>,
+    pub benchmarks: Vec<String>,
+    pub institutions: Vec<String>,
+}
+
+impl Entities {
+    pub fn get(&self, kind: &str) -> &[String] {
+        match kind {
+            "model" => &self.models,
+            "dataset" => &self.datasets,
+            "benchmark" => &self.benchmarks,
+            "institution" => &self.institutions,
+            _ => &[],
+        }
+    }
+
+    fn get_mut(&mut self, kind: &str) -> Option<&mut Vec<String>> {
+        match kind {
+            "model" => Some(&mut self.models),
+            "dataset" => Some(&mut self.datasets),
+            "benchmark" => Some(&mut self.benchmarks),
+            "institution" => Some(&mut self.institutions),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        KINDS.iter().all(|(kind, _)| self.get(kind).is_empty())
+    }
+
+    pub fn frontmatter(&self) -> Vec<(String, String)> {
+        KINDS
+            .iter()
+            .filter(|(kind, _)| !self.get(kind).is_empty())
+            .map(|(kind, _)| (format!("{}s", kind), self.get(kind).join("; ")))
+            .collect()
+    }
+}
+
+pub fn key(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+fn counts<'a>(db: &'a Db, kind: &str) -> Vec<(&'a str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for name in db.papers.values().flat_map(|r| r.entities.get(kind)) {
+        *counts.entry(name.as_str()).or_default() += 1;
+    }
+    let mut names: Vec<(&str, usize)> = counts.into_iter().collect();
+    names.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    names
+}
+
+pub fn vocabulary(db: &Db) -> Entities {
+    let mut vocabulary = Entities::default();
+    for (kind, _) in KINDS {
+        if let Some(list) = vocabulary.get_mut(kind) {
+            *list = counts(db, kind).into_iter().take(VOCABULARY_SIZE).map(|(n, _)| n.to_string()).collect();
+        }
+    }
+    vocabulary
+}
+
+fn canonical(name: &str, known: &[String]) -> String {
+    let name = name.trim().trim_matches(['-', '*', '•', '"', '.']).trim();
+    known.iter().find(|k| key(k) == key(name)).cloned().unwrap_or_else(|| name.to_string())
+}
+
+fn parse(reply: &str, known: &Entities) -> Entities {
+    let mut entities = Entities::default();
+    for line in reply.lines() {
+        let Some((label, names)) = line.split_once(':') else {
+            continue;
+        };
+        let label = label.trim().trim_start_matches(['-', '*']).trim();
+        let Some((kind, _)) = KINDS.iter().find(|(_, l)| l.eq_ignore_ascii_case(label)) else {
+            continue;
+        };
+        let known = known.get(kind);
+        let Some(list) = entities.get_mut(kind) else {
+            continue;
+        };
+        for name in names.split(';').map(|n| canonical(n, known)).filter(|n| !n.is_empty() && !n.eq_ignore_ascii_case("none")) {
+            if list.len() < MAX_PER_KIND && !list.iter().any(|existing| key(existing) == key(&name)) {
+                list.push(name);
+            }
+        }
+    }
+    entities
+}
+
+pub fn extract(client: &Client, api_key: &str, paper: &Paper, summary: &str, text: &str, known: &Entities) -> Result<Entities, String> {
+    let summary: String = strip_frontmatter(summary).chars().take(SUMMARY_CHARS).collect();
+    let first_page: String = text.chars().take(FIRST_PAGE_CHARS).collect();
+    let existing: Vec<String> = KINDS
+        .iter()
+        .map(|(kind, label)| {
+            let names = known.get(kind);
+            format!("{}: {}", label, if names.is_empty() { "(none yet)".to_string() } else { names.join("; ") })
+        })
+        .collect();
+    let prompt = format!(
+        r#"List the named artifacts the academic paper "{}" proposes, trains, evaluates or compares against, based on its summary and first page below.
+        Use the canonical public name of each artifact, e.g. "GPT-4" rather than "OpenAI's GPT4 model", "ImageNet" for "the ImageNet dataset" and "Stanford University" for "Stanford".
+        When an artifact matches a name already used in my library, write it exactly as listed there.
+        Institutions are the authors' affiliations.
+
+        Names already used in my library:
+        {}
+
+        First page:
+        {}
+
+        Summary:
+        {}
+
+        Answer with exactly these four lines, names separated by semicolons, or "none":
+        Models: ...
+        Datasets: ...
+        Benchmarks: ...
+        Institutions: ..."#,
+        paper.title,
+        existing.join("\n        "),
+        first_page,
+        summary
+    );
+    let reply = openai::in_pass("entities", || openai::chat(client, api_key, &prompt, 400))?;
+    Ok(parse(&reply, known))
+}
+
+fn matches(record: &PaperRecord, kind: &str, query: &str) -> bool {
+    let query = key(query);
+    !query.is_empty() && record.entities.get(kind).iter().any(|name| key(name).starts_with(&query))
+}
+
+fn backfill(args: &Args) -> Result<(), String> {
+    let limit: usize = args.parsed("limit", usize::MAX)?;
+    let client = crate::build_client()?;
+    let api_key = openai::chat_key()?;
+    let ras_dir = crate::ras_dir()?;
+
+    let mut db = Db::load()?;
+    let pending: Vec<PaperRecord> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && r.entities.is_empty() && !r.summary_file.is_empty())
+        .take(limit)
+        .cloned()
+        .collect();
+
+    println!("Extracting entities from {} papers", pending.len());
+    for record in pending {
+        let Ok(summary) = fs::read_to_string(ras_dir.join("summary").join(&record.summary_file)) else {
+            println!("  Skipping {}: summary file not found", record.title);
+            continue;
+        };
+        let text = if record.pdf_file.is_empty() { String::new() } else { extract_text_from_pdf(&ras_dir.join("papers").join(&record.pdf_file)).unwrap_or_default() };
+        let known = vocabulary(&db);
+        match extract(&client, &api_key, &record.to_paper(), &summary, &text, &known) {
+            Ok(entities) => {
+                let count: usize = KINDS.iter().map(|(kind, _)| entities.get(kind).len()).sum();
+                println!("  {}: {} entities", record.title, count);
+                if let Some(stored) = db.papers.get_mut(&record.id) {
+                    stored.entities = entities;
+                }
+                db.save()?;
+            }
+            Err(e) => println!("  Failed to extract entities from {}: {}", record.title, e),
+        }
+    }
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    if args.flag("extract") {
+        return backfill(args);
+    }
+    let db = Db::load()?;
+    let filters: Vec<(&str, &str)> = KINDS.iter().filter_map(|(kind, _)| args.value(kind).map(|query| (*kind, query))).collect();
+    if !filters.is_empty() {
+        let mut records: Vec<&PaperRecord> =
+            db.papers.values().filter(|r| r.status == "summarized" && filters.iter().all(|(kind, query)| matches(r, kind, query))).collect();
+        records.sort_by(|a, b| b.added_at.cmp(&a.added_at));
+        for record in &records {
+            print_record(record);
+        }
+        println!("\n{} papers", records.len());
+        return Ok(());
+    }
+
+    let top: usize = args.parsed("top", 15)?;
+    if db.papers.values().all(|r| r.entities.is_empty()) {
+        println!("No entities yet. Enable [entities] in the config or run `entities --extract` on existing summaries.");
+        return Ok(());
+    }
+    for (kind, label) in KINDS {
+        println!("{}:", label);
+        for (name, count) in counts(&db, kind).into_iter().take(top) {
+            println!("  {:<40} {:>4}", name, count);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RasError {
+    #[error("HOME environment variable not set")]
+    NoHome,
+    #[error("Failed to load config: {0}")]
+    Config(String),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Fetching papers failed")]
+    FetchFailed,
+    #[error("{failed} of {total} papers failed")]
+    PartialFailure { failed: usize, total: usize },
+    #[error("All {0} papers failed")]
+    TotalFailure(usize),
+    #[error("{0}")]
+    Database(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<String> for RasError {
+    fn from(message: String) -> Self {
+        RasError::Other(message)
+    }
+}
+
+impl From<RasError> for String {
+    fn from(error: RasError) -> Self {
+        error.to_string()
+    }
+}
+
+impl RasError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RasError::PartialFailure { .. } => 2,
+            RasError::TotalFailure(_) | RasError::FetchFailed => 3,
+            _ => 1,
+        }
+    }
+}
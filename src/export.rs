@@ -0,0 +1,200 @@
+use crate::anki;
+use crate::arxiv::{self, Metadata};
+use crate::book;
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::obsidian;
+use crate::org;
+use crate::readwise;
+use crate::site;
+use crate::zotero;
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+const BIBTEX_HEADING: &str = "## BibTeX";
+
+pub fn run(args: &Args) -> Result<(), String> {
+    match args.positional.first().map(|s| s.as_str()) {
+        Some("bibtex") => export_bibtex(args),
+        Some("csl-json") => export_csl_json(args),
+        Some("zotero") => zotero::export(&crate::build_client()?),
+        Some("readwise") => readwise::export(&crate::build_client()?),
+        Some("anki") => anki::export(args),
+        Some("book") => book::export(args),
+        Some("html") => site::export(args),
+        Some("obsidian") => obsidian::export(args),
+        Some("org") => org::export(args),
+        Some(other) => Err(format!("Unknown export format: {}", other)),
+        None => Err("Usage: export <bibtex|csl-json|zotero|readwise|anki|book|html|obsidian|org>".to_string()),
+    }
+}
+
+fn summarized_with_metadata(db: &Db) -> Result<(Vec<&PaperRecord>, HashMap<String, Metadata>), String> {
+    let records: Vec<&PaperRecord> = db.papers.values().filter(|r| r.status == "summarized").collect();
+    if records.is_empty() {
+        return Err("No summarized papers to export".to_string());
+    }
+
+    let client = crate::build_client()?;
+    let ids: Vec<String> = records.iter().map(|r| r.id.clone()).collect();
+    println!("Fetching bibliographic data for {} papers from arXiv", ids.len());
+    let metadata = arxiv::fetch_metadata(&client, &ids);
+    if records.len() > metadata.len() {
+        println!("{} papers will be exported without arXiv API data", records.len() - metadata.len());
+    }
+    Ok((records, metadata))
+}
+
+fn export_bibtex(args: &Args) -> Result<(), String> {
+    let output = args.value("output").unwrap_or("ras.bib");
+    let db = Db::load()?;
+    let (records, metadata) = summarized_with_metadata(&db)?;
+
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut used_keys = HashSet::new();
+    let mut bib = String::new();
+    for record in &records {
+        let meta = metadata.get(&record.id).cloned().unwrap_or_else(|| fallback_metadata(record));
+        let entry = bibtex_entry(&meta, &mut used_keys);
+        bib.push_str(&entry);
+        bib.push('\n');
+
+        if args.flag("append") {
+            let path = summary_dir.join(&record.summary_file);
+            if let Ok(content) = fs::read_to_string(&path) {
+                fs::write(&path, with_bibtex_block(&content, &entry)).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    fs::write(output, bib).map_err(|e| format!("Failed to write {}: {}", output, e))?;
+    println!("Wrote {} entries to {}", records.len(), output);
+    Ok(())
+}
+
+fn export_csl_json(args: &Args) -> Result<(), String> {
+    let output = args.value("output").unwrap_or("ras-csl.json");
+    let db = Db::load()?;
+    let (records, metadata) = summarized_with_metadata(&db)?;
+
+    let items: Vec<Value> = records
+        .iter()
+        .map(|record| csl_item(&metadata.get(&record.id).cloned().unwrap_or_else(|| fallback_metadata(record))))
+        .collect();
+    let json = serde_json::to_string_pretty(&items).map_err(|e| e.to_string())?;
+    fs::write(output, json).map_err(|e| format!("Failed to write {}: {}", output, e))?;
+    println!("Wrote {} items to {}", items.len(), output);
+    Ok(())
+}
+
+fn csl_item(meta: &Metadata) -> Value {
+    let authors: Vec<Value> = meta
+        .authors
+        .iter()
+        .map(|name| match name.rsplit_once(' ') {
+            Some((given, family)) => json!({ "given": given, "family": family }),
+            None => json!({ "literal": name }),
+        })
+        .collect();
+    let date_parts: Vec<u32> = meta
+        .published
+        .get(..10)
+        .unwrap_or(meta.year())
+        .split('-')
+        .filter_map(|p| p.parse().ok())
+        .collect();
+    json!({
+        "id": format!("arXiv:{}", meta.id),
+        "type": "article",
+        "title": meta.title,
+        "author": authors,
+        "issued": { "date-parts": [date_parts] },
+        "abstract": meta.summary,
+        "publisher": "arXiv",
+        "number": meta.id,
+        "DOI": meta.doi.clone().unwrap_or_default(),
+        "URL": format!("https://arxiv.org/abs/{}", meta.id),
+    })
+}
+
+fn fallback_metadata(record: &PaperRecord) -> Metadata {
+    Metadata {
+        id: record.id.clone(),
+        title: record.title.clone(),
+        primary_category: record.category.clone(),
+        published: format!("20{}", record.id.get(..2).unwrap_or("")),
+        ..Default::default()
+    }
+}
+
+fn citation_key(meta: &Metadata, used: &mut HashSet<String>) -> String {
+    let author = meta
+        .authors
+        .first()
+        .and_then(|a| a.split_whitespace().last())
+        .unwrap_or("arxiv");
+    let word = meta
+        .title
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|w| w.len() > 3)
+        .unwrap_or("paper");
+    let base: String = format!("{}{}{}", author, meta.year(), word)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+
+    let mut key = base.clone();
+    let mut suffix = b'a';
+    while !used.insert(key.clone()) {
+        key = format!("{}{}", base, suffix as char);
+        suffix += 1;
+    }
+    key
+}
+
+fn escape_bibtex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '&' | '%' | '$' | '#' | '_') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn bibtex_entry(meta: &Metadata, used_keys: &mut HashSet<String>) -> String {
+    let key = citation_key(meta, used_keys);
+    let mut fields = vec![
+        ("title", format!("{{{}}}", escape_bibtex(&meta.title))),
+        ("author", escape_bibtex(&meta.authors.join(" and "))),
+        ("year", meta.year().to_string()),
+        ("eprint", meta.id.clone()),
+        ("archivePrefix", "arXiv".to_string()),
+        ("primaryClass", meta.primary_category.clone()),
+        ("url", format!("https://arxiv.org/abs/{}", meta.id)),
+    ];
+    if let Some(doi) = &meta.doi {
+        fields.push(("doi", doi.clone()));
+    }
+    if let Some(journal) = &meta.journal_ref {
+        fields.push(("note", escape_bibtex(journal)));
+    }
+
+    let body: Vec<String> = fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| format!("  {} = {{{}}}", name, value))
+        .collect();
+    format!("@misc{{{},\n{}\n}}\n", key, body.join(",\n"))
+}
+
+fn with_bibtex_block(content: &str, entry: &str) -> String {
+    let body = match content.find(BIBTEX_HEADING) {
+        Some(pos) => content[..pos].trim_end(),
+        None => content.trim_end(),
+    };
+    format!("{}\n\n{}\n\n```bibtex\n{}```\n", body, BIBTEX_HEADING, entry)
+}
@@ -0,0 +1,217 @@
+use crate::cancel::{CANCELLED, CancellationToken};
+use crate::config::ExtractionConfig;
+use lopdf::Document;
+use std::io::ErrorKind;
+use std::panic::{self, AssertUnwindSafe};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const OCR_BACKEND: &str = "tesseract";
+pub const PAGE_BREAK: char = '\u{c}';
+
+static CONFIG: Mutex<Option<ExtractionConfig>> = Mutex::new(None);
+static OCR_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Extraction {
+    pub text: String,
+    pub backend: String,
+    pub problems: Vec<String>,
+}
+
+pub trait Backend {
+    fn name(&self) -> &str;
+    fn extract(&self, path: &Path, cancel: &CancellationToken) -> Result<String, String>;
+}
+
+pub struct Lopdf;
+
+impl Backend for Lopdf {
+    fn name(&self) -> &str {
+        "lopdf"
+    }
+
+    fn extract(&self, path: &Path, cancel: &CancellationToken) -> Result<String, String> {
+        let doc = Document::load(path).map_err(|e| e.to_string())?;
+        let mut text = String::new();
+        let pages: Vec<_> = doc.get_pages().keys().cloned().collect();
+        for page_id in pages {
+            cancel.check()?;
+            if let Ok(content) = doc.extract_text(&[page_id]) {
+                text.push_str(&content);
+                text.push('\n');
+                text.push(PAGE_BREAK);
+            }
+        }
+        Ok(text)
+    }
+}
+
+pub struct PdfExtract;
+
+impl Backend for PdfExtract {
+    fn name(&self) -> &str {
+        "pdf-extract"
+    }
+
+    fn extract(&self, path: &Path, _cancel: &CancellationToken) -> Result<String, String> {
+        panic::catch_unwind(AssertUnwindSafe(|| pdf_extract::extract_text_by_pages(path)))
+            .map_err(|_| "pdf-extract panicked".to_string())?
+            .map(|pages| pages.join(&PAGE_BREAK.to_string()))
+            .map_err(|e| e.to_string())
+    }
+}
+
+pub struct Pdftotext;
+
+impl Backend for Pdftotext {
+    fn name(&self) -> &str {
+        "pdftotext"
+    }
+
+    fn extract(&self, path: &Path, _cancel: &CancellationToken) -> Result<String, String> {
+        let output = run_tool(Command::new("pdftotext").args(["-enc", "UTF-8"]).arg(path).arg("-"), "pdftotext")?;
+        Ok(String::from_utf8_lossy(&output).to_string())
+    }
+}
+
+pub struct Tesseract {
+    pub language: String,
+    pub max_pages: usize,
+}
+
+fn run_tool(command: &mut Command, tool: &str) -> Result<Vec<u8>, String> {
+    let output = command.output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => format!("{} is not installed", tool),
+        _ => e.to_string(),
+    })?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", tool, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(output.stdout)
+}
+
+impl Backend for Tesseract {
+    fn name(&self) -> &str {
+        OCR_BACKEND
+    }
+
+    fn extract(&self, path: &Path, cancel: &CancellationToken) -> Result<String, String> {
+        let dir = std::env::temp_dir().join(format!("ras-ocr-{}-{}", std::process::id(), OCR_RUNS.fetch_add(1, Ordering::SeqCst)));
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let result = self.ocr_pages(path, &dir, cancel);
+        let _ = fs::remove_dir_all(&dir);
+        result
+    }
+}
+
+impl Tesseract {
+    fn ocr_pages(&self, path: &Path, dir: &Path, cancel: &CancellationToken) -> Result<String, String> {
+        run_tool(
+            Command::new("pdftoppm").args(["-r", "300", "-gray", "-png", "-l", &self.max_pages.to_string()]).arg(path).arg(dir.join("page")),
+            "pdftoppm",
+        )?;
+        let mut pages: Vec<PathBuf> = fs::read_dir(dir).map_err(|e| e.to_string())?.flatten().map(|e| e.path()).collect();
+        pages.sort();
+
+        let mut text = String::new();
+        for (i, page) in pages.iter().enumerate() {
+            cancel.check()?;
+            println!("  OCR page {}/{}", i + 1, pages.len());
+            let output = run_tool(Command::new("tesseract").arg(page).arg("-").args(["-l", &self.language]), "tesseract")?;
+            text.push_str(&String::from_utf8_lossy(&output));
+            text.push('\n');
+            text.push(PAGE_BREAK);
+        }
+        Ok(text)
+    }
+}
+
+pub fn backend(name: &str) -> Option<Box<dyn Backend>> {
+    match name {
+        "lopdf" => Some(Box::new(Lopdf)),
+        "pdf-extract" => Some(Box::new(PdfExtract)),
+        "pdftotext" => Some(Box::new(Pdftotext)),
+        _ => None,
+    }
+}
+
+pub fn configure(config: &ExtractionConfig) {
+    *CONFIG.lock().unwrap() = Some(config.clone());
+}
+
+pub fn page_count(path: &Path) -> usize {
+    Document::load(path).map(|doc| doc.get_pages().len()).unwrap_or(0)
+}
+
+pub fn quality_problem(text: &str, pages: usize, config: &ExtractionConfig) -> Option<String> {
+    let chars = text.chars().filter(|c| !c.is_whitespace()).count();
+    if chars == 0 {
+        return Some("no text".to_string());
+    }
+    let unreadable = text.chars().filter(|c| *c == '\u{FFFD}' || (c.is_control() && !c.is_whitespace())).count();
+    let ratio = unreadable as f64 / chars as f64;
+    if ratio > config.max_replacement_ratio {
+        return Some(format!("{:.1}% unreadable characters", ratio * 100.0));
+    }
+    let words_per_page = text.split_whitespace().count() / pages.max(1);
+    if words_per_page < config.min_words_per_page {
+        return Some(format!("{} words per page", words_per_page));
+    }
+    None
+}
+
+pub fn extract(path: &Path, cancel: &CancellationToken) -> Result<Extraction, String> {
+    let config = CONFIG.lock().unwrap().clone().unwrap_or_default();
+    let pages = page_count(path);
+    let mut problems: Vec<String> = Vec::new();
+    let mut best: Option<(usize, Extraction)> = None;
+
+    let mut backends: Vec<Box<dyn Backend>> = Vec::new();
+    for name in &config.backends {
+        match backend(name) {
+            Some(backend) => backends.push(backend),
+            None => problems.push(format!("{}: unknown backend", name)),
+        }
+    }
+    if config.ocr && pages > 0 {
+        backends.push(Box::new(Tesseract { language: config.ocr_language.clone(), max_pages: config.ocr_max_pages }));
+    }
+
+    for backend in backends {
+        cancel.check()?;
+        if backend.name() == OCR_BACKEND {
+            println!("  Text extraction failed ({}), running OCR on up to {} pages", problems.join("; "), config.ocr_max_pages);
+        }
+        match backend.extract(path, cancel) {
+            Ok(text) => match quality_problem(&text, pages, &config) {
+                None => {
+                    if !problems.is_empty() {
+                        println!("  Extracted text with {} after: {}", backend.name(), problems.join("; "));
+                    }
+                    return Ok(Extraction { text, backend: backend.name().to_string(), problems });
+                }
+                Some(problem) => {
+                    problems.push(format!("{}: {}", backend.name(), problem));
+                    let words = text.split_whitespace().count();
+                    if best.as_ref().is_none_or(|(most, _)| words > *most) {
+                        best = Some((words, Extraction { text, backend: backend.name().to_string(), problems: Vec::new() }));
+                    }
+                }
+            },
+            Err(e) if e == CANCELLED => return Err(e),
+            Err(e) => problems.push(format!("{}: {}", backend.name(), e)),
+        }
+    }
+
+    match best {
+        Some((words, mut extraction)) if words > 0 => {
+            println!("  Low-quality text extraction ({}), using the best result", problems.join("; "));
+            extraction.problems = problems;
+            Ok(extraction)
+        }
+        _ => Err(format!("No text extracted from PDF ({})", problems.join("; "))),
+    }
+}
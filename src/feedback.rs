@@ -0,0 +1,153 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::relevance::Profile;
+use crate::summary;
+use std::fs;
+use std::process::Command;
+
+pub fn record_open(id: &str) -> Result<(), String> {
+    let mut db = Db::load()?;
+    db.record_open(id);
+    db.save()
+}
+
+fn find_one(db: &Db, query: &str) -> Result<Option<PaperRecord>, String> {
+    match db.find(query).as_slice() {
+        [] => Err(format!("No summary found for: {}", query)),
+        [record] => Ok(Some((*record).clone())),
+        matches => {
+            println!("Multiple summaries match \"{}\":", query);
+            for record in matches {
+                println!("  {}  {}", record.id, record.title);
+            }
+            Ok(None)
+        }
+    }
+}
+
+pub fn run_open(args: &Args) -> Result<(), String> {
+    let query = args.positional.join(" ");
+    if query.is_empty() {
+        return Err("Usage: open <arxiv-id or title>".to_string());
+    }
+
+    let Some(record) = find_one(&Db::load()?, &query)? else {
+        return Ok(());
+    };
+
+    let path = crate::ras_dir()?.join("summary").join(&record.summary_file);
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    println!("{}", content);
+
+    record_open(&record.id)
+}
+
+pub fn run_rate(args: &Args) -> Result<(), String> {
+    let usage = "Usage: rate <arxiv-id or title> +1|-1|0";
+    let (rating, query) = args.positional.split_last().ok_or(usage)?;
+    let rating: i8 = match rating.as_str() {
+        "+1" | "1" | "up" => 1,
+        "-1" | "down" => -1,
+        "0" | "clear" => 0,
+        _ => return Err(usage.to_string()),
+    };
+    let query = query.join(" ");
+    if query.is_empty() {
+        return Err(usage.to_string());
+    }
+
+    let mut db = Db::load()?;
+    let Some(record) = find_one(&db, &query)? else {
+        return Ok(());
+    };
+    db.rate(&record.id, rating);
+    db.save()?;
+
+    let mut profile = Profile::load();
+    profile.trained_at = None;
+    if let Err(e) = profile.save() {
+        println!("Failed to update relevance profile: {}", e);
+    }
+    match rating {
+        0 => println!("Cleared the rating of {}", record.title),
+        _ => println!("Rated {} {:+}, the interest profile is retrained on the next run", record.title, rating),
+    }
+    Ok(())
+}
+
+pub fn run_mark(args: &Args) -> Result<(), String> {
+    let usage = "Usage: mark read|unread <arxiv-id or title>";
+    let read = match args.positional.first().map(String::as_str) {
+        Some("read") => true,
+        Some("unread") => false,
+        _ => return Err(usage.to_string()),
+    };
+    let query = args.positional[1..].join(" ");
+    if query.is_empty() {
+        return Err(usage.to_string());
+    }
+    let mut db = Db::load()?;
+    let Some(record) = find_one(&db, &query)? else {
+        return Ok(());
+    };
+    db.set_read(&record.id, read);
+    db.save()?;
+    println!("Marked {} as {}", record.title, if read { "read" } else { "unread" });
+    Ok(())
+}
+
+pub fn run_star(args: &Args, starred: bool) -> Result<(), String> {
+    let query = args.positional.join(" ");
+    if query.is_empty() {
+        return Err(format!("Usage: {} <arxiv-id or title>", if starred { "star" } else { "unstar" }));
+    }
+    let mut db = Db::load()?;
+    let Some(record) = find_one(&db, &query)? else {
+        return Ok(());
+    };
+    db.set_starred(&record.id, starred);
+    db.save()?;
+    println!("{} {}", if starred { "Starred" } else { "Unstarred" }, record.title);
+    Ok(())
+}
+
+fn edit(initial: &str) -> Result<String, String> {
+    let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")).unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().ok_or("EDITOR is empty")?;
+    let path = std::env::temp_dir().join(format!("ras-note-{}.md", std::process::id()));
+    fs::write(&path, initial).map_err(|e| e.to_string())?;
+    let status = Command::new(program).args(parts).arg(&path).status().map_err(|e| format!("Failed to start {}: {}", editor, e));
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string());
+    let _ = fs::remove_file(&path);
+    if !status?.success() {
+        return Err(format!("{} exited with an error, note not saved", editor));
+    }
+    content
+}
+
+pub fn run_note(args: &Args) -> Result<(), String> {
+    let query = args.positional.join(" ");
+    if query.is_empty() {
+        return Err("Usage: note <arxiv-id or title>".to_string());
+    }
+    let mut db = Db::load()?;
+    let Some(record) = find_one(&db, &query)? else {
+        return Ok(());
+    };
+
+    let path = crate::ras_dir()?.join("summary").join(&record.summary_file);
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let current = summary::notes(&content).unwrap_or(record.notes.clone());
+    let notes = edit(&current)?;
+    if notes.trim() == current.trim() {
+        println!("Notes unchanged: {}", record.title);
+        return Ok(());
+    }
+
+    fs::write(&path, summary::with_notes(&content, &notes)).map_err(|e| e.to_string())?;
+    db.set_notes(&record.id, notes.trim());
+    db.save()?;
+    println!("{} notes for {}", if notes.trim().is_empty() { "Removed" } else { "Saved" }, record.title);
+    Ok(())
+}
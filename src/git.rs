@@ -0,0 +1,61 @@
+use crate::config::GitConfig;
+use chrono::Local;
+use std::path::Path;
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output().map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", args.first().unwrap_or(&""), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn message(status: &str) -> Option<String> {
+    let (mut added, mut changed, mut other) = (0, 0, 0);
+    for line in status.lines() {
+        let (code, path) = line.split_at(line.len().min(3));
+        match (code.trim(), path.trim_matches('"').ends_with("-summary.md")) {
+            ("??" | "A", true) => added += 1,
+            (_, true) => changed += 1,
+            _ => other += 1,
+        }
+    }
+    let mut parts = Vec::new();
+    if added > 0 {
+        parts.push(format!("{} new {}", added, if added == 1 { "summary" } else { "summaries" }));
+    }
+    if changed > 0 {
+        parts.push(format!("{} updated", changed));
+    }
+    if parts.is_empty() && other > 0 {
+        parts.push(format!("{} files changed", other));
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!("{}: {}", Local::now().format("%Y-%m-%d"), parts.join(", ")))
+}
+
+pub fn commit(config: &GitConfig, dir: &Path) -> Result<Option<String>, String> {
+    if !dir.join(".git").exists() {
+        git(dir, &["init", "--quiet"])?;
+        println!("Initialized a git repository in {}", dir.display());
+    }
+    git(dir, &["add", "--all"])?;
+    let Some(message) = message(&git(dir, &["status", "--porcelain"])?) else {
+        return Ok(None);
+    };
+    let mut args = Vec::new();
+    if git(dir, &["config", "user.email"]).is_err() {
+        args.extend(["-c", "user.name=ras", "-c", "user.email=ras@localhost"]);
+    }
+    args.extend(["commit", "--quiet", "-m", &message]);
+    git(dir, &args)?;
+
+    if config.push {
+        let branch = if config.branch.is_empty() { "HEAD" } else { config.branch.as_str() };
+        git(dir, &["push", "--quiet", &config.remote, branch])?;
+    }
+    Ok(Some(message))
+}
@@ -0,0 +1,276 @@
+use crate::arxiv::Paper;
+use crate::db::Db;
+use crate::error::RasError;
+use crate::html::{escape_html, page};
+use crate::pdf::extract_text_from_pdf;
+use crate::serve;
+use crate::sources;
+use chrono::Local;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+static LONG_FIRST_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"((?:[A-Za-z][A-Za-z-]*\s+){1,9}?[A-Za-z][A-Za-z-]*)\s*\(([A-Z][A-Za-z0-9-]{0,8}[A-Z0-9]s?)\)").unwrap());
+static SHORT_FIRST_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b([A-Z][A-Za-z0-9-]{0,8}[A-Z0-9]s?)\s*\(([A-Za-z][A-Za-z-]*(?:\s+[A-Za-z][A-Za-z-]*){1,9})\)").unwrap());
+static TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+static WORD_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Z][A-Za-z0-9-]*[A-Z0-9]s?\b").unwrap());
+
+const STOPWORDS: &[&str] = &["a", "an", "and", "for", "from", "in", "of", "on", "the", "to", "with", "via", "by"];
+
+static GLOSSARY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub expansion: String,
+    pub paper_id: String,
+    pub paper_title: String,
+    pub date: String,
+    pub papers: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Glossary {
+    pub mined: Vec<String>,
+    pub entries: BTreeMap<String, Entry>,
+}
+
+fn glossary_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("glossary.json"))
+}
+
+fn markdown_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("glossary.md"))
+}
+
+impl Glossary {
+    pub fn load() -> Glossary {
+        glossary_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(glossary_path()?, json).map_err(|e| e.to_string())?;
+        fs::write(markdown_path()?, self.render()).map_err(|e| e.to_string())
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::from("# Glossary\n\nAcronyms defined in summarized papers, with the paper they were first seen in.\n\n");
+        out.push_str("| Acronym | Meaning | First seen | Papers |\n|---|---|---|---|\n");
+        for (acronym, entry) in &self.entries {
+            out.push_str(&format!(
+                "| {} | {} | [{}]({}) ({}) | {} |\n",
+                acronym,
+                entry.expansion,
+                entry.paper_title.replace('|', "/"),
+                sources::abs_url(&entry.paper_id),
+                entry.date,
+                entry.papers
+            ));
+        }
+        out
+    }
+
+    pub fn render_html(&self) -> String {
+        let mut body = String::from("<p><a href=\"/\">&larr; All summaries</a></p>\n<h1>Glossary</h1>\n<table>\n<tr><th>Acronym</th><th>Meaning</th><th>First seen</th><th>Papers</th></tr>\n");
+        for (acronym, entry) in &self.entries {
+            body.push_str(&format!(
+                "<tr id=\"{}\"><td><b>{}</b></td><td>{}</td><td><a href=\"{}\">{}</a> <span class=\"meta\">{}</span></td><td>{}</td></tr>\n",
+                escape_html(acronym),
+                escape_html(acronym),
+                escape_html(&entry.expansion),
+                escape_html(&serve::paper_url(&entry.paper_id)),
+                escape_html(&entry.paper_title),
+                escape_html(&entry.date),
+                entry.papers
+            ));
+        }
+        body.push_str("</table>\n");
+        page("Glossary", &body)
+    }
+
+    pub fn link_acronyms(&self, html: &str) -> String {
+        if self.entries.is_empty() {
+            return html.to_string();
+        }
+        let mut linked: HashSet<String> = HashSet::new();
+        let mut skip_depth = 0usize;
+        let mut out = String::with_capacity(html.len());
+        let mut last = 0;
+
+        for tag in TAG_REGEX.find_iter(html) {
+            let text = &html[last..tag.start()];
+            if skip_depth == 0 {
+                out.push_str(&self.link_text(text, &mut linked));
+            } else {
+                out.push_str(text);
+            }
+            let name = tag.as_str().trim_start_matches(['<', '/']).split([' ', '>']).next().unwrap_or("");
+            if matches!(name, "a" | "code" | "pre" | "h1" | "h2" | "h3") {
+                if tag.as_str().starts_with("</") {
+                    skip_depth = skip_depth.saturating_sub(1);
+                } else {
+                    skip_depth += 1;
+                }
+            }
+            out.push_str(tag.as_str());
+            last = tag.end();
+        }
+        out.push_str(&self.link_text(&html[last..], &mut linked));
+        out
+    }
+
+    fn link_text(&self, text: &str, linked: &mut HashSet<String>) -> String {
+        WORD_REGEX
+            .replace_all(text, |caps: &regex::Captures| {
+                let word = &caps[0];
+                let acronym = word.strip_suffix('s').filter(|a| self.entries.contains_key(*a)).unwrap_or(word);
+                match self.entries.get(acronym) {
+                    Some(entry) if linked.insert(acronym.to_string()) => format!(
+                        "<a href=\"/glossary#{}\" title=\"{}\">{}</a>",
+                        escape_html(acronym),
+                        escape_html(&entry.expansion),
+                        word
+                    ),
+                    _ => word.to_string(),
+                }
+            })
+            .to_string()
+    }
+}
+
+fn letters(acronym: &str) -> Vec<char> {
+    acronym
+        .strip_suffix('s')
+        .unwrap_or(acronym)
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn matches_letters(words: &[&str], letters: &[char]) -> bool {
+    let Some((word, rest)) = words.split_first() else {
+        return letters.is_empty();
+    };
+    let lower = word.to_lowercase();
+    if STOPWORDS.contains(&lower.as_str()) && matches_letters(rest, letters) {
+        return true;
+    }
+    let parts: Vec<char> = lower.split('-').filter_map(|p| p.chars().next()).collect();
+    [&parts[..1.min(parts.len())], &parts[..]]
+        .iter()
+        .any(|initials| !initials.is_empty() && letters.starts_with(initials) && matches_letters(rest, &letters[initials.len()..]))
+}
+
+fn expansion_for(candidate: &str, acronym: &str) -> Option<String> {
+    let letters = letters(acronym);
+    if letters.len() < 2 {
+        return None;
+    }
+    let words: Vec<&str> = candidate.split_whitespace().collect();
+    (0..words.len())
+        .rev()
+        .map(|start| &words[start..])
+        .find(|tail| tail[0].to_lowercase().starts_with(letters[0]) && matches_letters(tail, &letters))
+        .map(|tail| tail.join(" "))
+}
+
+fn acronym_key(acronym: &str) -> String {
+    match acronym.strip_suffix('s') {
+        Some(stem) if stem.chars().last().is_some_and(|c| c.is_ascii_uppercase()) => stem.to_string(),
+        _ => acronym.to_string(),
+    }
+}
+
+pub fn extract(text: &str) -> Vec<(String, String)> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut found: Vec<(String, String)> = Vec::new();
+    let mut push = |acronym: &str, expansion: Option<String>| {
+        let acronym = acronym_key(acronym);
+        if let Some(expansion) = expansion
+            && !found.iter().any(|(a, _)| *a == acronym)
+        {
+            found.push((acronym, expansion));
+        }
+    };
+    for caps in LONG_FIRST_REGEX.captures_iter(&text) {
+        push(&caps[2], expansion_for(&caps[1], &caps[2]));
+    }
+    for caps in SHORT_FIRST_REGEX.captures_iter(&text) {
+        let words: Vec<&str> = caps[2].split_whitespace().collect();
+        let letters = letters(&caps[1]);
+        push(&caps[1], (letters.len() >= 2 && matches_letters(&words, &letters)).then(|| caps[2].to_string()));
+    }
+    found
+}
+
+pub fn record(paper: &Paper, acronyms: &[(String, String)]) -> Result<usize, String> {
+    let _guard = GLOSSARY_LOCK.lock().unwrap();
+    let mut glossary = Glossary::load();
+    if glossary.mined.contains(&paper.id) {
+        return Ok(0);
+    }
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    let mut added = 0;
+    for (acronym, expansion) in acronyms {
+        match glossary.entries.get_mut(acronym) {
+            Some(entry) => entry.papers += 1,
+            None => {
+                glossary.entries.insert(
+                    acronym.clone(),
+                    Entry {
+                        expansion: expansion.clone(),
+                        paper_id: paper.id.clone(),
+                        paper_title: paper.title.clone(),
+                        date: date.clone(),
+                        papers: 1,
+                    },
+                );
+                added += 1;
+            }
+        }
+    }
+    glossary.mined.push(paper.id.clone());
+    glossary.save()?;
+    Ok(added)
+}
+
+pub fn run() -> Result<(), String> {
+    let papers_dir = crate::ras_dir()?.join("papers");
+    let mined: HashSet<String> = Glossary::load().mined.into_iter().collect();
+    let pending: Vec<(Paper, String)> = Db::load()?
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && !mined.contains(&r.id))
+        .map(|r| (r.to_paper(), r.pdf_file.clone()))
+        .collect();
+
+    println!("Collecting acronyms from {} papers", pending.len());
+    for (paper, pdf_file) in pending {
+        let pdf_path = papers_dir.join(pdf_file);
+        let text = match extract_text_from_pdf(&pdf_path) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("  Skipping {}: {}", paper.title, e);
+                continue;
+            }
+        };
+        let acronyms = extract(&text);
+        let added = record(&paper, &acronyms)?;
+        println!("  {}: {} acronyms ({} new)", paper.title, acronyms.len(), added);
+    }
+
+    println!("Glossary: {}", markdown_path()?.display());
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use crate::arxiv;
+use crate::cli::Args;
+use crate::config::Config;
+use crate::db::{Db, PaperRecord};
+use crate::references;
+use crate::scholar;
+use serde_json::json;
+
+const SCHOLAR: &str = "semantic-scholar";
+const REFERENCES: &str = "references";
+const MAX_LABEL_CHARS: usize = 60;
+
+struct Graph<'a> {
+    paper: &'a PaperRecord,
+    cites: Vec<(&'a PaperRecord, &'static str)>,
+    cited_by: Vec<(&'a PaperRecord, &'static str)>,
+}
+
+fn in_corpus<'a>(record: Option<&'a PaperRecord>, id: &str) -> Option<&'a PaperRecord> {
+    record.filter(|r| r.id != id && r.status == "summarized")
+}
+
+fn add<'a>(list: &mut Vec<(&'a PaperRecord, &'static str)>, record: &'a PaperRecord, source: &'static str) {
+    if !list.iter().any(|(r, _)| r.id == record.id) {
+        list.push((record, source));
+    }
+}
+
+fn cites_in_corpus<'a>(db: &'a Db, record: &PaperRecord) -> Vec<&'a PaperRecord> {
+    let mut found: Vec<&PaperRecord> = Vec::new();
+    for reference in &record.references {
+        if let Some(cited) = in_corpus(references::resolve(db, reference), &record.id)
+            && !found.iter().any(|r| r.id == cited.id)
+        {
+            found.push(cited);
+        }
+    }
+    found
+}
+
+fn build<'a>(db: &'a Db, paper: &'a PaperRecord, config: &Config, offline: bool) -> Graph<'a> {
+    let mut graph = Graph { paper, cites: Vec::new(), cited_by: Vec::new() };
+    for cited in cites_in_corpus(db, paper) {
+        add(&mut graph.cites, cited, REFERENCES);
+    }
+    for record in db.papers.values().filter(|r| r.id != paper.id && r.status == "summarized") {
+        if cites_in_corpus(db, record).iter().any(|r| r.id == paper.id) {
+            add(&mut graph.cited_by, record, REFERENCES);
+        }
+    }
+
+    if config.semantic_scholar.enabled && !offline {
+        let neighbors = crate::build_client().and_then(|client| scholar::neighbors(&client, &config.semantic_scholar, &paper.id));
+        match neighbors {
+            Ok((cited, citing)) => {
+                for record in cited.iter().filter_map(|r| in_corpus(references::resolve(db, r), &paper.id)) {
+                    add(&mut graph.cites, record, SCHOLAR);
+                }
+                for record in citing.iter().filter_map(|r| in_corpus(references::resolve(db, r), &paper.id)) {
+                    add(&mut graph.cited_by, record, SCHOLAR);
+                }
+            }
+            Err(e) => eprintln!("Semantic Scholar unavailable, using extracted references only: {}", e),
+        }
+    }
+    graph.cited_by.sort_by(|a, b| a.0.added_at.cmp(&b.0.added_at));
+    graph
+}
+
+impl Graph<'_> {
+    fn nodes(&self) -> Vec<(&PaperRecord, &'static str)> {
+        let mut nodes = vec![(self.paper, "paper")];
+        nodes.extend(self.cites.iter().map(|(r, _)| (*r, "cites")));
+        nodes.extend(self.cited_by.iter().filter(|(r, _)| !self.cites.iter().any(|(c, _)| c.id == r.id)).map(|(r, _)| (*r, "cited_by")));
+        nodes
+    }
+
+    fn edges(&self, db: &Db) -> Vec<(String, String, &'static str)> {
+        let mut edges: Vec<(String, String, &'static str)> = Vec::new();
+        for (record, source) in &self.cites {
+            edges.push((self.paper.id.clone(), record.id.clone(), source));
+        }
+        for (record, source) in &self.cited_by {
+            edges.push((record.id.clone(), self.paper.id.clone(), source));
+        }
+        let nodes = self.nodes();
+        for (from, _) in nodes.iter().filter(|(r, _)| r.id != self.paper.id) {
+            for to in cites_in_corpus(db, from) {
+                let edge = (from.id.clone(), to.id.clone(), REFERENCES);
+                if to.id != self.paper.id && nodes.iter().any(|(n, _)| n.id == to.id) && !edges.iter().any(|e| e.0 == edge.0 && e.1 == edge.1) {
+                    edges.push(edge);
+                }
+            }
+        }
+        edges
+    }
+
+    fn print(&self) {
+        println!("{}  {}", self.paper.id, self.paper.title);
+        for (label, list) in [("Cites", &self.cites), ("Cited by", &self.cited_by)] {
+            println!("\n{} ({} in your library):", label, list.len());
+            for (record, source) in list {
+                let via = if *source == SCHOLAR { "  (via Semantic Scholar)" } else { "" };
+                println!("  {:<14} {}{}", record.id, record.title, via);
+            }
+        }
+    }
+
+    fn dot(&self, db: &Db) -> String {
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::from("digraph citations {\n  rankdir=LR;\n  node [shape=box, style=rounded];\n");
+        for (record, relation) in self.nodes() {
+            let mut title: String = record.title.chars().take(MAX_LABEL_CHARS).collect();
+            if title.len() < record.title.len() {
+                title.push_str("...");
+            }
+            let style = if relation == "paper" { ", style=\"rounded,bold\"" } else { "" };
+            out.push_str(&format!("  \"{}\" [label=\"{}\\n{}\"{}];\n", escape(&record.id), escape(&title), escape(&record.id), style));
+        }
+        for (from, to, source) in self.edges(db) {
+            let style = if source == SCHOLAR { " [style=dashed]" } else { "" };
+            out.push_str(&format!("  \"{}\" -> \"{}\"{};\n", escape(&from), escape(&to), style));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn json(&self, db: &Db) -> serde_json::Value {
+        json!({
+            "paper": self.paper.id,
+            "nodes": self.nodes().iter().map(|(r, relation)| json!({
+                "id": r.id,
+                "title": r.title,
+                "summary_file": r.summary_file,
+                "relation": relation,
+            })).collect::<Vec<_>>(),
+            "edges": self.edges(db).iter().map(|(from, to, source)| json!({ "from": from, "to": to, "source": source })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let usage = "Usage: graph <id> [--format text|dot|json]";
+    let query = args.positional.first().ok_or(usage)?;
+    let id = arxiv::normalize_id(query).unwrap_or_else(|| query.to_string());
+    let config = Config::load()?;
+    let db = Db::load()?;
+    let paper = db.papers.get(&id).ok_or_else(|| format!("Paper not found in the database: {}", id))?;
+    let graph = build(&db, paper, &config, args.flag("offline"));
+
+    match args.value("format").unwrap_or("text") {
+        "text" => graph.print(),
+        "dot" => print!("{}", graph.dot(&db)),
+        "json" => println!("{}", serde_json::to_string_pretty(&graph.json(&db)).map_err(|e| e.to_string())?),
+        other => return Err(format!("Unknown format: {} (expected text, dot or json)", other)),
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::summary::strip_frontmatter;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+(?:\.\d+)?").unwrap());
+static SCALE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s?(thousand|k\b|million|m\b|billion|b\b|trillion|t\b)").unwrap());
+
+const RELATIVE_TOLERANCE: f64 = 0.01;
+const ABSOLUTE_TOLERANCE: f64 = 0.01;
+const UNVERIFIED: &str = " [unverified]";
+
+pub struct Check {
+    pub summary: String,
+    pub total: usize,
+    pub unverified: Vec<String>,
+}
+
+impl Check {
+    pub fn score(&self) -> Option<f64> {
+        (self.total > 0).then(|| (self.total - self.unverified.len()) as f64 / self.total as f64)
+    }
+}
+
+fn value(number: &str) -> Option<f64> {
+    number.replace(',', "").parse().ok()
+}
+
+fn is_claim(text: &str, start: usize, end: usize, number: &str) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    if before.is_some_and(|c| c.is_alphanumeric() || matches!(c, '§' | '.' | '/' | '-' | '_')) {
+        return false;
+    }
+    if after.is_some_and(|c| c == ']') {
+        return false;
+    }
+    let integer = !number.contains('.');
+    let percent = after == Some('%');
+    !(integer && !percent && value(number).is_some_and(|v| v <= 10.0))
+}
+
+fn scale(after: &str) -> f64 {
+    match SCALE_REGEX.captures(&after.to_lowercase()).map(|c| c[1].chars().next().unwrap_or(' ')) {
+        Some('k') => 1e3,
+        Some('m') => 1e6,
+        Some('b') => 1e9,
+        Some('t') => 1e12,
+        _ => 1.0,
+    }
+}
+
+fn source_values(source: &str) -> Vec<f64> {
+    let mut values: Vec<f64> = NUMBER_REGEX.find_iter(source).filter_map(|m| value(m.as_str())).collect();
+    values.sort_by(|a, b| a.total_cmp(b));
+    values.dedup();
+    values
+}
+
+fn matches(values: &[f64], claim: f64, scale: f64) -> bool {
+    [claim, claim / 100.0, claim * 100.0, claim * scale].iter().any(|&target| {
+        let tolerance = (target.abs() * RELATIVE_TOLERANCE).max(ABSOLUTE_TOLERANCE);
+        let start = values.partition_point(|v| *v < target - tolerance);
+        values.get(start).is_some_and(|v| *v <= target + tolerance)
+    })
+}
+
+pub fn claims(text: &str) -> Vec<String> {
+    let mut claims: Vec<String> = Vec::new();
+    for m in NUMBER_REGEX.find_iter(text) {
+        if !is_claim(text, m.start(), m.end(), m.as_str()) {
+            continue;
+        }
+        let end = if text[m.end()..].starts_with('%') { m.end() + 1 } else { m.end() };
+        let claim = text[m.start()..end].to_string();
+        if !claims.contains(&claim) {
+            claims.push(claim);
+        }
+    }
+    claims
+}
+
+pub fn verify(summary: &str, source: &str) -> Check {
+    let values = source_values(source);
+    let frontmatter = &summary[..summary.len() - strip_frontmatter(summary).len()];
+    let (head, body) = match strip_frontmatter(summary).split_once("\n---\n") {
+        Some((head, body)) => (format!("{}{}\n---\n", frontmatter, head), body),
+        None => (String::new(), summary),
+    };
+
+    let mut total = 0;
+    let mut unverified: Vec<String> = Vec::new();
+    let mut checked = String::new();
+    let mut last = 0;
+    for m in NUMBER_REGEX.find_iter(body) {
+        let number = m.as_str();
+        if !is_claim(body, m.start(), m.end(), number) {
+            continue;
+        }
+        let Some(claim) = value(number) else {
+            continue;
+        };
+        total += 1;
+        let after: String = body[m.end()..].chars().take(12).collect();
+        if matches(&values, claim, scale(&after)) {
+            continue;
+        }
+        let end = if body[m.end()..].starts_with('%') { m.end() + 1 } else { m.end() };
+        checked.push_str(&body[last..end]);
+        checked.push_str(UNVERIFIED);
+        last = end;
+        unverified.push(body[m.start()..end].to_string());
+    }
+    checked.push_str(&body[last..]);
+
+    Check { summary: format!("{}{}", head, checked), total, unverified }
+}
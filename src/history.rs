@@ -0,0 +1,217 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::search::SearchIndex;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Change {
+    Added { title: String, category: String, status: String },
+    Status { status: String },
+    Opened,
+    Tags { tags: Vec<String> },
+    Rated { rating: i8 },
+    Read { read: bool },
+    Starred { starred: bool },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Event {
+    pub at: String,
+    pub id: String,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+pub fn events_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("events.jsonl"))
+}
+
+pub fn record(id: &str, change: Change) {
+    let event = Event { at: Utc::now().to_rfc3339(), id: id.to_string(), change };
+    let (Ok(line), Ok(path)) = (serde_json::to_string(&event), events_path()) else {
+        return;
+    };
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+fn load_events() -> Vec<Event> {
+    events_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| content.lines().filter_map(|l| serde_json::from_str(l).ok()).collect())
+        .unwrap_or_default()
+}
+
+fn parse_time(at: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(at).ok().map(|t| t.with_timezone(&Utc))
+}
+
+pub fn history(db: &Db) -> Vec<(DateTime<Utc>, Event)> {
+    let mut events = load_events();
+    let added: HashSet<String> = events
+        .iter()
+        .filter(|e| matches!(e.change, Change::Added { .. }))
+        .map(|e| e.id.clone())
+        .collect();
+    let logged: HashSet<String> = events.iter().map(|e| e.id.clone()).collect();
+
+    for record in db.papers.values().filter(|r| !added.contains(&r.id)) {
+        events.push(Event {
+            at: record.added_at.clone(),
+            id: record.id.clone(),
+            change: Change::Added {
+                title: record.title.clone(),
+                category: record.category.clone(),
+                status: record.status.clone(),
+            },
+        });
+        if !record.tags.is_empty() {
+            events.push(Event {
+                at: record.added_at.clone(),
+                id: record.id.clone(),
+                change: Change::Tags { tags: record.tags.clone() },
+            });
+        }
+        if let Some(opened) = record.last_opened.as_ref().filter(|_| !logged.contains(&record.id)) {
+            events.push(Event { at: opened.clone(), id: record.id.clone(), change: Change::Opened });
+        }
+    }
+
+    let mut timed: Vec<(DateTime<Utc>, Event)> = events
+        .into_iter()
+        .filter_map(|e| parse_time(&e.at).map(|t| (t, e)))
+        .collect();
+    timed.sort_by_key(|(t, _)| *t);
+    timed
+}
+
+pub fn state_at(db: &Db, until: DateTime<Utc>) -> Db {
+    let mut state = Db::default();
+    for (_, event) in history(db).into_iter().take_while(|(t, _)| *t <= until) {
+        match event.change {
+            Change::Added { title, category, status } => {
+                let current = db.papers.get(&event.id);
+                state.papers.insert(
+                    event.id.clone(),
+                    PaperRecord {
+                        id: event.id.clone(),
+                        title,
+                        category,
+                        status,
+                        added_at: event.at.clone(),
+                        pdf_url: current.map(|r| r.pdf_url.clone()).unwrap_or_default(),
+                        summary_file: current.map(|r| r.summary_file.clone()).unwrap_or_default(),
+                        ..Default::default()
+                    },
+                );
+            }
+            Change::Status { status } => {
+                if let Some(record) = state.papers.get_mut(&event.id) {
+                    record.status = status;
+                }
+            }
+            Change::Opened => {
+                if let Some(record) = state.papers.get_mut(&event.id) {
+                    record.opens += 1;
+                    record.last_opened = Some(event.at.clone());
+                    record.read = Some(true);
+                }
+            }
+            Change::Tags { tags } => {
+                if let Some(record) = state.papers.get_mut(&event.id) {
+                    record.tags = tags;
+                }
+            }
+            Change::Rated { rating } => {
+                if let Some(record) = state.papers.get_mut(&event.id) {
+                    record.rating = rating;
+                }
+            }
+            Change::Read { read } => {
+                if let Some(record) = state.papers.get_mut(&event.id) {
+                    record.read = Some(read);
+                }
+            }
+            Change::Starred { starred } => {
+                if let Some(record) = state.papers.get_mut(&event.id) {
+                    record.starred = starred;
+                }
+            }
+        }
+    }
+    state
+}
+
+fn parse_date(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Some(time) = parse_time(value) {
+        return Ok(time);
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {} (use YYYY-MM-DD)", value))?;
+    let end_of_day = date.and_hms_opt(23, 59, 59).unwrap();
+    Local
+        .from_local_datetime(&end_of_day)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| format!("Invalid local date: {}", value))
+}
+
+pub fn print_record(record: &PaperRecord) {
+    let read = if record.is_read() { "read" } else { "    " };
+    let star = if record.starred { "*" } else { " " };
+    let added = record.added_at.get(..10).unwrap_or("");
+    let tags = if record.tags.is_empty() { String::new() } else { format!("  [{}]", record.tags.join(", ")) };
+    println!("{}  {} {}  {:<12} {:<10} {}{}", added, star, read, record.id, record.category, record.title, tags);
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let usage = "Usage: at <YYYY-MM-DD> list [--category C] [--tag T] | at <YYYY-MM-DD> search <query>";
+    let (Some(date), Some(action)) = (args.positional.first(), args.positional.get(1)) else {
+        return Err(usage.to_string());
+    };
+    let until = parse_date(date)?;
+    let state = state_at(&Db::load()?, until);
+
+    match action.as_str() {
+        "list" => {
+            let mut records: Vec<&PaperRecord> = state
+                .papers
+                .values()
+                .filter(|r| args.value("category").is_none_or(|c| r.category == c))
+                .filter(|r| args.value("tag").is_none_or(|t| r.tags.iter().any(|tag| tag == t)))
+                .collect();
+            records.sort_by(|a, b| b.added_at.cmp(&a.added_at));
+            for record in &records {
+                print_record(record);
+            }
+            let read = records.iter().filter(|r| r.is_read()).count();
+            println!("\n{} papers as of {} ({} read)", records.len(), date, read);
+        }
+        "search" => {
+            let query = args.positional[2..].join(" ");
+            if query.is_empty() {
+                return Err(usage.to_string());
+            }
+            let limit: usize = args.parsed("limit", 10)?;
+            let index = SearchIndex::build(&state, &crate::ras_dir()?.join("summary"));
+            let results = index.search(&query, limit);
+            for (id, score) in &results {
+                if let Some(record) = state.papers.get(id) {
+                    print!("{:>6.2}  ", score);
+                    print_record(record);
+                }
+            }
+            println!("\n{} matches as of {}", results.len(), date);
+        }
+        other => return Err(format!("Unknown action: {}\n{}", other, usage)),
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use crate::db::PaperRecord;
+use crate::sources;
+use crate::summary::strip_frontmatter;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd, html};
+
+const STYLE: &str = r#"body { font-family: -apple-system, "Segoe UI", sans-serif; max-width: 920px; margin: 2em auto; padding: 0 1em; color: #222; line-height: 1.5; }
+a { color: #1a5fb4; text-decoration: none; }
+a:hover { text-decoration: underline; }
+table { border-collapse: collapse; width: 100%; }
+td, th { text-align: left; padding: 6px 8px; border-bottom: 1px solid #eee; vertical-align: top; }
+form input, form select { margin-right: 6px; padding: 4px; }
+pre { background: #f6f8fa; padding: 0.8em; overflow-x: auto; }
+code { font-size: 0.92em; }
+blockquote { margin-left: 0; padding-left: 1em; border-left: 3px solid #ddd; color: #555; }
+img { max-width: 100%; }
+.math { overflow-x: auto; }
+.meta { color: #666; font-size: 0.9em; }
+.tag { background: #eef; border-radius: 4px; padding: 1px 6px; margin-right: 4px; font-size: 0.85em; }"#;
+
+const MATHJAX: &str = r#"<script>window.MathJax = { tex: { inlineMath: [["$", "$"], ["\\(", "\\)"]] } };</script>
+<script defer src="https://cdn.jsdelivr.net/npm/mathjax@3/es5/tex-chtml.js"></script>"#;
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_control()).collect();
+    match url.find([':', '/', '?', '#']) {
+        Some(i) if url[i..].starts_with(':') => matches!(url[..i].to_ascii_lowercase().as_str(), "http" | "https" | "mailto"),
+        _ => true,
+    }
+}
+
+pub fn render_markdown(markdown: &str) -> String {
+    let mut dropped: Vec<bool> = Vec::new();
+    let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_MATH).filter_map(move |event| match event {
+        Event::InlineMath(tex) => Some(Event::InlineHtml(CowStr::from(format!("<span class=\"math\">\\({}\\)</span>", escape_html(&tex))))),
+        Event::DisplayMath(tex) => Some(Event::InlineHtml(CowStr::from(format!("<span class=\"math\">\\[{}\\]</span>", escape_html(&tex))))),
+        // Summaries come from the model and the paper text, so raw HTML is shown as text
+        Event::Html(raw) | Event::InlineHtml(raw) if raw.trim_start().starts_with("<!--") => None,
+        Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+        // and links or images pointing at script or data URLs keep only their text
+        Event::Start(Tag::Link { ref dest_url, .. } | Tag::Image { ref dest_url, .. }) => {
+            let safe = is_safe_url(dest_url);
+            dropped.push(!safe);
+            safe.then_some(event)
+        }
+        Event::End(TagEnd::Link | TagEnd::Image) if dropped.pop().unwrap_or(false) => None,
+        event => Some(event),
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+pub fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>{}</title><style>{}</style>\n{}</head>\n<body>\n{}\n</body></html>\n",
+        escape_html(title),
+        STYLE,
+        MATHJAX,
+        body
+    )
+}
+
+pub fn summary_html(content: &str) -> String {
+    render_markdown(strip_frontmatter(content))
+}
+
+pub fn paper_meta(record: &PaperRecord) -> String {
+    format!(
+        "{} &middot; {} &middot; <a href=\"{}\">Abstract</a> &middot; <a href=\"{}\">PDF</a>",
+        escape_html(&record.category),
+        record.added_at.get(..10).unwrap_or_default(),
+        escape_html(&sources::abs_url(&record.id)),
+        escape_html(&record.pdf_url),
+    )
+}
+
+pub fn paper_row(record: &PaperRecord, href: &str, tldr: &str, actions: &str) -> String {
+    let tags: String = record.tags.iter().map(|t| format!("<span class=\"tag\">{}</span>", escape_html(t))).collect();
+    let title = if record.is_read() { escape_html(&record.title) } else { format!("<strong>{}</strong>", escape_html(&record.title)) };
+    format!(
+        "<tr><td>{}</td><td>{}</td><td><a href=\"{}\">{}</a> {}<br><span class=\"meta\">{}</span></td><td>{}</td></tr>\n",
+        record.added_at.get(..10).unwrap_or_default(),
+        escape_html(&record.category),
+        escape_html(href),
+        title,
+        tags,
+        escape_html(tldr),
+        actions,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_raw_html_from_summaries() {
+        let out = render_markdown("Intro <script>alert(1)</script>\n\n<img src=x onerror=\"alert(1)\">\n");
+        assert!(!out.contains("<script>"), "{}", out);
+        assert!(!out.contains("<img"), "{}", out);
+        assert!(out.contains("&lt;script&gt;"), "{}", out);
+    }
+
+    #[test]
+    fn drops_script_and_data_urls_but_keeps_the_text() {
+        let out = render_markdown("[details](javascript:fetch('/x')) and ![chart](data:image/svg+xml;base64,PHN2Zz4=) <JAVASCRIPT:alert(1)>\n");
+        assert!(!out.contains("<a") && !out.contains("<img"), "{}", out);
+        assert!(!out.contains("data:"), "{}", out);
+        assert!(out.contains("<p>details and chart JAVASCRIPT:alert(1)</p>"), "{}", out);
+
+        let out = render_markdown("[paper](https://arxiv.org/abs/2501.01234) [mail](mailto:a@b.c) [next](/papers/2501.01234) [top](#top)\n");
+        assert!(out.contains("href=\"https://arxiv.org/abs/2501.01234\""), "{}", out);
+        assert!(out.contains("href=\"mailto:a@b.c\""), "{}", out);
+        assert!(out.contains("href=\"/papers/2501.01234\""), "{}", out);
+        assert!(out.contains("href=\"#top\""), "{}", out);
+    }
+
+    #[test]
+    fn keeps_math_and_drops_comments() {
+        let out = render_markdown("Loss $a < b$\n\n<!-- ras:notes -->\nMy note\n");
+        assert!(out.contains("<span class=\"math\">\\(a &lt; b\\)</span>"), "{}", out);
+        assert!(!out.contains("ras:notes"), "{}", out);
+        assert!(out.contains("My note"), "{}", out);
+    }
+
+    #[test]
+    fn escapes_quotes() {
+        assert_eq!(escape_html(r#"<a href="x" title='y'>"#), "&lt;a href=&quot;x&quot; title=&#39;y&#39;&gt;");
+    }
+}
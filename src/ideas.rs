@@ -0,0 +1,191 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::db::Db;
+use crate::error::RasError;
+use crate::openai;
+use crate::pdf::extract_text_from_pdf;
+use crate::sources;
+use chrono::Local;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const MAX_CONTEXT_CHARS: usize = 20000;
+const SIMILARITY_THRESHOLD: f32 = 0.7;
+const SECTION_MARKERS: &[&str] = &["future work", "limitations", "open problems", "open questions", "conclusion"];
+
+static IDEAS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Idea {
+    pub text: String,
+    pub paper_id: String,
+    pub paper_title: String,
+    pub date: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct IdeasLog {
+    pub mined: Vec<String>,
+    pub ideas: Vec<Idea>,
+}
+
+fn log_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("ideas.json"))
+}
+
+fn markdown_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("ideas.md"))
+}
+
+impl IdeasLog {
+    pub fn load() -> IdeasLog {
+        log_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(log_path()?, json).map_err(|e| e.to_string())?;
+        fs::write(markdown_path()?, self.render()).map_err(|e| e.to_string())
+    }
+
+    fn is_duplicate(&self, text: &str) -> bool {
+        let candidate = word_set(text);
+        self.ideas.iter().any(|idea| jaccard(&candidate, &word_set(&idea.text)) >= SIMILARITY_THRESHOLD)
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::from("# Research Ideas\n\nOpen problems and future work mined from summarized papers.\n");
+        let mut ideas: Vec<&Idea> = self.ideas.iter().collect();
+        ideas.sort_by(|a, b| b.date.cmp(&a.date));
+
+        let mut current_date = "";
+        for idea in ideas {
+            if idea.date != current_date {
+                current_date = &idea.date;
+                out.push_str(&format!("\n## {}\n\n", current_date));
+            }
+            out.push_str(&format!(
+                "- {} — [{}]({})\n",
+                idea.text, idea.paper_title, sources::abs_url(&idea.paper_id)
+            ));
+        }
+        out
+    }
+}
+
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / a.union(b).count() as f32
+}
+
+fn relevant_text(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let half = (0..=lower.len() / 2).rev().find(|i| lower.is_char_boundary(*i)).unwrap_or(0);
+    let fallback = (0..=lower.len().saturating_sub(MAX_CONTEXT_CHARS))
+        .find(|i| lower.is_char_boundary(*i))
+        .unwrap_or(0);
+    let start = SECTION_MARKERS
+        .iter()
+        .filter_map(|m| lower[half..].find(m).map(|i| i + half))
+        .min()
+        .unwrap_or(fallback);
+    text[start..].chars().take(MAX_CONTEXT_CHARS).collect()
+}
+
+pub fn extract(client: &Client, api_key: &str, paper: &Paper, text: &str) -> Result<Vec<String>, String> {
+    let prompt = format!(
+        r#"From the following excerpt of the paper "{}", list the open problems, limitations the authors want to address, and future work directions they explicitly state.
+        Write each as a single self-contained sentence describing the research direction, one per line, starting with "- ".
+        If the authors state none, answer with "NONE". Do not invent directions that are not in the text.
+
+        Excerpt:
+        {}"#,
+        paper.title,
+        relevant_text(text)
+    );
+
+    let response = openai::chat(client, api_key, &prompt, 800)?;
+    Ok(response
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("- "))
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+pub fn record(paper: &Paper, ideas: &[String]) -> Result<usize, String> {
+    let _guard = IDEAS_LOCK.lock().unwrap();
+    let mut log = IdeasLog::load();
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    let mut added = 0;
+
+    for text in ideas {
+        if log.is_duplicate(text) {
+            continue;
+        }
+        log.ideas.push(Idea {
+            text: text.clone(),
+            paper_id: paper.id.clone(),
+            paper_title: paper.title.clone(),
+            date: date.clone(),
+        });
+        added += 1;
+    }
+    if !log.mined.contains(&paper.id) {
+        log.mined.push(paper.id.clone());
+    }
+    log.save()?;
+    Ok(added)
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let limit: usize = args.parsed("limit", usize::MAX)?;
+    let client = crate::build_client()?;
+    let api_key = openai::chat_key()?;
+    let papers_dir = crate::ras_dir()?.join("papers");
+
+    let mined: HashSet<String> = IdeasLog::load().mined.into_iter().collect();
+    let pending: Vec<(Paper, String)> = Db::load()?
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && !mined.contains(&r.id))
+        .map(|r| (r.to_paper(), r.pdf_file.clone()))
+        .take(limit)
+        .collect();
+
+    println!("Mining open questions from {} papers", pending.len());
+    for (paper, pdf_file) in pending {
+        let pdf_path = papers_dir.join(pdf_file);
+        let text = match extract_text_from_pdf(&pdf_path) {
+            Ok(text) => text,
+            Err(e) => {
+                println!("  Skipping {}: {}", paper.title, e);
+                continue;
+            }
+        };
+        let ideas = extract(&client, &api_key, &paper, &text)?;
+        let added = record(&paper, &ideas)?;
+        println!("  {}: {} ideas ({} new)", paper.title, ideas.len(), added);
+    }
+
+    println!("Ideas log: {}", markdown_path()?.display());
+    Ok(())
+}
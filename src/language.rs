@@ -0,0 +1,61 @@
+use crate::openai;
+use reqwest::blocking::Client;
+use whatlang::Lang;
+
+pub const SKIPPED: &str = "skipped by the language policy";
+
+const SAMPLE_CHARS: usize = 5000;
+const TRANSLATE_CHARS: usize = 12000;
+
+pub struct Language {
+    pub code: String,
+    pub name: String,
+}
+
+impl Language {
+    pub fn is_english(&self) -> bool {
+        self.code == Lang::Eng.code()
+    }
+}
+
+pub fn detect(text: &str) -> Option<Language> {
+    let sample: String = text.chars().take(SAMPLE_CHARS).collect();
+    let info = whatlang::detect(&sample).filter(|info| info.is_reliable())?;
+    Some(Language { code: info.lang().code().to_string(), name: info.lang().eng_name().to_string() })
+}
+
+pub fn instruction(language: &Language) -> String {
+    format!(
+        "[Note: this paper is written in {}. Write the summary in English, translating quotes and keeping the original term in parentheses where the translation is ambiguous.]\n\n",
+        language.name
+    )
+}
+
+pub fn translate(client: &Client, api_key: &str, language: &Language, text: &str, max_chars: usize) -> Result<String, String> {
+    let mut chunks: Vec<String> = vec![String::new()];
+    for paragraph in text.chars().take(max_chars).collect::<String>().split("\n\n") {
+        let current = chunks.last_mut().unwrap();
+        if !current.is_empty() && current.len() + paragraph.len() > TRANSLATE_CHARS {
+            chunks.push(String::new());
+        }
+        let current = chunks.last_mut().unwrap();
+        current.push_str(paragraph);
+        current.push_str("\n\n");
+    }
+
+    let mut translated = String::new();
+    for chunk in chunks.iter().filter(|c| !c.trim().is_empty()) {
+        let prompt = format!(
+            r#"Translate the following part of an academic paper from {} into English.
+            Keep numbers, units, equations, citations, model names and dataset names exactly as they are.
+            Return only the translation.
+
+            Text:
+            {}"#,
+            language.name, chunk
+        );
+        translated.push_str(openai::in_pass("translate", || openai::chat(client, api_key, &prompt, 4000))?.trim());
+        translated.push_str("\n\n");
+    }
+    Ok(translated)
+}
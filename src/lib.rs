@@ -0,0 +1,168 @@
+pub mod ab;
+pub mod anki;
+pub mod ar5iv;
+pub mod arxiv;
+pub mod ask;
+pub mod batch;
+pub mod biorxiv;
+pub mod benchmarks;
+pub mod book;
+pub mod cache;
+pub mod cancel;
+pub mod capabilities;
+pub mod captions;
+pub mod chunking;
+pub mod classics;
+pub mod cleaning;
+pub mod compare;
+pub mod cli;
+pub mod config;
+pub mod credentials;
+pub mod cron;
+pub mod daemon;
+pub mod dashboard;
+pub mod db;
+pub mod dedup;
+pub mod demo;
+pub mod depth;
+pub mod diff;
+pub mod digest;
+pub mod delivery;
+pub mod email;
+pub mod embeddings;
+pub mod entities;
+pub mod error;
+pub mod export;
+pub mod extraction;
+pub mod feedback;
+pub mod git;
+pub mod glossary;
+pub mod graph;
+pub mod grounding;
+pub mod history;
+pub mod html;
+pub mod ideas;
+pub mod language;
+pub mod lock;
+pub mod metrics;
+pub mod migrate;
+pub mod notifications;
+pub mod obsidian;
+pub mod openai;
+pub mod openreview;
+pub mod org;
+pub mod pdf;
+pub mod pick;
+pub mod pipeline;
+pub mod process;
+pub mod pwc;
+pub mod queue;
+pub mod readwise;
+pub mod references;
+pub mod regenerate;
+pub mod relevance;
+pub mod retry;
+pub mod runs;
+pub mod s3;
+pub mod scholar;
+pub mod semaphore;
+pub mod search;
+pub mod series;
+pub mod serve;
+pub mod site;
+pub mod sources;
+pub mod storage;
+pub mod summary;
+pub mod survey;
+pub mod sync;
+pub mod telegram;
+pub mod telemetry;
+pub mod topics;
+pub mod translate;
+pub mod trends;
+pub mod verify;
+pub mod versions;
+pub mod watch;
+pub mod watchlist;
+pub mod webdav;
+pub mod worker;
+pub mod zotero;
+
+pub use cancel::CancellationToken;
+pub use error::RasError;
+pub use pipeline::RunOptions;
+
+use config::{Config, NetworkConfig};
+use reqwest::blocking::Client;
+use reqwest::{Certificate, NoProxy, Proxy};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+static RAS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn ras_dir() -> Result<PathBuf, RasError> {
+    if let Some(dir) = RAS_DIR.get() {
+        return Ok(dir.clone());
+    }
+    let home = std::env::var("HOME").map_err(|_| RasError::NoHome)?;
+    Ok(PathBuf::from(home).join("ras"))
+}
+
+pub fn set_ras_dir(dir: PathBuf) -> Result<(), String> {
+    RAS_DIR.set(dir).map_err(|dir| format!("ras directory already set, cannot switch to {}", dir.display()))
+}
+
+fn proxy_setting(configured: &str, names: &[&str]) -> Option<String> {
+    if !configured.is_empty() {
+        return Some(configured.to_string());
+    }
+    names.iter().filter_map(|name| std::env::var(name).ok()).find(|value| !value.is_empty())
+}
+
+fn root_certificates(paths: &[String]) -> Result<Vec<Certificate>, String> {
+    let mut certificates = Vec::new();
+    for path in paths {
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read certificate {}: {}", path, e))?;
+        match Certificate::from_pem_bundle(&bytes) {
+            Ok(bundle) if !bundle.is_empty() => certificates.extend(bundle),
+            _ => certificates.push(Certificate::from_der(&bytes).map_err(|e| format!("Invalid certificate {}: {}", path, e))?),
+        }
+    }
+    Ok(certificates)
+}
+
+fn client_with(network: &NetworkConfig, connect_timeout: u64, timeout: u64) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(connect_timeout))
+        .timeout(Duration::from_secs(timeout))
+        .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36");
+    let no_proxy = proxy_setting(&network.no_proxy, &["NO_PROXY", "no_proxy"]).and_then(|list| NoProxy::from_string(&list));
+    if let Some(url) = proxy_setting(&network.http_proxy, &["HTTP_PROXY", "http_proxy"]) {
+        let proxy = Proxy::http(&url).map_err(|e| format!("Invalid HTTP proxy {}: {}", url, e))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+    }
+    if let Some(url) = proxy_setting(&network.https_proxy, &["HTTPS_PROXY", "https_proxy"]) {
+        let proxy = Proxy::https(&url).map_err(|e| format!("Invalid HTTPS proxy {}: {}", url, e))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy));
+    }
+    for certificate in root_certificates(&network.ca_certs)? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+pub fn build_client() -> Result<Client, String> {
+    let network = Config::load()?.network;
+    client_with(&network, network.connect_timeout, network.timeout)
+}
+
+pub fn build_llm_client() -> Result<Client, String> {
+    let network = Config::load()?.network;
+    client_with(&network, network.llm_connect_timeout, network.llm_timeout)
+}
+
+pub fn run(options: RunOptions) -> Result<(), RasError> {
+    pipeline::execute(options)
+}
@@ -0,0 +1,159 @@
+use crate::error::RasError;
+use chrono::Local;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static FORCE: AtomicBool = AtomicBool::new(false);
+
+pub struct RunLock {
+    path: PathBuf,
+}
+
+pub fn lock_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("run.lock"))
+}
+
+pub fn force() {
+    FORCE.store(true, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+fn is_running(pid: i32) -> Option<bool> {
+    Some(pid > 0 && (unsafe { libc::kill(pid, 0) } == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)))
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: i32) -> Option<bool> {
+    None
+}
+
+impl RunLock {
+    pub fn acquire(path: &Path) -> Result<RunLock, String> {
+        RunLock::acquire_with(path, FORCE.load(Ordering::SeqCst))
+    }
+
+    pub fn acquire_breaking_stale(path: &Path) -> Result<RunLock, String> {
+        RunLock::acquire_with(path, true)
+    }
+
+    fn acquire_with(path: &Path, break_stale: bool) -> Result<RunLock, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let _guard = FileLock::exclusive(&path.with_extension("lock.guard"))?;
+        let tmp = path.with_extension(format!("lock.{}", std::process::id()));
+        fs::write(&tmp, format!("{}\n{}\n", std::process::id(), Local::now().to_rfc3339()))
+            .map_err(|e| format!("Failed to create lock file {}: {}", tmp.display(), e))?;
+        let result = loop {
+            match fs::hard_link(&tmp, path) {
+                Ok(()) => break Ok(RunLock { path: path.to_path_buf() }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let owner = fs::read_to_string(path).unwrap_or_default();
+                    let mut lines = owner.lines();
+                    let pid = lines.next().unwrap_or_default().trim().to_string();
+                    let since = lines.next().map(|s| format!(", started {}", s.trim())).unwrap_or_default();
+                    let running = pid.parse().map_or(Some(false), is_running);
+                    if running == Some(true) {
+                        break Err(format!("Another run is in progress (pid {}{}), lock file: {}", pid, since, path.display()));
+                    }
+                    if running.is_none() && !FORCE.load(Ordering::SeqCst) {
+                        break Err(format!(
+                            "Found a lock from pid {}{}, rerun with --force to remove it if that run has finished: {}",
+                            if pid.is_empty() { "unknown" } else { &pid },
+                            since,
+                            path.display()
+                        ));
+                    }
+                    if !break_stale {
+                        break Err(format!(
+                            "Found a stale lock from pid {}{} that is no longer running, rerun with --force to remove it: {}",
+                            if pid.is_empty() { "unknown" } else { &pid },
+                            since,
+                            path.display()
+                        ));
+                    }
+                    println!("Removing stale lock from pid {}", pid);
+                    if let Err(e) = fs::remove_file(path) {
+                        break Err(format!("Failed to remove stale lock {}: {}", path.display(), e));
+                    }
+                }
+                Err(e) => break Err(format!("Failed to create lock file {}: {}", path.display(), e)),
+            }
+        };
+        let _ = fs::remove_file(&tmp);
+        result
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    pub fn exclusive(path: &Path) -> Result<FileLock, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|e| format!("Failed to open lock file {}: {}", path.display(), e))?;
+        file.lock().map_err(|e| format!("Failed to lock {}: {}", path.display(), e))?;
+        Ok(FileLock { _file: file })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ras-lock-{}-{}", std::process::id(), name)).join("run.lock")
+    }
+
+    #[test]
+    fn refuses_a_second_run_while_held() {
+        let path = lock_file("held");
+        let lock = RunLock::acquire_with(&path, false).unwrap();
+        let owner = fs::read_to_string(&path).unwrap();
+        assert_eq!(owner.lines().next(), Some(std::process::id().to_string().as_str()));
+        let error = RunLock::acquire_with(&path, true).err().unwrap();
+        assert!(error.starts_with("Another run is in progress"), "{}", error);
+        drop(lock);
+        assert!(!path.exists());
+        assert!(RunLock::acquire_with(&path, false).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detects_and_breaks_stale_locks() {
+        let path = lock_file("stale");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "999999999\n2025-03-01T07:00:00+00:00\n").unwrap();
+        let error = RunLock::acquire_with(&path, false).err().unwrap();
+        assert!(error.starts_with("Found a stale lock from pid 999999999, started 2025-03-01T07:00:00+00:00"), "{}", error);
+        assert!(path.exists());
+
+        let _lock = RunLock::acquire_breaking_stale(&path).unwrap();
+        let owner = fs::read_to_string(&path).unwrap();
+        assert_eq!(owner.lines().next(), Some(std::process::id().to_string().as_str()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn treats_unreadable_locks_as_stale() {
+        let path = lock_file("garbage");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not a pid").unwrap();
+        let error = RunLock::acquire_with(&path, false).err().unwrap();
+        assert!(error.starts_with("Found a stale lock from pid not a pid"), "{}", error);
+        assert!(RunLock::acquire_with(&path, true).is_ok());
+    }
+}
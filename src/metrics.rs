@@ -0,0 +1,118 @@
+use crate::runs::Run;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+const BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+const METRICS: &[(&str, &str, &str)] = &[
+    ("ras_runs_total", "counter", "Scheduled pipeline runs by status."),
+    ("ras_last_run_timestamp_seconds", "gauge", "Unix time the last scheduled run finished."),
+    ("ras_last_success_timestamp_seconds", "gauge", "Unix time the last successful run finished."),
+    ("ras_papers_fetched_total", "counter", "Papers returned by the configured sources."),
+    ("ras_papers_total", "counter", "Processed papers by outcome."),
+    ("ras_download_seconds", "histogram", "PDF download duration per paper."),
+    ("ras_extraction_seconds", "histogram", "PDF text extraction duration per paper."),
+    ("ras_llm_request_seconds", "histogram", "LLM request latency by provider."),
+    ("ras_llm_tokens_total", "counter", "LLM tokens consumed by pass and type."),
+    ("ras_llm_cost_dollars_total", "counter", "Estimated LLM cost in US dollars."),
+];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    values: BTreeMap<(&'static str, String), f64>,
+    histograms: BTreeMap<(&'static str, String), Histogram>,
+}
+
+static REGISTRY: Lazy<Mutex<Registry>> = Lazy::new(|| Mutex::new(Registry::default()));
+
+pub fn add(name: &'static str, labels: &str, value: f64) {
+    *REGISTRY.lock().unwrap().values.entry((name, labels.to_string())).or_default() += value;
+}
+
+pub fn set(name: &'static str, labels: &str, value: f64) {
+    REGISTRY.lock().unwrap().values.insert((name, labels.to_string()), value);
+}
+
+pub fn observe(name: &'static str, labels: &str, value: f64) {
+    let mut registry = REGISTRY.lock().unwrap();
+    let histogram = registry.histograms.entry((name, labels.to_string())).or_insert_with(|| Histogram { buckets: vec![0; BUCKETS.len()], ..Default::default() });
+    for (count, bound) in histogram.buckets.iter_mut().zip(BUCKETS) {
+        if value <= *bound {
+            *count += 1;
+        }
+    }
+    histogram.sum += value;
+    histogram.count += 1;
+}
+
+pub fn record_run(run: &Run) {
+    add("ras_papers_fetched_total", "", run.fetched as f64);
+    for paper in &run.papers {
+        add("ras_papers_total", &format!("outcome=\"{}\"", paper.outcome), 1.0);
+        if let Some(seconds) = paper.stages.get("download") {
+            observe("ras_download_seconds", "", *seconds);
+        }
+        if let Some(seconds) = paper.stages.get("extract") {
+            observe("ras_extraction_seconds", "", *seconds);
+        }
+    }
+    for (pass, usage) in &run.usage {
+        add("ras_llm_tokens_total", &format!("pass=\"{}\",type=\"prompt\"", pass), usage.prompt_tokens as f64);
+        add("ras_llm_tokens_total", &format!("pass=\"{}\",type=\"completion\"", pass), usage.completion_tokens as f64);
+    }
+    add("ras_llm_cost_dollars_total", "", run.cost);
+}
+
+fn series(name: &str, labels: &str, extra: &str) -> String {
+    match (labels.is_empty(), extra.is_empty()) {
+        (true, true) => name.to_string(),
+        (true, false) => format!("{}{{{}}}", name, extra),
+        (false, true) => format!("{}{{{}}}", name, labels),
+        (false, false) => format!("{}{{{},{}}}", name, labels, extra),
+    }
+}
+
+pub fn render() -> String {
+    let registry = REGISTRY.lock().unwrap();
+    let mut out = String::new();
+    for (name, kind, help) in METRICS {
+        out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+        for ((_, labels), value) in registry.values.range((*name, String::new())..).take_while(|((n, _), _)| n == name) {
+            out.push_str(&format!("{} {}\n", series(name, labels, ""), value));
+        }
+        for ((_, labels), histogram) in registry.histograms.range((*name, String::new())..).take_while(|((n, _), _)| n == name) {
+            for (count, bound) in histogram.buckets.iter().zip(BUCKETS) {
+                out.push_str(&format!("{} {}\n", series(&format!("{}_bucket", name), labels, &format!("le=\"{}\"", bound)), count));
+            }
+            out.push_str(&format!("{} {}\n", series(&format!("{}_bucket", name), labels, "le=\"+Inf\""), histogram.count));
+            out.push_str(&format!("{} {}\n", series(&format!("{}_sum", name), labels, ""), histogram.sum));
+            out.push_str(&format!("{} {}\n", series(&format!("{}_count", name), labels, ""), histogram.count));
+        }
+    }
+    out
+}
+
+pub fn serve(host: &str, port: u16) -> Result<(), String> {
+    let server = Server::http((host, port)).map_err(|e| format!("Failed to listen on {}:{}: {}", host, port, e))?;
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if request.url() == "/metrics" {
+                Response::from_string(render()).with_header(Header::from_bytes("Content-Type", "text/plain; version=0.0.4").unwrap())
+            } else {
+                Response::from_string("Not found").with_status_code(404)
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
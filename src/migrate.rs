@@ -0,0 +1,335 @@
+use crate::config::Config;
+use crate::db::{Db, PaperRecord};
+use crate::embeddings::{self, EmbeddingStore};
+use crate::pipeline::{file_stem, relative_link, summary_filename};
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static LINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\]\(([^)\s]+)\)").unwrap());
+
+struct Rename {
+    old: String,
+    new: String,
+}
+
+fn split(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+fn target(renames: &[&Rename], name: &str) -> Option<String> {
+    renames
+        .iter()
+        .filter(|r| name.strip_prefix(split(&r.old).1).is_some_and(|rest| rest.starts_with(['-', '.'])))
+        .max_by_key(|r| r.old.len())
+        .map(|r| format!("{}{}", r.new, &name[split(&r.old).1.len()..]))
+}
+
+fn plan_moves(dir: &Path, renames: &[Rename]) -> BTreeMap<String, String> {
+    let mut by_parent: BTreeMap<&str, Vec<&Rename>> = BTreeMap::new();
+    for rename in renames {
+        by_parent.entry(split(&rename.old).0).or_default().push(rename);
+    }
+    let mut moves = BTreeMap::new();
+    for (parent, renames) in by_parent {
+        for entry in fs::read_dir(dir.join(parent)).into_iter().flatten().flatten() {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if let Some(target) = target(&renames, &name) {
+                let old = if parent.is_empty() { name } else { format!("{}/{}", parent, name) };
+                moves.insert(old, target);
+            }
+        }
+    }
+    moves
+}
+
+fn move_files(dir: &Path, moves: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let mut moved = BTreeMap::new();
+    for (old, new) in moves {
+        let target = dir.join(new);
+        if target.exists() {
+            println!("  Skipping {}: {} already exists", old, new);
+            continue;
+        }
+        let result = match target.parent() {
+            Some(parent) => fs::create_dir_all(parent).and_then(|_| fs::rename(dir.join(old), &target)),
+            None => fs::rename(dir.join(old), &target),
+        };
+        match result {
+            Ok(()) => {
+                println!("  {} -> {}", old, new);
+                moved.insert(old.clone(), new.clone());
+            }
+            Err(e) => println!("  Failed to move {}: {}", old, e),
+        }
+    }
+    let parents: BTreeSet<&str> = moves.keys().map(|old| split(old).0).filter(|p| !p.is_empty()).collect();
+    for parent in parents.iter().rev() {
+        let mut dir_path = parent.to_string();
+        while fs::remove_dir(dir.join(&dir_path)).is_ok() {
+            match dir_path.rsplit_once('/') {
+                Some((up, _)) => dir_path = up.to_string(),
+                None => break,
+            }
+        }
+    }
+    moved
+}
+
+fn markdown_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            markdown_files(&path, files);
+        } else if path.extension().is_some_and(|e| e == "md") {
+            files.push(path);
+        }
+    }
+}
+
+fn moved_path(moves: &BTreeMap<String, String>, path: &str) -> Option<String> {
+    if let Some(new) = moves.get(path) {
+        return Some(new.clone());
+    }
+    moves
+        .iter()
+        .find_map(|(old, new)| path.strip_prefix(old.as_str()).filter(|rest| rest.starts_with('/')).map(|rest| format!("{}{}", new, rest)))
+}
+
+fn resolve(file: &str, link: &str) -> Option<String> {
+    let mut parts: Vec<&str> = split(file).0.split('/').filter(|p| !p.is_empty()).collect();
+    for part in link.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn rewrite_links(ras_dir: &Path, moves: &BTreeMap<String, String>) -> usize {
+    let reverse: BTreeMap<String, String> = moves.iter().map(|(old, new)| (new.clone(), old.clone())).collect();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(ras_dir).into_iter().flatten().flatten() {
+        if entry.path().is_dir() && entry.file_name() != "papers" {
+            markdown_files(&entry.path(), &mut files);
+        }
+    }
+    let mut rewritten = 0;
+    for path in files {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(file) = path.strip_prefix(ras_dir).ok().and_then(|p| p.to_str()).map(|p| p.replace('\\', "/")) else {
+            continue;
+        };
+        let old_file = moved_path(&reverse, &file);
+        let updated = LINK_REGEX.replace_all(&content, |caps: &regex::Captures| {
+            let link = &caps[1];
+            if link.contains("://") || link.starts_with(['#', '/']) {
+                return caps[0].to_string();
+            }
+            let Some(linked) = resolve(old_file.as_deref().unwrap_or(&file), &link.replace("%20", " ")) else {
+                return caps[0].to_string();
+            };
+            let new_linked = moved_path(moves, &linked);
+            if old_file.is_none() && new_linked.is_none() {
+                return caps[0].to_string();
+            }
+            format!("]({})", relative_link(&file, new_linked.as_deref().unwrap_or(&linked)))
+        });
+        if updated != content && fs::write(&path, updated.as_ref()).is_ok() {
+            rewritten += 1;
+        }
+    }
+    rewritten
+}
+
+fn rename_pdfs(dir: &Path, renames: &[Rename]) -> BTreeMap<String, String> {
+    let renames: Vec<&Rename> = renames.iter().collect();
+    let moves: BTreeMap<String, String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter_map(|name| target(&renames, &name).map(|target| (name, target)))
+        .collect();
+    move_files(dir, &moves)
+}
+
+pub fn import_legacy(db: &mut Db, summary_dir: &Path, papers_dir: &Path) -> usize {
+    let known: BTreeSet<String> = db.papers.values().map(|r| r.summary_file.clone()).collect();
+    let mut imported = 0;
+    for entry in fs::read_dir(summary_dir).into_iter().flatten().flatten() {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let Some(stem) = name.strip_suffix("-summary.md") else {
+            continue;
+        };
+        if known.contains(&name) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let title = content.lines().find_map(|l| l.strip_prefix("# ")).map(str::trim);
+        let id = content.lines().find_map(|l| l.strip_prefix("**arXiv ID**:")).map(str::trim);
+        let (Some(title), Some(id)) = (title, id) else {
+            continue;
+        };
+        if id.is_empty() || db.papers.get(id).is_some_and(|r| !r.summary_file.is_empty()) {
+            continue;
+        }
+        let pdf_url = content.lines().find_map(|l| l.strip_prefix("**PDF**:")).map(str::trim).unwrap_or_default();
+        let paper = crate::arxiv::Paper {
+            id: id.to_string(),
+            title: title.to_string(),
+            pdf_url: pdf_url.to_string(),
+            category: String::new(),
+            scholar: None,
+            code: None,
+            sources: Vec::new(),
+        };
+        let status = if content.contains("\n## Error\n") { "error" } else { "summarized" };
+        db.upsert_paper(&paper, &name, status);
+        if let Some(record) = db.papers.get_mut(id) {
+            let pdf_file = format!("{}.pdf", stem);
+            if papers_dir.join(&pdf_file).exists() {
+                record.pdf_file = pdf_file;
+            }
+            if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                record.added_at = DateTime::<chrono::Utc>::from(modified).to_rfc3339();
+            }
+        }
+        imported += 1;
+    }
+    imported
+}
+
+fn added_at(record: &PaperRecord) -> DateTime<Local> {
+    DateTime::parse_from_rfc3339(&record.added_at).map(|t| t.with_timezone(&Local)).unwrap_or_else(|_| Local::now())
+}
+
+pub fn run() -> Result<(), String> {
+    let config = Config::load()?;
+    let ras_dir = crate::ras_dir()?;
+    let mut db = Db::load()?;
+    let imported = import_legacy(&mut db, &ras_dir.join("summary"), &ras_dir.join("papers"));
+    if imported > 0 {
+        println!("Imported {} summaries written before ras.json tracked them", imported);
+        db.save()?;
+    }
+
+    let mut summaries = Vec::new();
+    let mut pdfs = Vec::new();
+    let mut new_files = BTreeMap::new();
+    for record in db.papers.values().filter(|r| !r.summary_file.is_empty()) {
+        let paper = record.to_paper();
+        let added = added_at(record);
+        let new = summary_filename(&paper, &config.files, added);
+        if new != record.summary_file {
+            let strip = |file: &str| file.strip_suffix("-summary.md").unwrap_or(file).to_string();
+            summaries.push(Rename { old: strip(&record.summary_file), new: strip(&new) });
+        }
+        new_files.insert(record.id.clone(), new);
+        let stem = file_stem(&paper, &config.files, added);
+        if let Some(old) = record.pdf_file.strip_suffix(".pdf").filter(|old| *old != stem) {
+            pdfs.push(Rename { old: old.to_string(), new: stem });
+        }
+    }
+    if summaries.is_empty() && pdfs.is_empty() {
+        println!("All files already follow the pattern {} and layout {:?}", config.files.pattern, config.files.layout);
+        return Ok(());
+    }
+
+    println!("Moving files to the pattern {} and layout {:?}", config.files.pattern, config.files.layout);
+    let (summary_dir, papers_dir) = (ras_dir.join("summary"), ras_dir.join("papers"));
+    let moved = move_files(&summary_dir, &plan_moves(&summary_dir, &summaries));
+    let links: BTreeMap<String, String> = moved.iter().map(|(old, new)| (format!("summary/{}", old), format!("summary/{}", new))).collect();
+    let rewritten = rewrite_links(&ras_dir, &links);
+    let moved = moved.len() + rename_pdfs(&papers_dir, &pdfs).len();
+
+    let mut renamed_summaries = Vec::new();
+    for record in db.papers.values_mut() {
+        let Some(summary_file) = new_files.remove(&record.id) else {
+            continue;
+        };
+        if summary_file != record.summary_file && summary_dir.join(&summary_file).exists() {
+            renamed_summaries.push((record.summary_file.clone(), summary_file.clone()));
+            record.summary_file = summary_file;
+        }
+        let pdf_file = format!("{}.pdf", file_stem(&record.to_paper(), &config.files, added_at(record)));
+        if papers_dir.join(&pdf_file).exists() {
+            record.pdf_file = pdf_file;
+        }
+    }
+    db.save()?;
+
+    let path = embeddings::store_path()?;
+    let mut store = EmbeddingStore::load(&path);
+    for (old, new) in renamed_summaries {
+        if let Some(entry) = store.entries.remove(&old) {
+            store.entries.insert(new, entry);
+        }
+    }
+    store.save(&path)?;
+
+    println!("Moved {} files and updated links in {} files", moved, rewritten);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename(old: &str, new: &str) -> Rename {
+        Rename { old: old.to_string(), new: new.to_string() }
+    }
+
+    #[test]
+    fn renames_every_file_of_a_paper() {
+        let renames = [rename("Attention", "1706.03762-Attention")];
+        let renames: Vec<&Rename> = renames.iter().collect();
+        assert_eq!(target(&renames, "Attention-summary.md").as_deref(), Some("1706.03762-Attention-summary.md"));
+        assert_eq!(target(&renames, "Attention-summary.pt-BR.md").as_deref(), Some("1706.03762-Attention-summary.pt-BR.md"));
+        assert_eq!(target(&renames, "Attention-topics").as_deref(), Some("1706.03762-Attention-topics"));
+        assert_eq!(target(&renames, "Attention.pdf").as_deref(), Some("1706.03762-Attention.pdf"));
+        assert_eq!(target(&renames, "Attention_Again-summary.md"), None);
+        assert_eq!(target(&renames, "Attentions-summary.md"), None);
+    }
+
+    #[test]
+    fn prefers_the_longest_matching_name() {
+        let renames = [rename("Sparse", "1-Sparse"), rename("Sparse-Attention", "2-Sparse-Attention")];
+        let renames: Vec<&Rename> = renames.iter().collect();
+        assert_eq!(target(&renames, "Sparse-Attention-summary.md").as_deref(), Some("2-Sparse-Attention-summary.md"));
+        assert_eq!(target(&renames, "Sparse-summary.md").as_deref(), Some("1-Sparse-summary.md"));
+    }
+
+    #[test]
+    fn keeps_the_directory_of_the_new_name() {
+        let renames = [rename("2025/Old", "2025/03/cs.LG/1-New")];
+        let renames: Vec<&Rename> = renames.iter().collect();
+        assert_eq!(target(&renames, "Old-summary.md").as_deref(), Some("2025/03/cs.LG/1-New-summary.md"));
+    }
+
+    #[test]
+    fn resolves_links_against_the_file() {
+        assert_eq!(resolve("summary/a-summary.md", "b-summary.md").as_deref(), Some("summary/b-summary.md"));
+        assert_eq!(resolve("summary/2025/03/a-summary.md", "../../2024/./b-summary.md").as_deref(), Some("summary/2024/b-summary.md"));
+        assert_eq!(resolve("summary/a-topics/1.md", "../a-summary.md").as_deref(), Some("summary/a-summary.md"));
+        assert_eq!(resolve("a-summary.md", "../b-summary.md"), None);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::config::NotificationsConfig;
+use crate::relevance::Profile;
+use crate::summary::SummaryInfo;
+use reqwest::blocking::Client;
+use serde_json::json;
+
+const DISCORD_MAX_CHARS: usize = 2000;
+
+pub struct RunReport<'a> {
+    pub fetched: usize,
+    pub processed: usize,
+    pub failed: usize,
+    pub summaries: &'a [SummaryInfo],
+}
+
+pub type WatchAlert<'a> = (&'a SummaryInfo, &'a [String]);
+
+pub enum Style {
+    Slack,
+    Discord,
+}
+
+pub fn notify_run(config: &NotificationsConfig, client: &Client, report: &RunReport, style: Style) {
+    if report.summaries.is_empty() && !config.notify_empty {
+        return;
+    }
+    let top = top_by_relevance(report.summaries, config.top_n);
+    let message = format_run(report, &top, &style);
+    send(config, client, style, message);
+}
+
+pub fn notify_failure(config: &NotificationsConfig, client: &Client, error: &str, style: Style) {
+    send(config, client, style, format!(":rotating_light: ras run failed: {}", error));
+}
+
+pub fn notify_watchlist(config: &NotificationsConfig, client: &Client, alerts: &[WatchAlert], style: Style) {
+    let message = format_watchlist(alerts, &style);
+    send(config, client, style, message);
+}
+
+fn send(config: &NotificationsConfig, client: &Client, style: Style, message: String) {
+    match style {
+        Style::Slack if !config.slack_webhook.is_empty() => {
+            let payload = json!({ "text": message });
+            match post(client, &config.slack_webhook, &payload) {
+                Ok(()) => println!("Slack notification sent"),
+                Err(e) => println!("Failed to send Slack notification: {}", e),
+            }
+        }
+        Style::Discord if !config.discord_webhook.is_empty() => {
+            let content: String = message.chars().take(DISCORD_MAX_CHARS).collect();
+            let payload = json!({ "content": content });
+            match post(client, &config.discord_webhook, &payload) {
+                Ok(()) => println!("Discord notification sent"),
+                Err(e) => println!("Failed to send Discord notification: {}", e),
+            }
+        }
+        _ => {}
+    }
+}
+
+fn post(client: &Client, url: &str, payload: &serde_json::Value) -> Result<(), String> {
+    let response = client.post(url).json(payload).send().map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(format!("Webhook error {}: {}", status, body));
+    }
+    Ok(())
+}
+
+fn top_by_relevance(summaries: &[SummaryInfo], top_n: usize) -> Vec<&SummaryInfo> {
+    let profile = Profile::load();
+    let mut scored: Vec<(f32, &SummaryInfo)> = summaries.iter().map(|s| (profile.keyword_score(&s.title), s)).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(top_n).map(|(_, s)| s).collect()
+}
+
+fn format_run(report: &RunReport, top: &[&SummaryInfo], style: &Style) -> String {
+    let mut text = format!(
+        "ras run finished: {} papers fetched, {} processed, {} new summaries, {} failed",
+        report.fetched,
+        report.processed,
+        report.summaries.len(),
+        report.failed
+    );
+    if !top.is_empty() {
+        text.push_str(&format!("\nTop {}:", top.len()));
+    }
+    for summary in top {
+        let link = match style {
+            Style::Slack => format!("<{}|{}>", summary.abs_url(), summary.title),
+            Style::Discord => format!("[{}](<{}>)", summary.title, summary.abs_url()),
+        };
+        text.push_str(&format!("\n• {}: {}", link, summary.tldr));
+    }
+    text
+}
+
+fn format_watchlist(alerts: &[WatchAlert], style: &Style) -> String {
+    let mut text = format!(":eyes: {} new papers from your author watchlist", alerts.len());
+    for (summary, authors) in alerts {
+        let link = match style {
+            Style::Slack => format!("<{}|{}>", summary.abs_url(), summary.title),
+            Style::Discord => format!("[{}](<{}>)", summary.title, summary.abs_url()),
+        };
+        text.push_str(&format!("\n• {} ({}): {}", link, authors.join(", "), summary.tldr));
+    }
+    text
+}
@@ -0,0 +1,179 @@
+use crate::cli::Args;
+use crate::config::{Config, ObsidianConfig};
+use crate::db::{Db, PaperRecord};
+use crate::embeddings::{self, EmbeddingStore};
+use crate::references;
+use crate::summary::{strip_frontmatter, tldr};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static SUMMARY_LINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+-summary\.md)\)").unwrap());
+
+const MIN_SIMILARITY: f32 = 0.8;
+
+fn vault_dir(config: &ObsidianConfig) -> PathBuf {
+    let vault = match config.vault.strip_prefix("~/") {
+        Some(rest) => format!("{}/{}", std::env::var("HOME").unwrap_or_default(), rest),
+        None => config.vault.clone(),
+    };
+    PathBuf::from(vault).join(&config.folder)
+}
+
+fn clean_name(text: &str) -> String {
+    let name: String = text.chars().filter(|c| !matches!(c, '*' | '"' | '\\' | '/' | '<' | '>' | ':' | '|' | '?' | '#' | '^' | '[' | ']')).collect();
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn tag(text: &str) -> String {
+    text.chars().map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '/') { c } else { '-' }).collect()
+}
+
+fn note_names(records: &[&PaperRecord]) -> HashMap<String, String> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut used: HashMap<String, usize> = HashMap::new();
+    for record in records {
+        let mut name = clean_name(&record.title);
+        if name.is_empty() {
+            name = clean_name(&record.id.replace(':', " "));
+        }
+        let count = used.entry(name.to_lowercase()).or_default();
+        *count += 1;
+        if *count > 1 {
+            name = format!("{} ({})", name, clean_name(&record.id.replace(':', " ")));
+        }
+        names.insert(record.id.clone(), name);
+    }
+    names
+}
+
+fn similar(store: &EmbeddingStore, record: &PaperRecord, files: &HashMap<&str, &str>, limit: usize) -> Vec<String> {
+    let Some(entry) = store.entries.get(&record.summary_file) else {
+        return Vec::new();
+    };
+    store
+        .search(&entry.vector, limit + 1)
+        .into_iter()
+        .filter(|(file, score)| *file != record.summary_file && *score >= MIN_SIMILARITY)
+        .filter_map(|(file, _)| files.get(file.as_str()).map(|id| id.to_string()))
+        .take(limit)
+        .collect()
+}
+
+fn frontmatter(record: &PaperRecord) -> String {
+    let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+    let mut tags = vec!["ras".to_string()];
+    tags.extend(record.tags.iter().chain([&record.category]).filter(|t| !t.is_empty()).map(|t| tag(t)));
+    format!(
+        "---\ntitle: {}\narxiv: {}\ncategory: {}\ntags: [{}]\naliases: [{}]\nadded: {}\nstatus: {}\nstarred: {}\n---\n\n",
+        quote(&record.title),
+        quote(&record.id),
+        quote(&record.category),
+        tags.join(", "),
+        quote(&record.id),
+        record.added_at.get(..10).unwrap_or_default(),
+        if record.is_read() { "read" } else { "unread" },
+        record.starred
+    )
+}
+
+fn write_note(path: &Path, content: &str) -> Result<bool, String> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+pub fn sync(config: &ObsidianConfig, db: &Db) -> Result<usize, String> {
+    let dir = vault_dir(config);
+    fs::create_dir_all(dir.join("Topics")).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let records: Vec<&PaperRecord> = db.papers.values().filter(|r| r.status == "summarized" && !r.summary_file.is_empty()).collect();
+    let names = note_names(&records);
+    let link = |id: &str| names.get(id).map(|name| format!("[[{}]]", name));
+    let files: HashMap<&str, &str> = records.iter().map(|r| (r.summary_file.as_str(), r.id.as_str())).collect();
+    let basenames: HashMap<&str, &str> = records.iter().map(|r| (r.summary_file.rsplit('/').next().unwrap_or(&r.summary_file), r.id.as_str())).collect();
+    let store = EmbeddingStore::load(&embeddings::store_path()?);
+
+    let mut cites: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut cited_by: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for record in &records {
+        for (_, file) in references::in_library(db, &record.id, &record.references) {
+            if let Some(id) = files.get(file) {
+                cites.entry(&record.id).or_default().push(id.to_string());
+                cited_by.entry(id.to_string()).or_default().push(&record.id);
+            }
+        }
+    }
+
+    let mut topics: BTreeMap<String, Vec<&PaperRecord>> = BTreeMap::new();
+    let mut written = 0;
+    for record in &records {
+        let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+        let body = SUMMARY_LINK_REGEX.replace_all(strip_frontmatter(&content), |captures: &regex::Captures| {
+            let file = captures[2].rsplit('/').next().unwrap_or(&captures[2]).replace("%20", " ");
+            basenames.get(file.as_str()).and_then(|id| link(id)).unwrap_or_else(|| captures[0].to_string())
+        });
+
+        let mut links: Vec<(String, Vec<String>)> = Vec::new();
+        for relation in &record.related {
+            links.push((relation.kind.replace('-', " "), link(&relation.id).into_iter().collect()));
+        }
+        let join = |ids: &[String]| ids.iter().filter_map(|id| link(id)).collect::<Vec<_>>();
+        links.push(("cites".to_string(), join(cites.get(record.id.as_str()).map(Vec::as_slice).unwrap_or_default())));
+        let citing: Vec<String> = cited_by.get(&record.id).map(|ids| ids.iter().map(|id| id.to_string()).collect()).unwrap_or_default();
+        links.push(("cited by".to_string(), join(&citing)));
+        links.push(("similar".to_string(), join(&similar(&store, record, &files, config.similar))));
+        links.retain(|(_, notes)| !notes.is_empty());
+
+        let mut note = format!("{}{}", frontmatter(record), body.trim_end());
+        if !links.is_empty() {
+            note.push_str("\n\n## Related Papers\n\n");
+            for (kind, notes) in links {
+                note.push_str(&format!("- {}: {}\n", kind, notes.join(", ")));
+            }
+        }
+        note.push('\n');
+        if write_note(&dir.join(format!("{}.md", names[&record.id])), &note)? {
+            written += 1;
+        }
+
+        let mut record_topics: Vec<String> = record.tags.clone();
+        if record_topics.is_empty() {
+            record_topics.push(if record.category.is_empty() { "Uncategorized".to_string() } else { record.category.clone() });
+        }
+        for topic in record_topics {
+            topics.entry(topic).or_default().push(record);
+        }
+    }
+
+    let mut index = "# Topics\n\n".to_string();
+    for (topic, papers) in &topics {
+        let name = clean_name(topic);
+        index.push_str(&format!("- [[{}]] ({} papers)\n", name, papers.len()));
+        let mut moc = format!("---\ntags: [ras, moc]\n---\n\n# {}\n\n", topic);
+        for record in papers {
+            let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+            moc.push_str(&format!("- [[{}]] — {}\n", names[&record.id], tldr(&content)));
+        }
+        write_note(&dir.join("Topics").join(format!("{}.md", name)), &moc)?;
+    }
+    write_note(&dir.join("Topics.md"), &index)?;
+    Ok(written)
+}
+
+pub fn export(args: &Args) -> Result<(), String> {
+    let mut config = Config::load()?.obsidian;
+    if let Some(vault) = args.value("vault") {
+        config.vault = vault.to_string();
+    }
+    if config.vault.is_empty() {
+        return Err("Usage: export obsidian --vault <path>, or set vault in the [obsidian] config section".to_string());
+    }
+    let written = sync(&config, &Db::load()?)?;
+    println!("Updated {} notes in {}", written, vault_dir(&config).display());
+    Ok(())
+}
@@ -0,0 +1,687 @@
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use crate::cache;
+use crate::cancel::{CANCELLED, CancellationToken};
+use crate::config::{Config, LlmConfig, ProviderConfig};
+use crate::error::RasError;
+use crate::metrics;
+use crate::retry;
+use crate::semaphore::Semaphore;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use md5::{Digest, Md5};
+use serde_json::{Value, json};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const CHAT_MODEL: &str = "gpt-4o-mini";
+pub const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+const MOCK_KEY: &str = "mock";
+const MOCK_DIMENSIONS: usize = 256;
+
+static MOCK: AtomicBool = AtomicBool::new(false);
+static ECHO: AtomicBool = AtomicBool::new(false);
+static LLM_CLIENT: Lazy<Option<Client>> = Lazy::new(|| crate::build_llm_client().ok());
+static LLM_CALLS: Semaphore = Semaphore::new(10);
+static USAGE: Mutex<BTreeMap<&'static str, Usage>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    static PASS: Cell<&'static str> = const { Cell::new("other") };
+    static MODEL: RefCell<String> = const { RefCell::new(String::new()) };
+    static CANCEL: RefCell<CancellationToken> = RefCell::new(CancellationToken::new());
+}
+
+const CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
+const EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const OLLAMA_URL: &str = "http://localhost:11434/v1/chat/completions";
+const CONTINUE_PROMPT: &str = "Your previous answer was cut off. Continue exactly where it stops, without repeating anything.";
+
+struct Chain {
+    providers: Vec<ProviderConfig>,
+    max_failures: u32,
+    stream: bool,
+    active: usize,
+    failures: u32,
+    spent: Vec<f64>,
+}
+
+impl Chain {
+    fn new(config: &LlmConfig) -> Chain {
+        let providers = if config.providers.is_empty() { vec![ProviderConfig::default()] } else { config.providers.clone() };
+        Chain { spent: vec![0.0; providers.len()], providers, max_failures: config.max_failures.max(1), stream: config.stream, active: 0, failures: 0 }
+    }
+
+    fn fall_back(&mut self, index: usize, reason: &str) {
+        if index != self.active || index + 1 >= self.providers.len() {
+            return;
+        }
+        self.active = index + 1;
+        self.failures = 0;
+        let (from, to) = (&self.providers[index], &self.providers[index + 1]);
+        println!("  LLM provider {} {} {}, falling back to {} {}", from.provider, from.model, reason, to.provider, to.model);
+    }
+}
+
+// Keeps the HTTP status of a failed call so callers can tell throttling from other failures
+struct ApiError {
+    status: Option<StatusCode>,
+    message: String,
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        ApiError { status: None, message }
+    }
+}
+
+impl From<ApiError> for String {
+    fn from(error: ApiError) -> Self {
+        error.message
+    }
+}
+
+static CHAIN: Lazy<Mutex<Chain>> = Lazy::new(|| Mutex::new(Chain::new(&Config::load().map(|c| c.llm).unwrap_or_default())));
+
+#[derive(Serialize)]
+struct OpenAIRequest {
+    model: String,
+    messages: Vec<Message>,
+    max_completion_tokens: u32,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Message {
+    pub role: String,
+    pub content: String,
+}
+
+impl Message {
+    pub fn new(role: &str, content: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenAIResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Usage,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub cost: f64,
+}
+
+impl Usage {
+    pub fn priced(prompt_tokens: u64, completion_tokens: u64, (input_price, output_price): (f64, f64)) -> Usage {
+        let cost = (prompt_tokens as f64 * input_price + completion_tokens as f64 * output_price) / 1_000_000.0;
+        Usage { prompt_tokens, completion_tokens, cost }
+    }
+
+    pub fn add(&mut self, other: Usage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cost += other.cost;
+    }
+}
+
+pub fn active_prices() -> (f64, f64) {
+    let chain = CHAIN.lock().unwrap();
+    chain.providers.get(chain.active).and_then(ProviderConfig::prices).unwrap_or_default()
+}
+
+pub fn in_pass<T>(pass: &'static str, f: impl FnOnce() -> T) -> T {
+    let previous = PASS.replace(pass);
+    let result = f();
+    PASS.set(previous);
+    result
+}
+
+pub fn with_cancel<T>(cancel: &CancellationToken, f: impl FnOnce() -> T) -> T {
+    let previous = CANCEL.replace(cancel.clone());
+    let result = f();
+    CANCEL.set(previous);
+    result
+}
+
+pub fn total_usage() -> Usage {
+    let mut total = Usage::default();
+    for usage in USAGE.lock().unwrap().values() {
+        total.add(*usage);
+    }
+    total
+}
+
+pub fn record_usage(pass: &'static str, usage: Usage) {
+    USAGE.lock().unwrap().entry(pass).or_default().add(usage);
+}
+
+pub fn take_usage() -> BTreeMap<&'static str, Usage> {
+    std::mem::take(&mut *USAGE.lock().unwrap())
+}
+
+#[derive(Serialize)]
+struct AnthropicRequest {
+    model: String,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    system: String,
+    messages: Vec<Message>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+#[derive(Deserialize)]
+struct AnthropicContent {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize, Default)]
+struct AnthropicUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+pub fn use_mock() {
+    MOCK.store(true, Ordering::SeqCst);
+}
+
+pub fn set_concurrency(limit: usize) {
+    LLM_CALLS.set_limit(limit);
+}
+
+fn is_mock() -> bool {
+    MOCK.load(Ordering::SeqCst)
+}
+
+fn mock_reply(messages: &[Message]) -> String {
+    let prompt = messages.last().map(|m| m.content.as_str()).unwrap_or("");
+    let title = prompt
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Title: "))
+        .unwrap_or("this paper");
+    let numbers: Vec<String> = prompt
+        .split(". ")
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| s.chars().any(|c| c.is_ascii_digit()) && s.len() < 300 && !s.contains("**"))
+        .take(3)
+        .collect();
+    format!(
+        "1. **Overview**: Offline demo summary of \"{}\". With an OpenAI API key this section describes the paper's core mission and contributions.\n\
+         2. **Key Results**:\n{}\n\
+         3. **Methodology**: Written by the demo model, which only quotes sentences from the paper text.\n\
+         4. **Critical Insights**: Configure `OPEN_AI_API_KEY` and run `ras` to get real summaries.",
+        title,
+        numbers.iter().map(|n| format!("   - {}", n)).collect::<Vec<_>>().join("\n")
+    )
+}
+
+fn mock_embedding(input: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; MOCK_DIMENSIONS];
+    for word in input.split(|c: char| !c.is_alphanumeric()).filter(|w| w.len() > 2) {
+        let hash = word.to_lowercase().bytes().fold(2166136261u32, |h, b| (h ^ b as u32).wrapping_mul(16777619));
+        vector[hash as usize % MOCK_DIMENSIONS] += 1.0;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt().max(1.0);
+    vector.iter().map(|v| v / norm).collect()
+}
+
+pub fn set_providers(config: &LlmConfig) {
+    *CHAIN.lock().unwrap() = Chain::new(config);
+}
+
+pub fn set_echo(echo: bool) {
+    ECHO.store(echo, Ordering::SeqCst);
+}
+
+pub fn set_last_model(model: &str) {
+    MODEL.set(model.to_string());
+}
+
+pub fn last_model() -> String {
+    MODEL.with_borrow(|model| model.clone())
+}
+
+pub fn api_key() -> Option<String> {
+    if is_mock() {
+        return Some(MOCK_KEY.to_string());
+    }
+    crate::credentials::get("OPEN_AI_API_KEY").filter(|key| !key.is_empty())
+}
+
+// For commands that only talk to OpenAI, such as embeddings and batches
+pub fn require_api_key() -> Result<String, String> {
+    api_key().ok_or_else(|| "OPEN_AI_API_KEY not set: export it or run `ras auth set openai`".to_string())
+}
+
+// Chat goes through the provider chain, which only needs the key when it has an OpenAI provider
+pub fn chat_key() -> Result<String, String> {
+    if CHAIN.lock().unwrap().providers.iter().any(|p| p.provider == "openai") {
+        return require_api_key();
+    }
+    Ok(api_key().unwrap_or_default())
+}
+
+pub fn chat(client: &Client, api_key: &str, prompt: &str, max_tokens: u32) -> Result<String, String> {
+    chat_messages(client, api_key, vec![Message::new("user", prompt)], max_tokens)
+}
+
+pub fn chat_with_usage(client: &Client, api_key: &str, prompt: &str, max_tokens: u32) -> Result<(String, Usage), String> {
+    chat_request(client, api_key, vec![Message::new("user", prompt)], max_tokens)
+}
+
+pub fn chat_messages(client: &Client, api_key: &str, messages: Vec<Message>, max_tokens: u32) -> Result<String, String> {
+    chat_request(client, api_key, messages, max_tokens).map(|(content, _)| content)
+}
+
+fn chat_request(client: &Client, api_key: &str, messages: Vec<Message>, max_tokens: u32) -> Result<(String, Usage), String> {
+    if is_mock() {
+        MODEL.set("mock".to_string());
+        return Ok((mock_reply(&messages), Usage::default()));
+    }
+    let (active, providers) = {
+        let chain = CHAIN.lock().unwrap();
+        (chain.active, chain.providers.clone())
+    };
+    let cacheable = matches!(PASS.get(), "draft" | "refine");
+    let cancel = CANCEL.with_borrow(|c| c.clone());
+    let mut last_error = String::from("No LLM provider configured");
+    for (index, provider) in providers.iter().enumerate().skip(active) {
+        let model = format!("{}/{}", provider.provider, provider.model);
+        let cache_key = cache::completion_key(&model, &messages, max_tokens);
+        if cacheable && let Some(content) = cache::completion(&cache_key) {
+            MODEL.set(model);
+            return Ok((content, Usage::default()));
+        }
+        let over_budget = {
+            let chain = CHAIN.lock().unwrap();
+            provider.max_cost > 0.0 && chain.spent[index] >= provider.max_cost
+        };
+        if over_budget {
+            CHAIN.lock().unwrap().fall_back(index, &format!("reached its cost limit of ${:.2}", provider.max_cost));
+            last_error = format!("{} {} reached its cost limit", provider.provider, provider.model);
+            continue;
+        }
+        let started = Instant::now();
+        cancel.check()?;
+        let result = provider_chat(client, api_key, provider, &messages, max_tokens, &cancel);
+        metrics::observe("ras_llm_request_seconds", &format!("provider=\"{}\"", provider.provider), started.elapsed().as_secs_f64());
+        match result {
+            Ok((content, usage)) => {
+                let usage = Usage::priced(usage.prompt_tokens, usage.completion_tokens, provider.prices().unwrap_or_default());
+                let mut chain = CHAIN.lock().unwrap();
+                if index == chain.active {
+                    chain.failures = 0;
+                }
+                chain.spent[index] += usage.cost;
+                drop(chain);
+                USAGE.lock().unwrap().entry(PASS.get()).or_default().add(usage);
+                if cacheable {
+                    cache::store_completion(&cache_key, &model, &content, usage);
+                }
+                MODEL.set(model);
+                return Ok((content, usage));
+            }
+            Err(e) if e.message == CANCELLED => return Err(e.message),
+            Err(e) => {
+                let mut chain = CHAIN.lock().unwrap();
+                if index == chain.active {
+                    chain.failures += 1;
+                    if e.status.is_some_and(retry::is_throttled) {
+                        chain.fall_back(index, "is rate limited");
+                    } else if chain.failures >= chain.max_failures {
+                        let reason = format!("failed {} times in a row", chain.failures);
+                        chain.fall_back(index, &reason);
+                    }
+                }
+                last_error = format!("{} {}: {}", provider.provider, provider.model, e.message);
+            }
+        }
+    }
+    Err(last_error)
+}
+
+fn provider_chat(
+    client: &Client,
+    api_key: &str,
+    provider: &ProviderConfig,
+    messages: &[Message],
+    max_tokens: u32,
+    cancel: &CancellationToken,
+) -> Result<(String, Usage), ApiError> {
+    let key = if provider.api_key_env.is_empty() {
+        match provider.provider.as_str() {
+            "openai" => api_key.to_string(),
+            "anthropic" => crate::credentials::get("ANTHROPIC_API_KEY").unwrap_or_default(),
+            _ => String::new(),
+        }
+    } else {
+        crate::credentials::get(&provider.api_key_env).unwrap_or_default()
+    };
+    let url = |default: &str| if provider.url.is_empty() { default.to_string() } else { provider.url.clone() };
+    let echo = ECHO.load(Ordering::SeqCst) && matches!(PASS.get(), "draft" | "refine");
+    if echo || CHAIN.lock().unwrap().stream {
+        return stream_chat(client, provider, &key, messages, max_tokens, echo, cancel);
+    }
+    match provider.provider.as_str() {
+        "openai" | "ollama" => {
+            let request = OpenAIRequest {
+                model: provider.model.clone(),
+                messages: messages.to_vec(),
+                max_completion_tokens: max_tokens,
+            };
+            let default_url = if provider.provider == "ollama" { OLLAMA_URL } else { CHAT_URL };
+            let headers = if key.is_empty() { Vec::new() } else { vec![("Authorization", format!("Bearer {}", key))] };
+            let api_response: OpenAIResponse = post(client, &headers, &url(default_url), &request)?;
+            match api_response.choices.into_iter().next() {
+                Some(choice) => Ok((choice.message.content, api_response.usage)),
+                None => Err("No response from API".to_string().into()),
+            }
+        }
+        "anthropic" => {
+            let system: Vec<&str> = messages.iter().filter(|m| m.role == "system").map(|m| m.content.as_str()).collect();
+            let request = AnthropicRequest {
+                model: provider.model.clone(),
+                max_tokens,
+                system: system.join("\n\n"),
+                messages: messages.iter().filter(|m| m.role != "system").cloned().collect(),
+            };
+            let headers = vec![("x-api-key", key), ("anthropic-version", ANTHROPIC_VERSION.to_string())];
+            let api_response: AnthropicResponse = post(client, &headers, &url(ANTHROPIC_URL), &request)?;
+            let text: String = api_response.content.into_iter().map(|c| c.text).collect();
+            if text.is_empty() {
+                return Err("No response from API".to_string().into());
+            }
+            Ok((text, Usage { prompt_tokens: api_response.usage.input_tokens, completion_tokens: api_response.usage.output_tokens, cost: 0.0 }))
+        }
+        other => Err(format!("Unknown LLM provider: {} (expected openai, anthropic or ollama)", other).into()),
+    }
+}
+
+pub fn embed(client: &Client, api_key: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    if is_mock() {
+        return Ok(inputs.iter().map(|i| mock_embedding(i)).collect());
+    }
+    let request = EmbeddingRequest {
+        model: EMBEDDING_MODEL,
+        input: inputs,
+    };
+
+    if api_key.is_empty() {
+        return Err("OPEN_AI_API_KEY not set: embeddings need an OpenAI key".to_string());
+    }
+    let headers = vec![("Authorization", format!("Bearer {}", api_key))];
+    let mut api_response: EmbeddingResponse = post(client, &headers, EMBEDDINGS_URL, &request)?;
+    if api_response.data.len() != inputs.len() {
+        return Err(format!("Expected {} embeddings, got {}", inputs.len(), api_response.data.len()));
+    }
+    api_response.data.sort_by_key(|d| d.index);
+    Ok(api_response.data.into_iter().map(|d| d.embedding).collect())
+}
+
+fn partial_path(provider: &ProviderConfig, messages: &[Message]) -> Result<PathBuf, RasError> {
+    let mut hasher = Md5::new();
+    hasher.update(format!("{}/{}", provider.provider, provider.model));
+    for message in messages {
+        hasher.update(&message.role);
+        hasher.update(&message.content);
+    }
+    Ok(crate::ras_dir()?.join("partial").join(format!("{:x}.txt", hasher.finalize())))
+}
+
+fn stream_request(provider: &ProviderConfig, key: &str, messages: Vec<Message>, max_tokens: u32) -> (String, Vec<(&'static str, String)>, Value) {
+    let url = |default: &str| if provider.url.is_empty() { default.to_string() } else { provider.url.clone() };
+    if provider.provider == "anthropic" {
+        let system: Vec<&str> = messages.iter().filter(|m| m.role == "system").map(|m| m.content.as_str()).collect();
+        let messages: Vec<&Message> = messages.iter().filter(|m| m.role != "system").collect();
+        let mut body = json!({"model": provider.model, "max_tokens": max_tokens, "messages": messages, "stream": true});
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+        let headers = vec![("x-api-key", key.to_string()), ("anthropic-version", ANTHROPIC_VERSION.to_string())];
+        return (url(ANTHROPIC_URL), headers, body);
+    }
+    let body = json!({
+        "model": provider.model,
+        "messages": messages,
+        "max_completion_tokens": max_tokens,
+        "stream": true,
+        "stream_options": {"include_usage": true},
+    });
+    let headers = if key.is_empty() { Vec::new() } else { vec![("Authorization", format!("Bearer {}", key))] };
+    (url(if provider.provider == "ollama" { OLLAMA_URL } else { CHAT_URL }), headers, body)
+}
+
+fn stream_chat(
+    client: &Client,
+    provider: &ProviderConfig,
+    key: &str,
+    messages: &[Message],
+    max_tokens: u32,
+    echo: bool,
+    cancel: &CancellationToken,
+) -> Result<(String, Usage), ApiError> {
+    let path = partial_path(provider, messages).map_err(String::from)?;
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() {
+        println!("  Resuming a cut-off response ({} characters saved)", content.chars().count());
+    }
+    let mut usage = Usage::default();
+    let mut last_error = ApiError::from(String::new());
+    let max_retries = 3;
+    let _permit = LLM_CALLS.acquire();
+    let mut wait = Duration::ZERO;
+    for attempt in 0..max_retries {
+        if attempt > 0 {
+            thread::sleep(wait);
+        }
+        cancel.check()?;
+        wait = retry::backoff(attempt);
+        let mut request_messages = messages.to_vec();
+        if !content.is_empty() {
+            request_messages.push(Message::new("assistant", &content));
+            request_messages.push(Message::new("user", CONTINUE_PROMPT));
+        }
+        let (url, headers, body) = stream_request(provider, key, request_messages, max_tokens);
+        match read_stream(client, &url, &headers, &body, &path, &mut content, &mut usage, echo, &mut wait, cancel) {
+            Ok(()) => {
+                let _ = fs::remove_file(&path);
+                if echo {
+                    println!();
+                }
+                return Ok((content, usage));
+            }
+            Err(e) if e.message == CANCELLED => return Err(e),
+            Err(e) if e.status.is_some_and(|s| s.is_client_error() && !retry::is_throttled(s)) => return Err(e),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(ApiError { message: format!("Failed after {} retries: {}", max_retries, last_error.message), ..last_error })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn read_stream(
+    client: &Client,
+    url: &str,
+    headers: &[(&str, String)],
+    body: &Value,
+    path: &PathBuf,
+    content: &mut String,
+    usage: &mut Usage,
+    echo: bool,
+    wait: &mut Duration,
+    cancel: &CancellationToken,
+) -> Result<(), ApiError> {
+    let client = LLM_CLIENT.as_ref().unwrap_or(client);
+    let mut builder = client.post(url).header("Content-Type", "application/json");
+    for (name, value) in headers {
+        builder = builder.header(*name, value);
+    }
+    let response = builder.json(body).send().map_err(|e| e.to_string())?;
+    let status = response.status();
+    if retry::is_throttled(status)
+        && let Some(after) = retry::retry_after(&response)
+    {
+        *wait = after;
+    }
+    if !status.is_success() {
+        return Err(ApiError { status: Some(status), message: format!("API error {}: {}", status, response.text().unwrap_or_default()) });
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut partial = OpenOptions::new().create(true).append(true).open(path).map_err(|e| e.to_string())?;
+
+    for line in BufReader::new(response).lines() {
+        let line = line.map_err(|e| format!("Stream interrupted: {}", e))?;
+        cancel.check()?;
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            continue;
+        };
+        if data == "[DONE]" {
+            return Ok(());
+        }
+        let Ok(event) = serde_json::from_str::<Value>(data) else {
+            continue;
+        };
+        let delta = match event["type"].as_str() {
+            Some("message_stop") => return Ok(()),
+            Some("message_start") => {
+                usage.prompt_tokens += event["message"]["usage"]["input_tokens"].as_u64().unwrap_or(0);
+                None
+            }
+            Some("message_delta") => {
+                usage.completion_tokens += event["usage"]["output_tokens"].as_u64().unwrap_or(0);
+                None
+            }
+            Some("content_block_delta") => event["delta"]["text"].as_str(),
+            _ => {
+                if let Some(tokens) = event["usage"]["prompt_tokens"].as_u64() {
+                    usage.prompt_tokens += tokens;
+                    usage.completion_tokens += event["usage"]["completion_tokens"].as_u64().unwrap_or(0);
+                }
+                event["choices"][0]["delta"]["content"].as_str()
+            }
+        };
+        if let Some(delta) = delta.filter(|d| !d.is_empty()) {
+            content.push_str(delta);
+            partial.write_all(delta.as_bytes()).map_err(|e| e.to_string())?;
+            if echo {
+                print!("{}", delta);
+                let _ = std::io::stdout().flush();
+            }
+        }
+    }
+    Err("Stream ended before the response was complete".to_string().into())
+}
+
+fn post<B: Serialize, T: DeserializeOwned>(client: &Client, headers: &[(&str, String)], url: &str, request: &B) -> Result<T, ApiError> {
+    let client = LLM_CLIENT.as_ref().unwrap_or(client);
+    let max_retries = 3;
+    let mut last_error = ApiError::from(String::new());
+    let _permit = LLM_CALLS.acquire();
+    let mut wait = Duration::ZERO;
+
+    for attempt in 0..max_retries {
+        if attempt > 0 {
+            thread::sleep(wait);
+        }
+        wait = retry::backoff(attempt);
+
+        let mut builder = client.post(url).header("Content-Type", "application/json");
+        for (name, value) in headers {
+            builder = builder.header(*name, value);
+        }
+        let response = match builder.json(request).send() {
+                Ok(r) => r,
+                Err(e) => {
+                    last_error = e.to_string().into();
+                    continue;
+                }
+            };
+
+        let status = response.status();
+        if retry::is_throttled(status)
+            && let Some(after) = retry::retry_after(&response)
+        {
+            wait = after;
+        }
+        let body = match response.text() {
+            Ok(b) => b,
+            Err(e) => {
+                last_error = e.to_string().into();
+                continue;
+            }
+        };
+
+        if status.as_u16() == 429 || status.as_u16() >= 500 {
+            last_error = ApiError { status: Some(status), message: format!("API error {}: {}", status, body) };
+            continue;
+        }
+
+        if !status.is_success() {
+            return Err(ApiError { status: Some(status), message: format!("API error {}: {}", status, body) });
+        }
+
+        match serde_json::from_str(&body) {
+            Ok(r) => return Ok(r),
+            Err(e) => {
+                last_error = format!("Parse error: {} - Body: {}", e, body).into();
+                continue;
+            }
+        }
+    }
+
+    Err(ApiError { message: format!("Failed after {} retries: {}", max_retries, last_error.message), ..last_error })
+}
@@ -0,0 +1,125 @@
+use crate::arxiv::Paper;
+use crate::config::OpenReviewSourceConfig;
+use crate::openai;
+use reqwest::blocking::Client;
+use serde_json::Value;
+
+const API_URL: &str = "https://api2.openreview.net/notes";
+const PAGE_SIZE: usize = 1000;
+const REVIEW_FIELDS: &[&str] = &["summary", "review", "strengths", "weaknesses", "questions", "limitations", "rating"];
+const MAX_REVIEW_CHARS: usize = 30000;
+
+pub fn split_id(id: &str) -> Option<&str> {
+    id.strip_prefix("openreview:")
+}
+
+pub fn abs_url(id: &str) -> Option<String> {
+    split_id(id).map(|forum| format!("https://openreview.net/forum?id={}", forum))
+}
+
+fn field(content: &Value, name: &str) -> Option<String> {
+    let value = &content[name];
+    value["value"]
+        .as_str()
+        .or(value.as_str())
+        .map(|s| s.to_string())
+        .or_else(|| value["value"].as_i64().or(value.as_i64()).map(|n| n.to_string()))
+}
+
+fn get(client: &Client, query: &[(&str, String)]) -> Result<Vec<Value>, String> {
+    let body: Value = client
+        .get(API_URL)
+        .query(query)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| e.to_string())?;
+    Ok(body["notes"].as_array().cloned().unwrap_or_default())
+}
+
+pub fn fetch(client: &Client, config: &OpenReviewSourceConfig, limit: usize) -> Result<Vec<Paper>, String> {
+    if config.venue.is_empty() {
+        return Err("set [sources.openreview] venue, e.g. \"ICLR.cc/2025/Conference\"".to_string());
+    }
+    let invitation = if config.invitation.is_empty() {
+        format!("{}/-/Submission", config.venue)
+    } else {
+        config.invitation.clone()
+    };
+
+    let mut papers = Vec::new();
+    let mut offset = 0;
+    while papers.len() < limit {
+        let notes = get(
+            client,
+            &[("invitation", invitation.clone()), ("offset", offset.to_string()), ("limit", PAGE_SIZE.to_string())],
+        )?;
+        offset += notes.len();
+        papers.extend(notes.iter().filter_map(|note| parse_note(note, &config.venue)));
+        if notes.len() < PAGE_SIZE {
+            break;
+        }
+    }
+    papers.truncate(limit);
+    Ok(papers)
+}
+
+fn parse_note(note: &Value, venue: &str) -> Option<Paper> {
+    let forum = note["forum"].as_str().or(note["id"].as_str())?;
+    let content = &note["content"];
+    let title = field(content, "title")?;
+    field(content, "pdf")?;
+    Some(Paper {
+        id: format!("openreview:{}", forum),
+        title: title.split_whitespace().collect::<Vec<_>>().join(" "),
+        pdf_url: format!("https://openreview.net/pdf?id={}", forum),
+        category: field(content, "primary_area").unwrap_or_else(|| venue.to_string()),
+        scholar: None,
+        code: None,
+        sources: Vec::new(),
+    })
+}
+
+fn fetch_reviews(client: &Client, forum: &str) -> Result<Vec<String>, String> {
+    let notes = get(client, &[("forum", forum.to_string())])?;
+    Ok(notes
+        .iter()
+        .filter(|note| {
+            let invitations = note["invitations"].as_array().into_iter().flatten().filter_map(|i| i.as_str());
+            invitations.chain(note["invitation"].as_str()).any(|i| i.ends_with("Official_Review"))
+        })
+        .map(|note| {
+            REVIEW_FIELDS
+                .iter()
+                .filter_map(|name| field(&note["content"], name).map(|value| format!("{}: {}", name, value)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|review| !review.is_empty())
+        .collect())
+}
+
+pub fn criticism(client: &Client, api_key: &str, paper: &Paper, forum: &str) -> Result<Option<String>, String> {
+    let reviews = fetch_reviews(client, forum)?;
+    if reviews.is_empty() {
+        return Ok(None);
+    }
+    println!("  Summarizing {} reviews: {}", reviews.len(), paper.title);
+
+    let mut text = String::new();
+    for (i, review) in reviews.iter().enumerate() {
+        text.push_str(&format!("### Review {}\n{}\n\n", i + 1, review));
+    }
+    let text: String = text.chars().take(MAX_REVIEW_CHARS).collect();
+    let prompt = format!(
+        r#"The following are the public peer reviews of the paper "{}".
+
+        Summarize what the reviewers criticized as concise bullet points: weaknesses, missing experiments, unclear claims and concerns they agreed on.
+        Mention the ratings if given. Do not hallucinate. Use only the reviews below.
+
+        Reviews:
+        {}"#,
+        paper.title, text
+    );
+    openai::chat(client, api_key, &prompt, 800).map(Some)
+}
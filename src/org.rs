@@ -0,0 +1,120 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::sources;
+use crate::summary::{Frontmatter, strip_frontmatter};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+
+static LINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap());
+static BOLD_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*\*([^*]+)\*\*").unwrap());
+static CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"`([^`]+)`").unwrap());
+static HEADING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(#{1,6})\s+(.*)$").unwrap());
+
+fn inline(line: &str) -> String {
+    let line = LINK_REGEX.replace_all(line, "[[$2][$1]]");
+    let line = BOLD_REGEX.replace_all(&line, "*$1*");
+    CODE_REGEX.replace_all(&line, "~$1~").to_string()
+}
+
+fn body(markdown: &str) -> String {
+    let mut out = Vec::new();
+    let (mut in_code, mut in_quote) = (false, false);
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            out.push(if in_code { "#+END_SRC".to_string() } else { format!("#+BEGIN_SRC {}", lang.trim()).trim_end().to_string() });
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push(line.to_string());
+            continue;
+        }
+        let quoted = trimmed.strip_prefix('>');
+        if quoted.is_some() != in_quote {
+            out.push(if in_quote { "#+END_QUOTE" } else { "#+BEGIN_QUOTE" }.to_string());
+            in_quote = !in_quote;
+        }
+        if let Some(text) = quoted {
+            out.push(inline(text.trim()));
+        } else if let Some(captures) = HEADING_REGEX.captures(line) {
+            out.push(format!("{} {}", "*".repeat(captures[1].len().max(2)), inline(&captures[2])));
+        } else if trimmed.chars().all(|c| c == '-') && trimmed.len() >= 3 {
+            out.push("-----".to_string());
+        } else if trimmed.starts_with("|") && trimmed.chars().all(|c| matches!(c, '|' | '-' | ':' | ' ')) {
+            let cells: Vec<&str> = trimmed.trim_matches('|').split('|').collect();
+            out.push(format!("|{}|", cells.iter().map(|c| "-".repeat(c.len().max(3))).collect::<Vec<_>>().join("+")));
+        } else if let Some(item) = trimmed.strip_prefix("* ") {
+            out.push(format!("{}- {}", &line[..line.len() - trimmed.len()], inline(item)));
+        } else {
+            out.push(inline(line));
+        }
+    }
+    if in_quote {
+        out.push("#+END_QUOTE".to_string());
+    }
+    out.join("\n")
+}
+
+fn tag(text: &str) -> String {
+    text.chars().map(|c| if c.is_alphanumeric() || matches!(c, '_' | '@') { c } else { '_' }).collect()
+}
+
+pub fn render(record: &PaperRecord, content: &str) -> String {
+    let frontmatter = Frontmatter::parse(content).map(|(frontmatter, _)| frontmatter).unwrap_or_default();
+    let text = strip_frontmatter(content);
+    let text = text.split_once("\n---\n").map(|(_, b)| b).unwrap_or(text);
+    let tags: String = record.tags.iter().map(|t| format!(":{}", tag(t))).collect();
+    let mut org = format!(
+        "* {} {}{}\n:PROPERTIES:\n",
+        if record.is_read() { "DONE" } else { "TODO" },
+        record.title,
+        if tags.is_empty() { String::new() } else { format!("   {}:", tags) }
+    );
+    let mut properties = vec![
+        ("ARXIV_ID", record.id.clone()),
+        ("CATEGORY", record.category.clone()),
+        ("URL", sources::abs_url(&record.id)),
+        ("PDF", record.pdf_url.clone()),
+        ("ADDED", record.added_at.get(..10).unwrap_or_default().to_string()),
+    ];
+    for key in ["model", "depth", "grounding"] {
+        properties.push((key, frontmatter.get(key).unwrap_or_default().to_string()));
+    }
+    for (key, value) in properties.into_iter().filter(|(_, value)| !value.is_empty()) {
+        org.push_str(&format!(":{}: {}\n", key.to_uppercase(), value));
+    }
+    if record.starred {
+        org.push_str(":STARRED: t\n");
+    }
+    org.push_str(":END:\n\n");
+    org.push_str(body(text.trim()).trim());
+    org.push('\n');
+    org
+}
+
+pub fn render_file(record: &PaperRecord, content: &str) -> String {
+    format!("#+TITLE: {}\n#+TODO: TODO | DONE\n\n{}", record.title, render(record, content))
+}
+
+pub fn export(args: &Args) -> Result<(), String> {
+    let output = args.value("output").unwrap_or("ras.org");
+    let db = Db::load()?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut records: Vec<&PaperRecord> = db.papers.values().filter(|r| r.status == "summarized").collect();
+    if records.is_empty() {
+        return Err("No summarized papers to export".to_string());
+    }
+    records.sort_by(|a, b| a.added_at.cmp(&b.added_at));
+
+    let mut org = "#+TITLE: ras summaries\n#+TODO: TODO | DONE\n\n".to_string();
+    for record in &records {
+        let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+        org.push_str(&render(record, &content));
+        org.push('\n');
+    }
+    fs::write(output, org).map_err(|e| format!("Failed to write {}: {}", output, e))?;
+    println!("Wrote {} papers to {}", records.len(), output);
+    Ok(())
+}
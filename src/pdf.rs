@@ -0,0 +1,222 @@
+use crate::cancel::{CANCELLED, CancellationToken};
+use crate::extraction;
+use crate::retry;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use reqwest::header::{CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::thread;
+
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+const CHECK_BYTES: u64 = 1024;
+const NOT_A_PDF: &str = "not a PDF";
+const HTTP_ERROR: &str = "HTTP error";
+
+pub fn extract_text_from_pdf(path: &Path) -> Result<String, String> {
+    extract_text_cancellable(path, &CancellationToken::new())
+}
+
+pub fn extract_text_cancellable(path: &Path, cancel: &CancellationToken) -> Result<String, String> {
+    extraction::extract(path, cancel).map(|extraction| extraction.text)
+}
+
+pub fn download_from_mirrors(client: &Client, urls: &[String], path: &Path, cancel: &CancellationToken) -> Result<String, String> {
+    let mut last_error = "no download URL".to_string();
+    let mut previous_file: Option<&str> = None;
+    for url in urls {
+        let file = url.rsplit('/').next();
+        if previous_file.is_some_and(|previous| Some(previous) != file) {
+            let _ = fs::remove_file(path.with_extension("pdf.part"));
+        }
+        previous_file = file;
+        match download_pdf(client, url, path, cancel) {
+            Ok(()) => return Ok(url.clone()),
+            Err(e) if e == CANCELLED => return Err(e),
+            Err(e) => {
+                if urls.len() > 1 {
+                    println!("  Download from {} failed ({}), trying the next mirror", url, e);
+                }
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+pub fn download_pdf(client: &Client, url: &str, path: &Path, cancel: &CancellationToken) -> Result<(), String> {
+    let part = path.with_extension("pdf.part");
+    let mut last_error = String::new();
+    for attempt in 0..DOWNLOAD_ATTEMPTS {
+        if attempt > 0 {
+            thread::sleep(retry::backoff(attempt - 1));
+        }
+        match download_part(client, url, &part, cancel) {
+            Ok(()) => match validate_pdf(&part) {
+                Ok(()) => return fs::rename(&part, path).map_err(|e| e.to_string()),
+                Err(e) => {
+                    let _ = fs::remove_file(&part);
+                    if e.starts_with(NOT_A_PDF) {
+                        return Err(e);
+                    }
+                    println!("  Downloaded file is invalid ({}), downloading again", e);
+                    last_error = e;
+                }
+            },
+            Err(e) if e == CANCELLED || e.starts_with(NOT_A_PDF) || e.starts_with(HTTP_ERROR) => return Err(e),
+            Err(e) => {
+                println!("  Download interrupted ({}), resuming", e);
+                last_error = e;
+            }
+        }
+    }
+    Err(format!("Failed after {} attempts: {}", DOWNLOAD_ATTEMPTS, last_error))
+}
+
+fn download_part(client: &Client, url: &str, part: &Path, cancel: &CancellationToken) -> Result<(), String> {
+    let offset = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let mut response = retry::send(url, || {
+        let request = client.get(url);
+        if offset > 0 { request.header(RANGE, format!("bytes={}-", offset)) } else { request }
+    })?;
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        let _ = fs::remove_file(part);
+        return Err("server rejected the resume range, restarting".to_string());
+    }
+    let status = response.status();
+    if !status.is_success() {
+        return Err(if retry::is_transient(status) { format!("server returned {}", status) } else { format!("{} {}", HTTP_ERROR, status) });
+    }
+    let content_type = response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or("").to_lowercase();
+    if content_type.contains("html") {
+        let mut head = vec![0u8; CHECK_BYTES as usize];
+        let read = response.read(&mut head).unwrap_or(0);
+        return Err(not_a_pdf(&head[..read]));
+    }
+
+    let (mut file, mut written) = if status == StatusCode::PARTIAL_CONTENT {
+        let start = response.headers().get(CONTENT_RANGE).and_then(|v| v.to_str().ok()).and_then(content_range_start);
+        if start != Some(offset) {
+            let _ = fs::remove_file(part);
+            return Err(format!("server did not resume at byte {}, restarting", offset));
+        }
+        (OpenOptions::new().append(true).open(part).map_err(|e| e.to_string())?, offset)
+    } else {
+        (fs::File::create(part).map_err(|e| e.to_string())?, 0)
+    };
+    let expected = response.content_length().map(|length| length + written);
+
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        cancel.check()?;
+        let read = response.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read]).map_err(|e| e.to_string())?;
+        written += read as u64;
+    }
+    file.flush().map_err(|e| e.to_string())?;
+
+    match expected {
+        Some(expected) if written != expected => Err(format!("received {} of {} bytes", written, expected)),
+        _ => Ok(()),
+    }
+}
+
+fn content_range_start(value: &str) -> Option<u64> {
+    value.trim().strip_prefix("bytes ")?.split('-').next()?.trim().parse().ok()
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub fn validate_pdf(path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let length = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut head = vec![0u8; CHECK_BYTES.min(length) as usize];
+    file.read_exact(&mut head).map_err(|e| e.to_string())?;
+    if !head.starts_with(b"%PDF-") {
+        return Err(not_a_pdf(&head));
+    }
+
+    file.seek(SeekFrom::Start(length.saturating_sub(CHECK_BYTES))).map_err(|e| e.to_string())?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).map_err(|e| e.to_string())?;
+    if !tail.windows(5).any(|w| w == b"%%EOF") {
+        return Err("missing %%EOF marker, the file is probably truncated".to_string());
+    }
+    Ok(())
+}
+
+fn not_a_pdf(head: &[u8]) -> String {
+    let text = String::from_utf8_lossy(head).to_lowercase();
+    if text.contains("pdf unavailable") || text.contains("no pdf") {
+        format!("{}: arXiv placeholder page, the PDF is unavailable", NOT_A_PDF)
+    } else if text.contains("<html") || text.contains("<!doctype") {
+        format!("{}: got an HTML page", NOT_A_PDF)
+    } else {
+        format!("{}: missing %PDF- header", NOT_A_PDF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(name: &str, content: &[u8]) -> Result<(), String> {
+        let path = std::env::temp_dir().join(format!("ras-pdf-{}-{}.pdf", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        let result = validate_pdf(&path);
+        let _ = fs::remove_file(&path);
+        result
+    }
+
+    #[test]
+    fn accepts_complete_pdfs() {
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        pdf.extend(vec![b'x'; 4096]);
+        pdf.extend(b"\nstartxref\n123\n%%EOF\n");
+        assert_eq!(validate("complete", &pdf), Ok(()));
+        assert_eq!(validate("tiny", b"%PDF-1.4\n%%EOF"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_truncated_pdfs() {
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        pdf.extend(vec![b'x'; 4096]);
+        let error = validate("truncated", &pdf).unwrap_err();
+        assert!(error.contains("%%EOF"), "{}", error);
+    }
+
+    #[test]
+    fn explains_what_came_back_instead_of_a_pdf() {
+        assert_eq!(validate("html", b"<!DOCTYPE html><html><body>Hi</body></html>"), Err(format!("{}: got an HTML page", NOT_A_PDF)));
+        assert_eq!(
+            validate("placeholder", b"<html>PDF unavailable for 2501.01234</html>"),
+            Err(format!("{}: arXiv placeholder page, the PDF is unavailable", NOT_A_PDF))
+        );
+        assert_eq!(validate("garbage", b"\x00\x01binary"), Err(format!("{}: missing %PDF- header", NOT_A_PDF)));
+    }
+
+    #[test]
+    fn reads_the_start_of_a_content_range() {
+        assert_eq!(content_range_start("bytes 1000-1999/2000"), Some(1000));
+        assert_eq!(content_range_start("bytes 0-99/*"), Some(0));
+        assert_eq!(content_range_start("bytes */2000"), None);
+        assert_eq!(content_range_start("1000-1999/2000"), None);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::arxiv::{self, Paper};
+use crate::cli::Args;
+use crate::config::Config;
+use crate::db::Db;
+use crate::error::RasError;
+use crate::lock;
+use crate::pipeline::{self, RunOptions};
+use crate::sources::{self, FetchOptions};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use inquire::MultiSelect;
+use std::fmt;
+
+struct Choice {
+    paper: Paper,
+    summary: String,
+}
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.paper.title, self.paper.category, self.paper.id)
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), RasError> {
+    let config = Config::load().map_err(RasError::Config)?;
+    let client = crate::build_client()?;
+    crate::cache::configure(&config.cache);
+    let requested = RunOptions::from_args(args)?;
+
+    println!("Fetching papers...");
+    let opts = FetchOptions { limit: requested.limit.unwrap_or(config.sources.limit), since: requested.since, primary_only: requested.primary_only };
+    let (papers, failed) = sources::fetch_all(&client, &sources::configured(&config.sources), &opts);
+    if papers.is_empty() && failed > 0 {
+        return Err(RasError::FetchFailed);
+    }
+    let db = Db::load()?;
+    let papers: Vec<Paper> = papers
+        .into_iter()
+        .filter(|p| db.papers.get(&p.id).is_none_or(|r| r.status != "summarized"))
+        .collect();
+    if papers.is_empty() {
+        println!("No new papers to pick from");
+        return Ok(());
+    }
+
+    let ids: Vec<String> = papers.iter().map(|p| p.id.clone()).collect();
+    let mut abstracts = arxiv::fetch_abstracts(&client, &ids);
+    let choices: Vec<Choice> = papers
+        .into_iter()
+        .map(|paper| Choice { summary: abstracts.remove(&paper.id).unwrap_or_default(), paper })
+        .collect();
+
+    let matcher = SkimMatcherV2::default();
+    let scorer = |input: &str, choice: &Choice, _: &str, _: usize| {
+        if input.is_empty() {
+            return Some(0);
+        }
+        matcher.fuzzy_match(&format!("{} {}", choice.paper.title, choice.summary), input)
+    };
+    let selected = MultiSelect::new(&format!("Pick papers to summarize ({} new):", choices.len()), choices)
+        .with_page_size(15)
+        .with_help_message("type to search titles and abstracts, space to select, enter to confirm, esc to cancel")
+        .with_scorer(&scorer)
+        .prompt_skippable()
+        .map_err(|e| RasError::Other(format!("Interactive picker failed: {}", e)))?
+        .unwrap_or_default();
+    if selected.is_empty() {
+        println!("Nothing selected");
+        return Ok(());
+    }
+
+    let mut papers: Vec<Paper> = selected.into_iter().map(|c| c.paper).collect();
+    for paper in papers.iter_mut() {
+        paper.sources.push("pick".to_string());
+    }
+    println!("Summarizing {} picked papers", papers.len());
+    let _lock = lock::RunLock::acquire(&lock::lock_path()?)?;
+    pipeline::execute(requested.with_papers(papers))
+}
@@ -0,0 +1,1639 @@
+use crate::ar5iv;
+use crate::arxiv::{self, Paper, fetch_abstracts};
+use crate::benchmarks;
+use crate::cache;
+use crate::cancel::{CANCELLED, CancellationToken};
+use crate::capabilities::{self, Capability};
+use crate::captions;
+use crate::chunking;
+use crate::cleaning;
+use crate::cli::Args;
+use crate::config::{Config, FilesConfig, LlmConfig, PipelineConfig, ProviderConfig, SlugConfig, SummaryConfig};
+use crate::dashboard::{self, Dashboard};
+use crate::db::{Db, SharedDb};
+use crate::dedup;
+use crate::delivery;
+use crate::depth::{Depth, TLDR_CHARS};
+use crate::entities::{self, Entities};
+use crate::extraction;
+use crate::git;
+use crate::glossary;
+use crate::grounding;
+use crate::ideas;
+use crate::language;
+use crate::metrics;
+use crate::migrate;
+use crate::notifications::RunReport;
+use crate::obsidian;
+use crate::openai::{self, Usage};
+use crate::openreview;
+use crate::org;
+use crate::pwc;
+use crate::queue::SpillQueue;
+use crate::pdf::{download_from_mirrors, sha256_file, validate_pdf};
+use crate::process;
+use crate::error::RasError;
+use crate::relevance;
+use crate::runs;
+use crate::scholar;
+use crate::storage;
+use crate::semaphore::Semaphore;
+use crate::references::{self, Reference};
+use crate::series::{self, Relation};
+use crate::sources::{self, FetchOptions};
+use crate::survey::{self, TopicFiles};
+use crate::telemetry;
+use crate::sync;
+use crate::topics;
+use crate::translate;
+use crate::versions;
+use crate::summary::{self, Frontmatter, SummaryInfo, set_frontmatter_field};
+use crate::watchlist;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+
+static SANITIZE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"[<>:"/\\|?*\x00-\x1f]"#).unwrap());
+
+pub const MAX_PROMPT_CHARS: usize = 100000;
+pub const PROMPT_VERSION: u32 = 1;
+const CHARS_PER_TOKEN: usize = 4;
+const OFFLINE_SKIPPED: &str = "PDF not downloaded (offline)";
+
+pub const SUMMARY_SECTIONS: &str = r#"Please analyze the text provided and structure your summary using the following specific sections:
+        1. **Overview**: A concise description of the paper's core mission, what it introduces (e.g., specific benchmarks, datasets, or models), and its primary goal.
+        2. **Key Results**: detailed quantitative findings. Do not be vague. Extract specific metrics, leaderboard rankings, scores (e.g., "Model X scored 56.1%"), and domain-specific performance comparisons.
+        3. **Methodology**: Explain the specific approach used. Detail the dataset composition (e.g., number of test cases, expert sources) and the evaluation/grading process (e.g., "hurdle criteria," "grounding checks," or specific algorithms).
+        4. **Critical Insights**: Discuss the nuances, limitations, or specific behaviors observed in the study. Look for failure modes (e.g., hallucinations), performance gaps between domains, or qualitative observations made by the authors."#;
+
+#[derive(Clone, Default)]
+pub struct RunOptions {
+    pub require_code: bool,
+    pub refine: bool,
+    pub depth: Depth,
+    pub languages: Vec<String>,
+    pub update_versions: bool,
+    pub dry_run: bool,
+    pub dashboard: bool,
+    pub offline: bool,
+    pub papers: Option<Vec<Paper>>,
+    pub since: Option<DateTime<Utc>>,
+    pub since_last_run: bool,
+    pub primary_only: bool,
+    pub limit: Option<usize>,
+    pub concurrency: Option<usize>,
+    pub downloads: Option<usize>,
+    pub extractions: Option<usize>,
+    pub llm_calls: Option<usize>,
+    pub model: Option<String>,
+    pub prompt: Option<(String, String)>,
+    pub cancel: CancellationToken,
+    paper_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+impl RunOptions {
+    pub fn from_args(args: &Args) -> Result<RunOptions, String> {
+        let since = match (args.value("since"), args.value("last")) {
+            (Some("last"), _) | (None, None) => None,
+            (Some(date), _) => Some(parse_date(date)?),
+            (None, Some(window)) => Some(Utc::now() - parse_window(window)?),
+        };
+        Ok(RunOptions {
+            require_code: args.flag("require-code"),
+            refine: args.flag("refine"),
+            depth: args.value("depth").map(Depth::parse).transpose()?.unwrap_or_default(),
+            languages: translate::parse_languages(args.value("lang").unwrap_or(""))?,
+            dry_run: args.flag("dry-run"),
+            dashboard: args.flag("dashboard"),
+            offline: args.flag("offline"),
+            since,
+            since_last_run: args.value("since") == Some("last"),
+            primary_only: args.flag("primary-only"),
+            limit: parse_count(args, "limit")?,
+            concurrency: parse_count(args, "concurrency")?,
+            downloads: parse_count(args, "downloads")?,
+            extractions: parse_count(args, "extractions")?,
+            llm_calls: parse_count(args, "llm-calls")?,
+            ..Default::default()
+        })
+    }
+
+    fn apply_limits(&self, config: &mut PipelineConfig) {
+        if let Some(threads) = self.concurrency {
+            config.extract_threads = threads;
+            config.summarize_threads = threads;
+        }
+        config.downloads = self.downloads.unwrap_or(config.downloads);
+        config.extractions = self.extractions.unwrap_or(config.extractions);
+        config.llm_calls = self.llm_calls.unwrap_or(config.llm_calls);
+    }
+
+    fn apply_model(&self, config: &mut LlmConfig) {
+        let Some(model) = &self.model else {
+            return;
+        };
+        let configured = config.providers.iter().find(|p| p.model == *model || format!("{}/{}", p.provider, p.model) == *model).cloned();
+        let provider = configured.unwrap_or_else(|| match model.split_once('/') {
+            Some((provider, model)) => ProviderConfig { provider: provider.to_string(), model: model.to_string(), ..Default::default() },
+            None => ProviderConfig { model: model.clone(), ..Default::default() },
+        });
+        provider.warn_if_unpriced();
+        config.providers = vec![provider];
+    }
+
+    pub fn for_papers(papers: Vec<Paper>) -> RunOptions {
+        RunOptions { papers: Some(papers), ..Default::default() }
+    }
+
+    pub fn with_papers(self, papers: Vec<Paper>) -> RunOptions {
+        RunOptions { papers: Some(papers), ..self }
+    }
+
+    pub fn paper_token(&self, id: &str) -> CancellationToken {
+        let mut tokens = self.paper_tokens.lock().unwrap();
+        tokens.entry(id.to_string()).or_insert_with(|| self.cancel.child()).clone()
+    }
+
+    pub fn cancel_paper(&self, id: &str) {
+        self.paper_token(id).cancel();
+    }
+}
+
+fn parse_count(args: &Args, name: &str) -> Result<Option<usize>, String> {
+    args.value(name)
+        .map(|v| v.parse().map_err(|_| format!("Invalid value for --{}: {}", name, v)))
+        .transpose()
+}
+
+fn parse_date(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc()))
+        .map_err(|_| format!("Invalid date for --since: {} (expected YYYY-MM-DD)", value))
+}
+
+pub fn parse_window(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid value for --last: {} (expected e.g. 12h, 7d or 2w)", value);
+    let (amount, unit) = value.split_at(value.len().saturating_sub(1));
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+pub struct RunContext {
+    pub config: Config,
+    pub options: RunOptions,
+    pub papers_dir: PathBuf,
+    pub summary_dir: PathBuf,
+    pub openai_key: String,
+    pub client: Client,
+    pub db: SharedDb,
+    pub fetch_failed: AtomicBool,
+    pub watched: Mutex<HashMap<String, Vec<String>>>,
+    pub versions: Mutex<HashMap<String, versions::Update>>,
+    pub tracker: runs::Tracker,
+    pub drafts: Mutex<HashMap<String, String>>,
+    pub file_dates: Mutex<HashMap<String, DateTime<Local>>>,
+    pub downloads: Semaphore,
+    pub extractions: Semaphore,
+}
+
+impl RunContext {
+    pub fn new(config: Config, options: RunOptions) -> Result<RunContext, String> {
+        let ras_dir = crate::ras_dir()?;
+        let papers_dir = ras_dir.join("papers");
+        let summary_dir = ras_dir.join("summary");
+
+        if options.dry_run {
+            cache::no_store();
+        } else {
+            fs::create_dir_all(&papers_dir).map_err(|e| format!("Failed to create papers directory: {}", e))?;
+            fs::create_dir_all(&summary_dir).map_err(|e| format!("Failed to create summary directory: {}", e))?;
+        }
+
+        openai::set_concurrency(config.pipeline.llm_calls);
+        openai::set_providers(&config.llm);
+        arxiv::set_request_delay(config.sources.arxiv.request_delay_secs);
+        cache::configure(&config.cache);
+        extraction::configure(&config.extraction);
+        Ok(RunContext {
+            downloads: Semaphore::new(config.pipeline.downloads.max(1)),
+            extractions: Semaphore::new(config.pipeline.extractions.max(1)),
+            config,
+            options,
+            papers_dir,
+            summary_dir,
+            openai_key: openai::chat_key()?,
+            client: crate::build_client()?,
+            db: Db::shared()?,
+            fetch_failed: AtomicBool::new(false),
+            watched: Mutex::new(HashMap::new()),
+            versions: Mutex::new(HashMap::new()),
+            tracker: runs::Tracker::default(),
+            drafts: Mutex::new(HashMap::new()),
+            file_dates: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn summary_file(&self, paper: &Paper) -> String {
+        let recorded = self.db.lock().unwrap().papers.get(&paper.id).map(|r| r.summary_file.clone()).unwrap_or_default();
+        if recorded.is_empty() { summary_filename(paper, &self.config.files, self.file_date(paper)) } else { recorded }
+    }
+
+    pub fn pdf_file(&self, paper: &Paper) -> String {
+        let recorded = self.db.lock().unwrap().papers.get(&paper.id).map(|r| r.pdf_file.clone()).unwrap_or_default();
+        if recorded.is_empty() { pdf_filename(paper, &self.config.files, self.file_date(paper)) } else { recorded }
+    }
+
+    fn file_date(&self, paper: &Paper) -> DateTime<Local> {
+        let added = self.db.lock().unwrap().papers.get(&paper.id).map(|r| r.added_at.clone()).unwrap_or_default();
+        match DateTime::parse_from_rfc3339(&added) {
+            Ok(at) => at.with_timezone(&Local),
+            Err(_) => *self.file_dates.lock().unwrap().entry(paper.id.clone()).or_insert_with(Local::now),
+        }
+    }
+
+    fn pin_file_date(&self, db: &mut Db, paper: &Paper) {
+        if let (Some(at), Some(record)) = (self.file_dates.lock().unwrap().remove(&paper.id), db.papers.get_mut(&paper.id)) {
+            record.added_at = at.with_timezone(&Utc).to_rfc3339();
+        }
+    }
+
+    fn processed(&self, paper: &Paper) -> bool {
+        let (recorded, gave_up) = self
+            .db
+            .lock()
+            .unwrap()
+            .papers
+            .get(&paper.id)
+            .map_or((false, false), |r| (!r.summary_file.is_empty() && !needs_retry(&r.status), r.attempts >= self.config.pipeline.max_attempts || r.status == "skipped"));
+        gave_up || (recorded && self.summary_dir.join(self.summary_file(paper)).exists())
+    }
+}
+
+pub fn execute(options: RunOptions) -> Result<(), RasError> {
+    let error = match thread::spawn(move || run(options)).join() {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(e)) => e,
+        Err(panic) => RasError::Other(
+            panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "pipeline panicked".to_string()),
+        ),
+    };
+    let aborted = !matches!(error, RasError::PartialFailure { .. } | RasError::TotalFailure(_) | RasError::FetchFailed);
+    if aborted && let (Ok(config), Ok(client)) = (Config::load(), crate::build_client()) {
+        delivery::deliver_failure(&config, &client, &error.to_string());
+    }
+    Err(error)
+}
+
+pub fn run(options: RunOptions) -> Result<(), RasError> {
+    let mut config = Config::load().map_err(RasError::Config)?;
+    options.apply_limits(&mut config.pipeline);
+    options.apply_model(&mut config.llm);
+    let ctx = RunContext::new(config, options)?;
+    storage::check_free_space(&ctx.config.storage, &ctx.papers_dir)?;
+    let ctx = Arc::new(ctx);
+    let started = Utc::now();
+    let clock = SystemTime::now();
+    capabilities::reset();
+    openai::take_usage();
+    let (fetched, papers_to_process) = select_papers(&ctx);
+    ctx.tracker.span("fetch", "", clock, SystemTime::now());
+    if ctx.options.dry_run {
+        print_plan(&ctx, &papers_to_process);
+        return Ok(());
+    }
+
+    let dashboard = if (ctx.options.dashboard || ctx.config.pipeline.dashboard) && io::stdout().is_terminal() && !papers_to_process.is_empty() {
+        dashboard::start(ctx.clone(), &papers_to_process)
+            .inspect_err(|e| println!("Failed to start the dashboard: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    if ctx.config.summary.progressive {
+        write_abstract_summaries(&ctx, &papers_to_process);
+        finish_run(&ctx, fetched, &papers_to_process);
+        println!("\nUpgrading to full PDF-based summaries...");
+        process_all(&ctx, &papers_to_process);
+    } else {
+        process_all(&ctx, &papers_to_process);
+        finish_run(&ctx, fetched, &papers_to_process);
+    }
+    let log = dashboard.map(Dashboard::finish);
+
+    if !ctx.config.storage.keep_pdfs.is_empty() {
+        let mut db = ctx.db.lock().unwrap();
+        match storage::clean(&ctx.config.storage, &mut db, &ctx.papers_dir, false) {
+            Ok((removed, freed)) if removed > 0 => {
+                println!("Retention policy deleted {} files ({:.1} MB)", removed, freed as f64 / 1_048_576.0);
+                if let Err(e) = db.save() {
+                    println!("Failed to update database: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => println!("Failed to apply retention policy: {}", e),
+        }
+    }
+
+    if ctx.options.papers.is_none() && !ctx.options.cancel.is_cancelled() && !ctx.fetch_failed.load(Ordering::SeqCst) {
+        let mut db = ctx.db.lock().unwrap();
+        db.set_watermark(started);
+        if let Err(e) = db.save() {
+            println!("Failed to record run watermark: {}", e);
+        }
+    }
+
+    let usage = openai::take_usage();
+    if !usage.is_empty() {
+        println!("\nLLM usage:");
+        for (pass, usage) in &usage {
+            println!("  {}: {} prompt + {} completion tokens (${:.4})", pass, usage.prompt_tokens, usage.completion_tokens, usage.cost);
+        }
+    }
+
+    let finished = Utc::now();
+    let report = runs::Run {
+        id: runs::run_id(started),
+        started: started.to_rfc3339(),
+        finished: finished.to_rfc3339(),
+        seconds: (finished - started).num_milliseconds() as f64 / 1000.0,
+        fetched,
+        cancelled: ctx.options.cancel.is_cancelled(),
+        papers: ctx.tracker.report(&papers_to_process),
+        cost: usage.values().fold(0.0, |total, u| total + u.cost),
+        usage: usage.into_iter().map(|(pass, usage)| (pass.to_string(), usage)).collect(),
+    };
+    metrics::record_run(&report);
+    if !ctx.config.telemetry.endpoint.is_empty() {
+        match telemetry::export(&ctx.client, &ctx.config.telemetry, &report, clock, SystemTime::now(), &ctx.tracker.spans()) {
+            Ok(count) => println!("Exported {} trace spans to {}", count, ctx.config.telemetry.endpoint),
+            Err(e) => println!("Failed to export traces: {}", e),
+        }
+    }
+    match runs::save(&report) {
+        Ok(path) => println!("Run report saved: {}", path.display()),
+        Err(e) => println!("Failed to save run report: {}", e),
+    }
+    if let Some(log) = log {
+        match runs::save_log(&report.id, &log) {
+            Ok(path) => println!("Run log saved: {}", path.display()),
+            Err(e) => println!("Failed to save run log: {}", e),
+        }
+    }
+    let total = report.papers.len();
+    let summarized = report.papers.iter().filter(|p| p.outcome == "summarized").count();
+    let failed = report.papers.iter().filter(|p| p.outcome == "failed" || (p.outcome == "skipped" && !expected_skip(&p.reason))).count();
+
+    println!("\nDone!");
+    match failed {
+        0 if total == 0 && ctx.fetch_failed.load(Ordering::SeqCst) => Err(RasError::FetchFailed),
+        0 => Ok(()),
+        _ if summarized == 0 => Err(RasError::TotalFailure(total)),
+        _ => Err(RasError::PartialFailure { failed, total }),
+    }
+}
+
+fn estimated_usage(ctx: &RunContext) -> Usage {
+    let depth = ctx.options.depth;
+    let text_chars = if depth == Depth::Tldr { TLDR_CHARS } else { MAX_PROMPT_CHARS };
+    let summary = Usage { prompt_tokens: (text_chars / CHARS_PER_TOKEN) as u64, completion_tokens: depth.max_tokens() as u64, cost: 0.0 };
+    let mut usage = summary;
+    if ctx.options.refine || ctx.config.summary.refine {
+        usage.add(Usage { prompt_tokens: summary.prompt_tokens + summary.completion_tokens, ..summary });
+    }
+    let languages = if ctx.options.languages.is_empty() { &ctx.config.summary.languages } else { &ctx.options.languages };
+    for _ in languages {
+        usage.add(Usage { prompt_tokens: summary.completion_tokens, ..summary });
+    }
+    usage
+}
+
+fn print_plan(ctx: &RunContext, papers: &[Paper]) {
+    println!("\nDry run: {} papers would be summarized", papers.len());
+    let mut downloads = 0;
+    for paper in papers {
+        let exists = ctx.papers_dir.join(ctx.pdf_file(paper)).exists();
+        if !exists {
+            downloads += 1;
+        }
+        println!("  {} {}  {}", if exists { " " } else { "↓" }, paper.id, paper.title);
+    }
+    let per_paper = estimated_usage(ctx);
+    let total = Usage::priced(per_paper.prompt_tokens * papers.len() as u64, per_paper.completion_tokens * papers.len() as u64, openai::active_prices());
+    println!("\n{} PDFs would be downloaded (marked ↓), {} are already on disk", downloads, papers.len() - downloads);
+    println!(
+        "Estimated LLM usage: up to {} prompt + {} completion tokens (${:.4}), assuming every paper fills the {} summary prompt",
+        total.prompt_tokens,
+        total.completion_tokens,
+        total.cost,
+        ctx.options.depth.name()
+    );
+    println!("Nothing was downloaded, summarized or written.");
+}
+
+fn process_all(ctx: &Arc<RunContext>, papers_to_process: &[Paper]) {
+    let total_papers = papers_to_process.len();
+    let memory_limit = ctx.config.pipeline.queue_memory_mb * 1024 * 1024;
+    let queue: SpillQueue<(Paper, Extracted)> = match crate::ras_dir().map_err(String::from).and_then(|dir| SpillQueue::new(dir.join("queue"), memory_limit)) {
+        Ok(queue) => queue,
+        Err(e) => {
+            println!("Failed to create extraction queue: {}", e);
+            return;
+        }
+    };
+    let pending = Mutex::new(papers_to_process.iter().cloned().collect::<VecDeque<Paper>>());
+    let processed = AtomicUsize::new(0);
+    let progress = || println!("Progress: {}/{}", processed.fetch_add(1, Ordering::SeqCst) + 1, total_papers);
+
+    thread::scope(|scope| {
+        for _ in 0..ctx.config.pipeline.summarize_threads.max(1) {
+            scope.spawn(|| {
+                while let Some((paper, extracted)) = queue.pop() {
+                    guard(&paper, || {
+                        let outcome = ctx.tracker.time(&paper, "summarize", || summarize_extracted(&paper, extracted, ctx));
+                        store_outcome(&paper, &outcome, ctx);
+                    });
+                    progress();
+                }
+            });
+        }
+
+        let extractors: Vec<_> = (0..ctx.config.pipeline.extract_threads.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    while !ctx.options.cancel.is_cancelled() {
+                        let Some(paper) = pending.lock().unwrap().pop_front() else {
+                            break;
+                        };
+                        let extracted = guard(&paper, || match extract_paper(&paper, ctx) {
+                            Ok(extracted) => Some(extracted),
+                            Err(outcome) => {
+                                store_outcome(&paper, &outcome, ctx);
+                                None
+                            }
+                        });
+                        match extracted.flatten() {
+                            Some(extracted) => {
+                                if let Err(e) = queue.push((paper, extracted)) {
+                                    println!("  {}", e);
+                                    progress();
+                                }
+                            }
+                            None => progress(),
+                        }
+                    }
+                })
+            })
+            .collect();
+        for extractor in extractors {
+            let _ = extractor.join();
+        }
+        queue.close();
+    });
+
+    let remaining = pending.lock().unwrap().len();
+    if remaining > 0 {
+        println!("Run cancelled, skipping {} remaining papers", remaining);
+    }
+    let spilled = queue.total_spilled();
+    if spilled > 0 {
+        println!("Spilled {} extracted papers to disk while summarization caught up", spilled);
+    }
+}
+
+fn guard<R>(paper: &Paper, work: impl FnOnce() -> R) -> Option<R> {
+    match panic::catch_unwind(AssertUnwindSafe(work)) {
+        Ok(result) => Some(result),
+        Err(_) => {
+            println!("  Processing panicked: {}", paper.title);
+            None
+        }
+    }
+}
+
+fn write_abstract_summaries(ctx: &RunContext, papers: &[Paper]) {
+    let pending: Vec<&Paper> = {
+        let db = ctx.db.lock().unwrap();
+        papers
+            .iter()
+            .filter(|p| db.papers.get(&p.id).is_none_or(|r| r.stage != "abstract"))
+            .collect()
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    println!("Writing abstract-based summaries for {} papers...", pending.len());
+    let ids: Vec<String> = pending.iter().map(|p| p.id.clone()).collect();
+    let abstracts = fetch_abstracts(&ctx.client, &ids);
+
+    for paper in pending {
+        if ctx.options.cancel.is_cancelled() {
+            break;
+        }
+        let Some(abstract_text) = abstracts.get(&paper.id) else {
+            println!("  No abstract available: {}", paper.title);
+            continue;
+        };
+        match generate_abstract_summary(ctx, paper, abstract_text) {
+            Ok(summary) => {
+                let summary_filename = ctx.summary_file(paper);
+                if let Err(e) = write_summary(&ctx.summary_dir.join(&summary_filename), &summary) {
+                    println!("  Failed to write abstract summary: {}", e);
+                    continue;
+                }
+                record_paper(ctx, paper, &summary_filename, "", "", "summarized");
+                let mut db = ctx.db.lock().unwrap();
+                db.set_stage(&paper.id, "abstract");
+                if let Err(e) = db.save() {
+                    println!("  Failed to update database: {}", e);
+                    ctx.tracker.fail(paper, &format!("failed to update database: {}", e));
+                }
+                println!("  Abstract summary saved: {}", summary_filename);
+            }
+            Err(e) => println!("  Failed to summarize abstract: {}", e),
+        }
+    }
+}
+
+fn generate_abstract_summary(ctx: &RunContext, paper: &Paper, abstract_text: &str) -> Result<String, String> {
+    let prompt = format!(
+        r#"Write a short preliminary summary of the following academic paper based only on its abstract.
+        Title: {}
+        arXiv ID: {}
+
+        Abstract:
+        {}
+
+        Use two sections:
+        1. **Overview**: Two or three sentences on what the paper introduces and its primary goal.
+        2. **Key Claims**: A bullet list of the main results or contributions stated in the abstract.
+
+        **Constraint:** Do not hallucinate. Use only the abstract."#,
+        paper.title, paper.id, abstract_text
+    );
+
+    let content = openai::chat(&ctx.client, &ctx.openai_key, &prompt, 500)?;
+    let mut frontmatter = Frontmatter::for_paper(paper, "summarized");
+    frontmatter.set("stage", "abstract");
+    Ok(format!(
+        "{}# {}\n\n{}\n> Preliminary summary based on the abstract. The full PDF-based summary will replace it.\n\n---\n\n{}",
+        frontmatter.render(),
+        paper.title, metadata_section(paper), content
+    ))
+}
+
+pub fn finish_run(ctx: &RunContext, fetched: usize, papers_to_process: &[Paper]) {
+    let total_papers = papers_to_process.len();
+    let new_summaries: Vec<SummaryInfo> = {
+        let db = ctx.db.lock().unwrap();
+        papers_to_process
+            .iter()
+            .filter_map(|p| db.papers.get(&p.id))
+            .filter(|r| r.status == "summarized")
+            .map(|r| SummaryInfo::from_record(r, &ctx.summary_dir))
+            .collect()
+    };
+
+    let report = RunReport {
+        fetched,
+        processed: total_papers,
+        failed: total_papers - new_summaries.len(),
+        summaries: &new_summaries,
+    };
+    if !ctx.options.offline {
+        delivery::deliver_run(&ctx.config, &ctx.client, &report);
+    }
+    if !ctx.config.obsidian.vault.is_empty() && !new_summaries.is_empty() {
+        let db = ctx.db.lock().unwrap();
+        match obsidian::sync(&ctx.config.obsidian, &db) {
+            Ok(written) => println!("Updated {} notes in the Obsidian vault", written),
+            Err(e) => println!("Failed to update the Obsidian vault: {}", e),
+        }
+    }
+    if ctx.config.git.enabled {
+        match git::commit(&ctx.config.git, &ctx.summary_dir) {
+            Ok(Some(message)) => println!("Committed summaries: {}", message),
+            Ok(None) => {}
+            Err(e) => println!("Failed to commit summaries: {}", e),
+        }
+    }
+    if !ctx.config.sync.target.is_empty()
+        && !ctx.options.offline
+        && let Err(e) = sync::sync(&ctx.client, &ctx.config.sync, &ctx.config.sync.target, ctx.config.sync.pdfs)
+    {
+        println!("Failed to sync summaries: {}", e);
+    }
+
+    let watched = ctx.watched.lock().unwrap();
+    let alerts: Vec<(&SummaryInfo, &[String])> = new_summaries
+        .iter()
+        .filter_map(|s| watched.get(&s.id).map(|authors| (s, authors.as_slice())))
+        .collect();
+    if !alerts.is_empty() {
+        let mut db = ctx.db.lock().unwrap();
+        for (summary, authors) in &alerts {
+            for author in authors.iter() {
+                db.record_watch(author, &summary.id);
+            }
+        }
+        if let Err(e) = db.save() {
+            println!("Failed to record watchlist papers: {}", e);
+        }
+        drop(db);
+        delivery::deliver_watchlist(&ctx.config, &ctx.client, &alerts);
+    }
+}
+
+pub fn select_papers(ctx: &RunContext) -> (usize, Vec<Paper>) {
+    {
+        let mut db = ctx.db.lock().unwrap();
+        let imported = migrate::import_legacy(&mut db, &ctx.summary_dir, &ctx.papers_dir);
+        if imported > 0 {
+            println!("Imported {} summaries written before ras.json tracked them", imported);
+            if !ctx.options.dry_run
+                && let Err(e) = db.save()
+            {
+                eprintln!("Failed to save imported summaries: {}", e);
+            }
+        }
+    }
+    let existing = ctx.db.lock().unwrap().papers.values().filter(|r| !r.summary_file.is_empty()).count();
+    println!("Found {} existing summaries", existing);
+
+    let papers = match &ctx.options.papers {
+        Some(papers) => papers.clone(),
+        None => {
+            let watermark = ctx.db.lock().unwrap().watermark();
+            let since = match (ctx.options.since, ctx.options.since_last_run || ctx.config.sources.since_last_run) {
+                (Some(since), _) => Some(since),
+                (None, true) if watermark.is_none() => {
+                    println!("No previous successful run recorded, fetching the recent listing");
+                    None
+                }
+                (None, true) => watermark,
+                (None, false) => None,
+            };
+            match since {
+                Some(since) => println!("Fetching papers submitted since {}...", since.format("%Y-%m-%d %H:%M UTC")),
+                None => println!("Fetching papers..."),
+            }
+            let opts = FetchOptions { limit: ctx.options.limit.unwrap_or(ctx.config.sources.limit), since, primary_only: ctx.options.primary_only };
+            let (papers, failed) = sources::fetch_all(&ctx.client, &sources::configured(&ctx.config.sources), &opts);
+            ctx.fetch_failed.store(failed > 0, Ordering::SeqCst);
+            if ctx.config.dedup.enabled {
+                let mut db = ctx.db.lock().unwrap();
+                let papers = dedup::merge(&ctx.client, &ctx.openai_key, &ctx.config.dedup, &mut db, papers);
+                if !ctx.options.dry_run
+                    && let Err(e) = db.save()
+                {
+                    println!("Failed to record merged duplicates: {}", e);
+                }
+                papers
+            } else {
+                papers
+            }
+        }
+    };
+    println!("Found {} papers", papers.len());
+    let fetched = papers.len();
+
+    let mut papers_to_process: Vec<Paper> = papers
+        .into_iter()
+        .filter(|p| !ctx.processed(p))
+        .collect();
+    if !ctx.options.offline {
+        scholar::enrich(&ctx.client, &ctx.config.semantic_scholar, &mut papers_to_process);
+    }
+    let require_code = ctx.options.require_code || ctx.config.papers_with_code.require_code;
+    if (ctx.config.papers_with_code.enabled || require_code) && !ctx.options.offline {
+        pwc::enrich(&ctx.client, &mut papers_to_process, require_code);
+    }
+
+    let mut papers_to_process = if ctx.options.papers.is_some() {
+        papers_to_process
+    } else {
+        let watched = watchlist::find(&ctx.client, &ctx.config.watchlist, &papers_to_process);
+        for paper in papers_to_process.iter_mut().filter(|p| watched.contains_key(&p.id)) {
+            println!("Watched author {}: {}", watched[&paper.id].join(", "), paper.title);
+            paper.sources.push("watchlist".to_string());
+        }
+        let ranked = relevance::rank_papers(&ctx.client, &ctx.openai_key, &ctx.config.relevance, papers_to_process, &watched, !ctx.options.dry_run);
+        *ctx.watched.lock().unwrap() = watched;
+        ranked
+    };
+
+    let pending_upgrades: Vec<Paper> = ctx
+        .db
+        .lock()
+        .unwrap()
+        .papers
+        .values()
+        .filter(|r| r.stage == "abstract" && !papers_to_process.iter().any(|p| p.id == r.id))
+        .map(|r| r.to_paper())
+        .collect();
+    if !pending_upgrades.is_empty() {
+        println!("{} abstract summaries waiting for a full upgrade", pending_upgrades.len());
+        papers_to_process.extend(pending_upgrades);
+    }
+
+    if ctx.options.papers.is_none() {
+        let max_attempts = ctx.config.pipeline.max_attempts;
+        let retries: Vec<Paper> = ctx
+            .db
+            .lock()
+            .unwrap()
+            .papers
+            .values()
+            .filter(|r| needs_retry(&r.status) && r.attempts < max_attempts)
+            .filter(|r| !papers_to_process.iter().any(|p| p.id == r.id))
+            .map(|r| r.to_paper())
+            .collect();
+        if !retries.is_empty() {
+            println!("Retrying {} papers that failed on earlier runs", retries.len());
+            papers_to_process.extend(retries);
+        }
+    }
+
+    if (ctx.options.update_versions || ctx.config.versions.resummarize) && !ctx.options.dry_run {
+        for paper in versions::prepare(ctx) {
+            papers_to_process.retain(|p| p.id != paper.id);
+            papers_to_process.push(paper);
+        }
+    }
+
+    println!("{} papers need processing", papers_to_process.len());
+
+    (fetched, papers_to_process)
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "lowercase")]
+pub enum Outcome {
+    Summarized {
+        summary: String,
+        pdf_sha256: String,
+        #[serde(default)]
+        pdf_mirror: String,
+        #[serde(default)]
+        ideas: Vec<String>,
+        #[serde(default)]
+        acronyms: Vec<(String, String)>,
+        #[serde(default)]
+        cited: Vec<Relation>,
+        #[serde(default)]
+        topics: TopicFiles,
+        #[serde(default)]
+        references: Vec<Reference>,
+        #[serde(default)]
+        grounding: Option<f64>,
+        #[serde(default)]
+        translations: Vec<(String, String)>,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        benchmarks: Vec<benchmarks::Row>,
+        #[serde(default)]
+        entities: Entities,
+    },
+    Failed {
+        error: String,
+        pdf_sha256: String,
+        #[serde(default)]
+        pdf_mirror: String,
+    },
+    Skipped { reason: String },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Extracted {
+    pub pdf_sha256: String,
+    #[serde(default)]
+    pub pdf_mirror: String,
+    pub text: String,
+    #[serde(default)]
+    pub text_source: Option<TextSource>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TextSource {
+    Html { url: String, reason: String },
+    Ocr { reason: String },
+}
+
+impl TextSource {
+    fn frontmatter(&self) -> &str {
+        match self {
+            TextSource::Html { url, .. } => url,
+            TextSource::Ocr { .. } => "ocr",
+        }
+    }
+
+    fn note(&self) -> String {
+        match self {
+            TextSource::Html { url, reason } => {
+                format!("Summarized from the HTML full text at {} because the PDF could not be used: {}", url, reason)
+            }
+            TextSource::Ocr { reason } => {
+                format!("Summarized from OCR text of the scanned PDF, so numbers, formulas and tables may contain recognition errors. Text extraction failed: {}", reason)
+            }
+        }
+    }
+}
+
+pub fn produce_outcome(paper: &Paper, ctx: &RunContext) -> Outcome {
+    match extract_paper(paper, ctx) {
+        Ok(extracted) => summarize_extracted(paper, extracted, ctx),
+        Err(outcome) => *outcome,
+    }
+}
+
+pub fn extract_paper(paper: &Paper, ctx: &RunContext) -> Result<Extracted, Box<Outcome>> {
+    let cancel = ctx.options.paper_token(&paper.id);
+    if cancel.is_cancelled() {
+        return Err(Box::new(Outcome::Skipped { reason: CANCELLED.to_string() }));
+    }
+    println!("Processing: {}", paper.title);
+
+    let outcome = match extract_pdf(paper, ctx, &cancel) {
+        Ok(extracted) => return Ok(extracted),
+        Err(outcome) => outcome,
+    };
+    let (reason, pdf_sha256, pdf_mirror) = match outcome.as_ref() {
+        Outcome::Skipped { reason } if reason != CANCELLED => (reason.clone(), String::new(), String::new()),
+        Outcome::Failed { error, pdf_sha256, pdf_mirror } => (error.clone(), pdf_sha256.clone(), pdf_mirror.clone()),
+        _ => return Err(outcome),
+    };
+    if !ctx.config.summary.html_fallback || ctx.options.offline || arxiv::normalize_id(&paper.id).is_none() {
+        return Err(outcome);
+    }
+
+    println!("  Trying the HTML full text instead: {}", paper.title);
+    match ctx.tracker.time(paper, "html", || ar5iv::fetch_text(&ctx.client, &paper.id)) {
+        Ok((url, text)) => {
+            println!("  Using HTML full text from {}: {}", url, paper.title);
+            Ok(Extracted { pdf_sha256, pdf_mirror, text, text_source: Some(TextSource::Html { url, reason }) })
+        }
+        Err(e) => {
+            println!("  HTML full text unavailable: {}", e);
+            Err(outcome)
+        }
+    }
+}
+
+fn extract_pdf(paper: &Paper, ctx: &RunContext, cancel: &CancellationToken) -> Result<Extracted, Box<Outcome>> {
+    let pdf_filename = ctx.pdf_file(paper);
+    let pdf_path = ctx.papers_dir.join(&pdf_filename);
+
+    let recorded_sha256 = ctx.db.lock().unwrap().papers.get(&paper.id).map(|r| r.pdf_sha256.clone()).unwrap_or_default();
+    if pdf_path.exists() {
+        let problem = validate_pdf(&pdf_path).err().or_else(|| {
+            let matches = recorded_sha256.is_empty() || sha256_file(&pdf_path).is_ok_and(|sha| sha == recorded_sha256);
+            (!matches).then(|| "checksum differs from the database".to_string())
+        });
+        if let Some(problem) = problem {
+            println!("  Existing PDF is corrupted ({}), downloading it again: {}", problem, pdf_filename);
+            let _ = fs::remove_file(&pdf_path);
+        }
+    }
+
+    let mut pdf_mirror = String::new();
+    if !pdf_path.exists() && ctx.options.offline {
+        println!("  PDF not downloaded, skipping while offline: {}", paper.title);
+        return Err(Box::new(Outcome::Skipped { reason: OFFLINE_SKIPPED.to_string() }));
+    }
+    if !pdf_path.exists() {
+        let _permit = ctx.downloads.acquire();
+        println!("  Downloading PDF: {}", paper.title);
+        let urls = arxiv::pdf_urls(paper, &ctx.config.sources.arxiv.mirrors);
+        match ctx.tracker.time(paper, "download", || download_from_mirrors(&ctx.client, &urls, &pdf_path, cancel)) {
+            Ok(url) => {
+                println!("  PDF saved: {} (from {})", pdf_filename, url);
+                ctx.tracker.downloaded(paper);
+                pdf_mirror = url;
+            }
+            Err(e) => {
+                println!("  Failed to download PDF: {}", e);
+                return Err(Box::new(Outcome::Skipped { reason: format!("download failed: {}", e) }));
+            }
+        }
+    } else {
+        println!("  PDF already exists: {}", pdf_filename);
+    }
+
+    if let Err(e) = validate_pdf(&pdf_path) {
+        println!("  Invalid PDF ({}): {}", e, pdf_filename);
+        let _ = fs::remove_file(&pdf_path);
+        return Err(Box::new(Outcome::Skipped { reason: format!("invalid PDF: {}", e) }));
+    }
+
+    let pdf_sha256 = sha256_file(&pdf_path).unwrap_or_default();
+    if let Some(extracted) = process::saved_extraction(paper, &pdf_sha256) {
+        println!("  Using previously extracted text: {}", paper.title);
+        ctx.tracker.extracted(paper);
+        return Ok(extracted);
+    }
+
+    let permit = ctx.extractions.acquire();
+    println!("  Extracting text from PDF: {}", paper.title);
+    let extracted = ctx.tracker.time(paper, "extract", || extraction::extract(&pdf_path, cancel));
+    drop(permit);
+    match extracted {
+        Ok(extraction::Extraction { text, backend, problems }) => {
+            if text.trim().is_empty() {
+                println!("  PDF text extraction returned empty content: {}", paper.title);
+                return Err(Box::new(Outcome::Failed {
+                    error: "PDF text extraction returned empty content".to_string(),
+                    pdf_sha256,
+                    pdf_mirror,
+                }));
+            }
+            ctx.tracker.extracted(paper);
+            let text_source = (backend == extraction::OCR_BACKEND).then(|| TextSource::Ocr { reason: problems.join("; ") });
+            Ok(Extracted { pdf_sha256, pdf_mirror, text, text_source })
+        },
+        Err(e) if e == CANCELLED => Err(Box::new(Outcome::Skipped { reason: e })),
+        Err(e) => {
+            println!("  Failed to extract PDF text: {}", e);
+            Err(Box::new(Outcome::Failed { error: e, pdf_sha256, pdf_mirror }))
+        }
+    }
+}
+
+pub fn summarize_extracted(paper: &Paper, extracted: Extracted, ctx: &RunContext) -> Outcome {
+    let cancel = ctx.options.paper_token(&paper.id);
+    if cancel.is_cancelled() {
+        return Outcome::Skipped { reason: CANCELLED.to_string() };
+    }
+    let Extracted { pdf_sha256, pdf_mirror, text, text_source } = extracted;
+    let mut pdf_text = cleaning::clean(&text, &ctx.config.cleaning);
+
+    let language = language::detect(&pdf_text);
+    if let Some(language) = language.as_ref().filter(|l| !l.is_english()) {
+        println!("  Paper is written in {}: {}", language.name, paper.title);
+        match ctx.config.language.policy.as_str() {
+            "skip" => {
+                let mut db = ctx.db.lock().unwrap();
+                db.upsert_paper(paper, "", "skipped");
+                if let Some(record) = db.papers.get_mut(&paper.id) {
+                    record.language = language.code.clone();
+                }
+                if let Err(e) = db.save() {
+                    println!("  Failed to update database: {}", e);
+                    return Outcome::Failed { error: format!("failed to update database: {}", e), pdf_sha256, pdf_mirror };
+                }
+                return Outcome::Skipped { reason: format!("{}: written in {}", language::SKIPPED, language.name) };
+            }
+            "translate" => {
+                println!("  Translating paper text from {}: {}", language.name, paper.title);
+                match language::translate(&ctx.client, &ctx.openai_key, language, &pdf_text, MAX_PROMPT_CHARS) {
+                    Ok(translated) => pdf_text = translated,
+                    Err(e) => {
+                        println!("  Failed to translate paper text: {}", e);
+                        pdf_text = format!("{}{}", language::instruction(language), pdf_text);
+                    }
+                }
+            }
+            "instruct" => pdf_text = format!("{}{}", language::instruction(language), pdf_text),
+            _ => {}
+        }
+    }
+
+    let cited = series::cited_companions(&text);
+    let companions = if ctx.config.summary.combine_related {
+        let relations = series::detect(paper, &cited, &ctx.db.lock().unwrap());
+        series::companion_texts(ctx, &relations)
+    } else {
+        Vec::new()
+    };
+
+    println!("  Generating summary: {}", paper.title);
+    let mut topics = Vec::new();
+    let result = openai::with_cancel(&cancel, || if ctx.options.depth == Depth::Tldr {
+        generate_summary(ctx, paper, &pdf_text, &cancel)
+    } else if !companions.is_empty() {
+        println!("  Reading {} related papers together: {}", companions.len(), paper.title);
+        cancel.check().and_then(|_| series::summarize_combined(ctx, paper, &pdf_text, &companions))
+    } else if survey::is_survey(&ctx.config.survey, paper, &pdf_text) {
+        println!("  Splitting survey into per-topic summaries: {}", paper.title);
+        match survey::summarize(ctx, paper, &pdf_text, &cancel) {
+            Ok(Some((overview, files))) => {
+                topics = files;
+                Ok(overview)
+            }
+            Ok(None) => generate_summary(ctx, paper, &pdf_text, &cancel),
+            Err(e) => Err(e),
+        }
+    } else {
+        generate_summary(ctx, paper, &pdf_text, &cancel)
+    });
+    match result {
+        Ok(mut summary) => {
+            let model = openai::last_model();
+            if !model.is_empty() {
+                summary = set_frontmatter_field(&summary, "model", &model);
+            }
+            if let Some((name, _)) = &ctx.options.prompt {
+                summary = set_frontmatter_field(&summary, "prompt", name);
+            }
+            if let Some(language) = &language {
+                summary = set_frontmatter_field(&summary, "language", &language.code);
+            }
+            let grounding = if ctx.config.summary.verify_numbers {
+                let check = grounding::verify(&summary, &pdf_text);
+                summary = check.summary.clone();
+                if !check.unverified.is_empty() {
+                    println!("  {} of {} numbers not found in the paper text: {}", check.unverified.len(), check.total, paper.title);
+                    summary.push_str(&format!(
+                        "\n\n> {} of {} numbers in this summary could not be found in the paper text and are marked [unverified].\n",
+                        check.unverified.len(),
+                        check.total
+                    ));
+                }
+                let score = check.score();
+                if let Some(score) = score {
+                    summary = set_frontmatter_field(&summary, "grounding", &format!("{:.2}", score));
+                }
+                score
+            } else {
+                None
+            };
+            let update = ctx.versions.lock().unwrap().get(&paper.id).cloned();
+            if let Some(update) = update {
+                println!("  Describing changes since v{}: {}", update.previous, paper.title);
+                summary = set_frontmatter_field(&summary, "version", &update.current.to_string());
+                let previous = fs::read_to_string(ctx.summary_dir.join(ctx.summary_file(paper))).map_err(|e| e.to_string());
+                match previous.and_then(|previous| versions::changelog(&ctx.client, &ctx.openai_key, &previous, &update, &summary)) {
+                    Ok(changes) => summary.push_str(&changes),
+                    Err(e) => println!("  Failed to describe version changes: {}", e),
+                }
+            }
+            if let Some(source) = &text_source {
+                summary = set_frontmatter_field(&summary, "text_source", source.frontmatter());
+                summary.push_str(&format!("\n\n> {}\n", source.note()));
+            }
+            if ctx.config.sources.openreview.reviews
+                && let Some(forum) = openreview::split_id(&paper.id)
+            {
+                match openreview::criticism(&ctx.client, &ctx.openai_key, paper, forum) {
+                    Ok(Some(section)) => summary.push_str(&format!("\n\n## What Reviewers Criticized\n\n{}\n", section)),
+                    Ok(None) => {}
+                    Err(e) => println!("  Failed to summarize reviews: {}", e),
+                }
+            }
+            if ctx.config.papers_with_code.leaderboard
+                && capabilities::available(Capability::PapersWithCode)
+                && let Some(code) = paper.code.as_ref().filter(|c| !c.results.is_empty())
+            {
+                println!("  Cross-checking results against Papers with Code leaderboards: {}", paper.title);
+                let context = pwc::leaderboard_context(&ctx.client, code, &pdf_text);
+                if !context.is_empty() {
+                    summary.push_str(&format!("\n\n{}", context));
+                }
+            }
+            let ideas = if ctx.config.ideas.enabled {
+                println!("  Extracting open questions: {}", paper.title);
+                ideas::extract(&ctx.client, &ctx.openai_key, paper, &pdf_text).unwrap_or_else(|e| {
+                    println!("  Failed to extract open questions: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            let benchmarks = if ctx.config.benchmarks.enabled {
+                println!("  Extracting results tables: {}", paper.title);
+                benchmarks::extract(&ctx.client, &ctx.openai_key, paper, &pdf_text).unwrap_or_else(|e| {
+                    println!("  Failed to extract results tables: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            let acronyms = if ctx.config.glossary.enabled { glossary::extract(&pdf_text) } else { Vec::new() };
+            let references = references::parse(&text);
+            let tags = if ctx.config.tagging.enabled {
+                let vocabulary = topics::vocabulary(&ctx.db.lock().unwrap());
+                match topics::tag(&ctx.client, &ctx.openai_key, paper, &summary, &vocabulary) {
+                    Ok(tags) => {
+                        summary = set_frontmatter_field(&summary, "tags", &tags.join(", "));
+                        tags
+                    }
+                    Err(e) => {
+                        println!("  Failed to tag summary: {}", e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let entities = if ctx.config.entities.enabled {
+                let known = entities::vocabulary(&ctx.db.lock().unwrap());
+                match entities::extract(&ctx.client, &ctx.openai_key, paper, &summary, &pdf_text, &known) {
+                    Ok(entities) => {
+                        for (field, names) in entities.frontmatter() {
+                            summary = set_frontmatter_field(&summary, &field, &names);
+                        }
+                        entities
+                    }
+                    Err(e) => {
+                        println!("  Failed to extract entities: {}", e);
+                        Entities::default()
+                    }
+                }
+            } else {
+                Entities::default()
+            };
+            let languages = if ctx.options.languages.is_empty() { &ctx.config.summary.languages } else { &ctx.options.languages };
+            let mut translations = Vec::new();
+            for language in languages {
+                println!("  Translating summary to {}: {}", language, paper.title);
+                match translate::translate(&ctx.client, &ctx.openai_key, &summary, language) {
+                    Ok(translated) => translations.push((language.clone(), translated)),
+                    Err(e) => println!("  Failed to translate summary to {}: {}", language, e),
+                }
+            }
+            Outcome::Summarized { summary, pdf_sha256, pdf_mirror, ideas, acronyms, cited, topics, references, grounding, translations, tags, benchmarks, entities }
+        }
+        Err(e) if e == CANCELLED => Outcome::Skipped { reason: e },
+        Err(e) => {
+            println!("  Failed to generate summary: {}", e);
+            Outcome::Failed { error: e, pdf_sha256, pdf_mirror }
+        }
+    }
+}
+
+pub fn store_outcome(paper: &Paper, outcome: &Outcome, ctx: &RunContext) {
+    ctx.tracker.finish(paper, outcome);
+    let summary_filename = ctx.summary_file(paper);
+    let summary_path = ctx.summary_dir.join(&summary_filename);
+
+    match outcome {
+        Outcome::Summarized { summary, pdf_sha256, pdf_mirror, ideas, acronyms, cited, topics, references, grounding, translations, tags, benchmarks, entities } => {
+            let (upgraded, library, notes) = {
+                let db = ctx.db.lock().unwrap();
+                let record = db.papers.get(&paper.id);
+                let upgraded = record.is_some_and(|r| r.stage == "abstract");
+                let notes = fs::read_to_string(&summary_path)
+                    .ok()
+                    .and_then(|existing| summary::notes(&existing))
+                    .or_else(|| record.map(|r| r.notes.clone()))
+                    .unwrap_or_default();
+                (upgraded, references::library_section(&db, &paper.id, &summary_filename, references), notes)
+            };
+            process::discard_extraction(paper);
+            let stage = if upgraded { "upgraded" } else { "full" };
+            let summary = summary::with_notes(&format!("{}{}", set_frontmatter_field(summary, "stage", stage), library), &notes);
+            let update = ctx.versions.lock().unwrap().get(&paper.id).cloned();
+            if let Some(update) = update
+                && let Err(e) = versions::archive_summary(&ctx.summary_dir, &summary_filename, &update)
+            {
+                println!("  Failed to archive the v{} summary: {}", update.previous, e);
+                ctx.tracker.fail(paper, &format!("failed to archive the v{} summary: {}", update.previous, e));
+                record_failure(ctx, paper, &format!("failed to archive the v{} summary: {}", update.previous, e));
+                return;
+            }
+            if let Err(e) = write_summary(&summary_path, &summary) {
+                println!("  Failed to write summary: {}", e);
+                ctx.tracker.fail(paper, &format!("failed to write summary: {}", e));
+                record_failure(ctx, paper, &format!("failed to write summary: {}", e));
+                return;
+            }
+            for (language, content) in translations {
+                let file = translate::filename(&summary_filename, language);
+                if let Err(e) = fs::write(ctx.summary_dir.join(&file), content) {
+                    println!("  Failed to write translated summary {}: {}", file, e);
+                }
+            }
+            for (file, content) in topics {
+                let path = ctx.summary_dir.join(file);
+                if let Some(parent) = path.parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                if let Err(e) = fs::write(&path, content) {
+                    println!("  Failed to write topic summary {}: {}", file, e);
+                }
+            }
+            record_paper(ctx, paper, &summary_filename, pdf_sha256, pdf_mirror, "summarized");
+            let mut db = ctx.db.lock().unwrap();
+            db.set_stage(&paper.id, stage);
+            if let Some(record) = db.papers.get_mut(&paper.id) {
+                record.references = references.clone();
+                record.grounding = *grounding;
+                record.entities = entities.clone();
+                record.language = Frontmatter::parse(&summary).and_then(|(frontmatter, _)| frontmatter.get("language").map(str::to_string)).unwrap_or_default();
+                if let Some(update) = ctx.versions.lock().unwrap().get(&paper.id) {
+                    record.version = update.current;
+                }
+            }
+            for tag in tags {
+                db.add_tag(&paper.id, tag);
+            }
+            let relations = series::detect(paper, cited, &db);
+            if !relations.is_empty() {
+                println!("  Linked {} related papers: {}", relations.len(), paper.title);
+                series::link(&mut db, &paper.id, &relations);
+            }
+            let has_pdf = db.papers.get(&paper.id).is_some_and(|r| !r.pdf_file.is_empty() && ctx.papers_dir.join(&r.pdf_file).exists());
+            if ctx.config.storage.delete_pdf_after_summary
+                && has_pdf
+                && let Err(e) = storage::remove_pdf(&mut db, &ctx.papers_dir, &paper.id)
+            {
+                println!("  Failed to delete PDF: {}", e);
+            }
+            if ctx.config.files.format == "org"
+                && let Some(record) = db.papers.get(&paper.id)
+                && let Err(e) = fs::write(summary_path.with_extension("org"), org::render_file(record, &summary))
+            {
+                println!("  Failed to write org summary: {}", e);
+            }
+            if let Err(e) = db.save() {
+                println!("  Failed to update database: {}", e);
+                ctx.tracker.fail(paper, &format!("failed to update database: {}", e));
+            }
+            drop(db);
+            if upgraded {
+                println!("  Summary upgraded: {}", summary_filename);
+            } else {
+                println!("  Summary saved: {}", summary_filename);
+            }
+            if !ideas.is_empty() {
+                match ideas::record(paper, ideas) {
+                    Ok(added) => println!("  Added {} research ideas: {}", added, paper.title),
+                    Err(e) => println!("  Failed to update ideas log: {}", e),
+                }
+            }
+            if ctx.config.benchmarks.enabled {
+                match benchmarks::record(paper, benchmarks) {
+                    Ok(count) => println!("  Recorded {} benchmark results: {}", count, paper.title),
+                    Err(e) => println!("  Failed to update benchmarks table: {}", e),
+                }
+            }
+            if !acronyms.is_empty() {
+                match glossary::record(paper, acronyms) {
+                    Ok(added) => println!("  Added {} acronyms to the glossary: {}", added, paper.title),
+                    Err(e) => println!("  Failed to update glossary: {}", e),
+                }
+            }
+        }
+        Outcome::Failed { error, .. } if ctx.versions.lock().unwrap().contains_key(&paper.id) => {
+            println!("  Keeping the previous version's summary: {}", summary_filename);
+            record_failure(ctx, paper, error);
+        }
+        Outcome::Failed { error, pdf_sha256, pdf_mirror } => {
+            let content = format!(
+                "{}# {}\n\n**arXiv ID**: {}\n**PDF**: {}\n\n---\n\n## Error\n\nFailed to process this paper.\n\n**Error**: {}\n\nPlease review manually.",
+                Frontmatter::for_paper(paper, "error").render(),
+                paper.title, paper.id, paper.pdf_url, error
+            );
+            let _ = write_summary(&summary_path, &content);
+            record_paper(ctx, paper, &summary_filename, pdf_sha256, pdf_mirror, "error");
+            println!("  Error summary saved: {}", summary_filename);
+            record_failure(ctx, paper, error);
+        }
+        Outcome::Skipped { reason } if reason.starts_with(language::SKIPPED) => println!("  Not summarized, {}", reason),
+        Outcome::Skipped { reason } if !expected_skip(reason) => record_failure(ctx, paper, reason),
+        Outcome::Skipped { .. } => {}
+    }
+}
+
+fn needs_retry(status: &str) -> bool {
+    status == "error" || status == "failed"
+}
+
+fn expected_skip(reason: &str) -> bool {
+    reason == CANCELLED || reason == OFFLINE_SKIPPED || reason.starts_with(language::SKIPPED)
+}
+
+pub fn record_failure(ctx: &RunContext, paper: &Paper, error: &str) {
+    let mut db = ctx.db.lock().unwrap();
+    db.record_failure(paper, error);
+    ctx.pin_file_date(&mut db, paper);
+    let attempts = db.papers.get(&paper.id).map_or(0, |r| r.attempts);
+    if attempts < ctx.config.pipeline.max_attempts {
+        println!("  Will retry on the next run (attempt {}/{}): {}", attempts, ctx.config.pipeline.max_attempts, paper.title);
+    } else {
+        println!("  Giving up after {} attempts: {}", attempts, paper.title);
+    }
+    if let Err(e) = db.save() {
+        println!("  Failed to update database: {}", e);
+    }
+}
+
+pub fn write_summary(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
+
+fn record_paper(ctx: &RunContext, paper: &Paper, summary_filename: &str, pdf_sha256: &str, pdf_mirror: &str, status: &str) {
+    let pdf_file = ctx.pdf_file(paper);
+    let mut db = ctx.db.lock().unwrap();
+    db.upsert_paper(paper, summary_filename, status);
+    ctx.pin_file_date(&mut db, paper);
+    if let Some(record) = db.papers.get_mut(&paper.id) {
+        record.pdf_file = pdf_file;
+        record.pdf_sha256 = pdf_sha256.to_string();
+        if !pdf_mirror.is_empty() {
+            record.pdf_mirror = pdf_mirror.to_string();
+        }
+        if status == "summarized" {
+            record.attempts = 0;
+            record.last_error.clear();
+        }
+    }
+    if let Err(e) = db.save() {
+        println!("  Failed to update database: {}", e);
+        ctx.tracker.fail(paper, &format!("failed to update database: {}", e));
+    }
+}
+
+pub fn slugify(text: &str, slug: &SlugConfig) -> String {
+    let text = if slug.lowercase { text.to_lowercase() } else { text.to_string() };
+    let mut out = String::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let separator = if out.is_empty() { "" } else { slug.separator.as_str() };
+        let length = out.chars().count() + separator.chars().count() + word.chars().count();
+        if slug.max_length > 0 && length > slug.max_length {
+            if out.is_empty() {
+                out = word.chars().take(slug.max_length).collect();
+            }
+            break;
+        }
+        out.push_str(separator);
+        out.push_str(word);
+    }
+    out
+}
+
+pub fn file_stem(paper: &Paper, files: &FilesConfig, at: DateTime<Local>) -> String {
+    let category = if paper.category.is_empty() { "uncategorized" } else { &paper.category };
+    let stem = files
+        .pattern
+        .replace("{id}", &sanitize_filename(&paper.id))
+        .replace("{title-slug}", &slugify(&paper.title, &files.slug))
+        .replace("{title}", &sanitize_filename(&paper.title))
+        .replace("{date}", &at.format("%Y-%m-%d").to_string())
+        .replace("{category}", &sanitize_filename(category));
+    sanitize_filename(&stem)
+}
+
+pub fn pdf_filename(paper: &Paper, files: &FilesConfig, at: DateTime<Local>) -> String {
+    format!("{}.pdf", file_stem(paper, files, at))
+}
+
+pub fn summary_filename(paper: &Paper, files: &FilesConfig, at: DateTime<Local>) -> String {
+    let category = if paper.category.is_empty() { "uncategorized" } else { &paper.category };
+    let dir: Vec<String> = files
+        .layout
+        .replace("{year}", &at.format("%Y").to_string())
+        .replace("{month}", &at.format("%m").to_string())
+        .replace("{category}", category)
+        .split('/')
+        .map(sanitize_filename)
+        .filter(|part| !part.is_empty() && part != "." && part != "..")
+        .collect();
+    let file = format!("{}-summary.md", file_stem(paper, files, at));
+    if dir.is_empty() { file } else { format!("{}/{}", dir.join("/"), file) }
+}
+
+pub fn relative_link(from: &str, to: &str) -> String {
+    let from_dirs: Vec<&str> = from.split('/').collect();
+    let from_dirs = &from_dirs[..from_dirs.len() - 1];
+    let to_parts: Vec<&str> = to.split('/').collect();
+    let common = from_dirs.iter().zip(&to_parts).take_while(|(a, b)| a == b).count().min(to_parts.len() - 1);
+    let mut parts: Vec<&str> = vec![".."; from_dirs.len() - common];
+    parts.extend(&to_parts[common..]);
+    parts.join("/").replace(' ', "%20")
+}
+
+pub fn sanitize_filename(name: &str) -> String {
+    let sanitized = SANITIZE_REGEX.replace_all(name, "_").to_string();
+    let sanitized = sanitized.replace(' ', "_");
+    let sanitized = sanitized.trim().to_string();
+    if sanitized.chars().count() > 200 {
+        sanitized.chars().take(200).collect()
+    } else {
+        sanitized
+    }
+}
+
+pub fn generate_summary(ctx: &RunContext, paper: &Paper, pdf_text: &str, cancel: &CancellationToken) -> Result<String, String> {
+    let mut summary_content = openai::in_pass("draft", || draft_summary(ctx, paper, pdf_text, cancel))?;
+    if ctx.options.refine || ctx.config.summary.refine {
+        cancel.check()?;
+        println!("  Refining summary: {}", paper.title);
+        summary_content = openai::in_pass("refine", || refine_summary(ctx, paper, pdf_text, &summary_content))?;
+    }
+    Ok(set_frontmatter_field(&render_summary(paper, &summary_content), "depth", ctx.options.depth.name()))
+}
+
+fn refine_summary(ctx: &RunContext, paper: &Paper, pdf_text: &str, draft: &str) -> Result<String, String> {
+    let budget = MAX_PROMPT_CHARS.saturating_sub(draft.chars().count());
+    let text = if ctx.config.summary.prioritize_sections {
+        chunking::prompt_text(pdf_text, budget)
+    } else {
+        pdf_text.chars().take(budget).collect()
+    };
+    let prompt = format!(
+        r#"Below is a draft summary of an academic paper, followed by the paper text. Review the draft against the text.
+        Title: {}
+        arXiv ID: {}
+
+        Draft Summary:
+        {}
+
+        Paper Content:
+        {}
+
+        Produce a corrected final summary:
+        - Add key results, methods or limitations from the text that the draft omits, with their exact numbers.
+        - Remove or correct every claim or number the text does not support.
+        - Keep the draft's sections, headings, format and any citation anchors such as [§4.2].
+
+        Return only the final summary, without commentary about the changes."#,
+        paper.title, paper.id, draft, text
+    );
+    openai::chat(&ctx.client, &ctx.openai_key, &prompt, ctx.options.depth.max_tokens() + 500)
+}
+
+pub fn needs_chunking(ctx: &RunContext, pdf_text: &str) -> bool {
+    ctx.options.depth != Depth::Tldr && chunking::should_chunk(&ctx.config.summary, pdf_text)
+}
+
+pub fn fill_prompt_template(template: &str, paper: &Paper, sections: &str, text: &str) -> String {
+    let prompt = template
+        .replace("{title}", &paper.title)
+        .replace("{id}", &paper.id)
+        .replace("{arxiv_id}", &paper.id)
+        .replace("{pdf_url}", &paper.pdf_url)
+        .replace("{sections}", sections);
+    if prompt.contains("{text}") {
+        prompt.replace("{text}", text)
+    } else {
+        format!("{}\n\nPaper Content:\n{}", prompt, text)
+    }
+}
+
+pub fn draft_prompt(ctx: &RunContext, paper: &Paper, pdf_text: &str) -> String {
+    let depth = ctx.options.depth;
+    let budget = if depth == Depth::Tldr { TLDR_CHARS } else { MAX_PROMPT_CHARS };
+    let truncated_text = if ctx.config.summary.prioritize_sections {
+        chunking::prompt_text(pdf_text, budget)
+    } else {
+        pdf_text.chars().take(budget).collect()
+    };
+    let captions = if depth == Depth::Tldr { String::new() } else { caption_block(&ctx.config.summary, pdf_text) };
+
+    if let Some((_, template)) = &ctx.options.prompt {
+        return fill_prompt_template(template, paper, &depth.sections(), &truncated_text);
+    }
+
+    format!(
+        r#"Please provide a comprehensive, evidence-based summary of the following academic paper based on the provided text.
+        Title: {}
+        arXiv ID: {}
+        PDF URL: {}
+
+        Paper Content:
+        {}
+
+        {}
+        {}
+        **Constraint:** Do not hallucinate. Base the summary *strictly* on the provided text context."#,
+        paper.title, paper.id, paper.pdf_url, &truncated_text, depth.sections(), captions
+    )
+}
+
+fn draft_summary(ctx: &RunContext, paper: &Paper, pdf_text: &str, cancel: &CancellationToken) -> Result<String, String> {
+    if let Some(draft) = ctx.drafts.lock().unwrap().remove(&paper.id) {
+        return Ok(draft);
+    }
+    let depth = ctx.options.depth;
+    if needs_chunking(ctx, pdf_text) {
+        println!("  Using chunked summarization: {}", paper.title);
+        chunking::summarize_chunked(&ctx.client, &ctx.openai_key, &ctx.config.summary, depth, paper, pdf_text, cancel)
+    } else {
+        let prompt = draft_prompt(ctx, paper, pdf_text);
+        cancel.check()?;
+        openai::chat(&ctx.client, &ctx.openai_key, &prompt, depth.max_tokens())
+    }
+}
+
+pub fn caption_block(config: &SummaryConfig, text: &str) -> String {
+    if config.captions { captions::prompt_block(text) } else { String::new() }
+}
+
+pub fn render_summary(paper: &Paper, summary_content: &str) -> String {
+    format!(
+        "{}# {}\n\n{}\n---\n\n{}",
+        Frontmatter::for_paper(paper, "summarized").render(),
+        paper.title, metadata_section(paper), summary_content
+    )
+}
+
+fn metadata_section(paper: &Paper) -> String {
+    let mut section = format!("**arXiv ID**: {}\n**PDF**: {}\n", paper.id, paper.pdf_url);
+    if let Some(code) = &paper.code {
+        section.push_str(&code.metadata_lines());
+        section.push('\n');
+        section.push_str(&code.results_table());
+    } else {
+        section.push('\n');
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn paper(id: &str, title: &str) -> Paper {
+        Paper {
+            id: id.to_string(),
+            title: title.to_string(),
+            pdf_url: String::new(),
+            category: "cs.LG".to_string(),
+            scholar: None,
+            code: None,
+            sources: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn slugifies_titles() {
+        let slug = SlugConfig::default();
+        assert_eq!(slugify("Attention Is All You Need!", &slug), "attention-is-all-you-need");
+        assert_eq!(slugify("  GPT-4: A Report ", &SlugConfig { lowercase: false, separator: "_".to_string(), max_length: 0 }), "GPT_4_A_Report");
+        assert_eq!(slugify("Scaling Laws for Neural Language Models", &SlugConfig { max_length: 20, ..Default::default() }), "scaling-laws-for");
+        assert_eq!(slugify("Supercalifragilistic", &SlugConfig { max_length: 5, ..Default::default() }), "super");
+    }
+
+    #[test]
+    fn fills_in_the_file_pattern() {
+        let at = Local.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let fast = paper("2503.01234", "Fast: Tokenizers / Parsers");
+        let files = |pattern: &str| FilesConfig { pattern: pattern.to_string(), ..Default::default() };
+        assert_eq!(file_stem(&fast, &files("{id}-{title}"), at), "2503.01234-Fast__Tokenizers___Parsers");
+        assert_eq!(file_stem(&fast, &files("{date}-{id}-{title-slug}"), at), "2025-03-01-2503.01234-fast-tokenizers-parsers");
+        assert_eq!(file_stem(&fast, &files("{category}/{id}"), at), "cs.LG_2503.01234");
+        assert_eq!(file_stem(&paper("hep-th/9901001", "T"), &files("{id}"), at), "hep-th_9901001");
+    }
+
+    #[test]
+    fn names_pdf_and_summary_from_the_same_date() {
+        let at = Local.with_ymd_and_hms(2025, 3, 1, 23, 59, 0).unwrap();
+        let fast = paper("2503.01234", "Fast");
+        let files = FilesConfig { pattern: "{date}-{id}".to_string(), ..Default::default() };
+        assert_eq!(pdf_filename(&fast, &files, at), "2025-03-01-2503.01234.pdf");
+        assert!(summary_filename(&fast, &files, at).ends_with("2025-03-01-2503.01234-summary.md"));
+    }
+
+    #[test]
+    fn policy_skips_are_not_failures() {
+        assert!(expected_skip(CANCELLED));
+        assert!(expected_skip(OFFLINE_SKIPPED));
+        assert!(expected_skip(&format!("{}: written in German", language::SKIPPED)));
+        assert!(!expected_skip("PDF text extraction returned empty content"));
+    }
+
+    #[test]
+    fn retries_papers_that_only_have_an_error_summary() {
+        assert!(needs_retry("error"));
+        assert!(needs_retry("failed"));
+        assert!(!needs_retry("summarized"));
+        assert!(!needs_retry("skipped"));
+    }
+
+    #[test]
+    fn links_between_summaries_relatively() {
+        assert_eq!(relative_link("summary/a-summary.md", "summary/b-summary.md"), "b-summary.md");
+        assert_eq!(relative_link("summary/2025/03/a-summary.md", "summary/2024/12/b c-summary.md"), "../../2024/12/b%20c-summary.md");
+        assert_eq!(relative_link("summary/a-summary.md", "summary/a-topics/1.md"), "a-topics/1.md");
+        assert_eq!(relative_link("summary/a-topics/1.md", "summary/a-summary.md"), "../a-summary.md");
+    }
+}
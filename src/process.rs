@@ -0,0 +1,131 @@
+use crate::arxiv::{self, Paper};
+use crate::cli::Args;
+use crate::config::Config;
+use crate::error::RasError;
+use crate::pipeline::{self, Extracted, RunContext, RunOptions, sanitize_filename};
+use std::fs;
+use std::path::PathBuf;
+
+fn extracted_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("extracted"))
+}
+
+fn extracted_path(paper: &Paper) -> Result<PathBuf, RasError> {
+    Ok(extracted_dir()?.join(format!("{}.json", sanitize_filename(&paper.id))))
+}
+
+pub fn saved_extraction(paper: &Paper, pdf_sha256: &str) -> Option<Extracted> {
+    let content = fs::read_to_string(extracted_path(paper).ok()?).ok()?;
+    serde_json::from_str::<Extracted>(&content).ok().filter(|e| e.pdf_sha256 == pdf_sha256)
+}
+
+pub fn discard_extraction(paper: &Paper) {
+    if let Ok(path) = extracted_path(paper) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn untracked_paper(stem: &str) -> Paper {
+    match arxiv::normalize_id(stem.split('-').next().unwrap_or(stem)) {
+        Some(id) => Paper {
+            pdf_url: format!("https://arxiv.org/pdf/{}.pdf", id),
+            title: stem.split_once('-').map_or(stem, |(_, title)| title).replace('_', " "),
+            id,
+            category: String::new(),
+            scholar: None,
+            code: None,
+            sources: vec!["local".to_string()],
+        },
+        None => Paper {
+            id: format!("local:{}", stem),
+            title: stem.replace('_', " "),
+            pdf_url: String::new(),
+            category: String::new(),
+            scholar: None,
+            code: None,
+            sources: vec!["local".to_string()],
+        },
+    }
+}
+
+fn backlog(ctx: &RunContext) -> Vec<Paper> {
+    let mut files: Vec<String> = fs::read_dir(&ctx.papers_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".pdf"))
+        .collect();
+    files.sort();
+
+    let mut db = ctx.db.lock().unwrap();
+    let mut papers = Vec::new();
+    for file in files {
+        if let Some(record) = db.papers.values().find(|r| r.pdf_file == file) {
+            if record.status != "summarized" || record.stage == "abstract" {
+                papers.push(record.to_paper());
+            }
+            continue;
+        }
+        let paper = untracked_paper(file.trim_end_matches(".pdf"));
+        if db.papers.get(&paper.id).is_some_and(|r| r.status == "summarized") {
+            continue;
+        }
+        db.upsert_paper(&paper, "", "pending");
+        if let Some(record) = db.papers.get_mut(&paper.id) {
+            record.pdf_file = file;
+        }
+        papers.push(paper);
+    }
+    if let Err(e) = db.save() {
+        println!("Failed to update database: {}", e);
+    }
+    papers
+}
+
+fn extract_only(ctx: &RunContext, papers: &[Paper]) -> Result<(), String> {
+    fs::create_dir_all(extracted_dir()?).map_err(|e| e.to_string())?;
+    let mut saved = 0;
+    for paper in papers {
+        match pipeline::extract_paper(paper, ctx) {
+            Ok(extracted) => {
+                let json = serde_json::to_string(&extracted).map_err(|e| e.to_string())?;
+                fs::write(extracted_path(paper)?, json).map_err(|e| e.to_string())?;
+                saved += 1;
+            }
+            Err(_) => println!("  Could not extract: {}", paper.title),
+        }
+    }
+    println!("Saved extracted text for {} of {} papers in {}, run `ras process` to summarize them", saved, papers.len(), extracted_dir()?.display());
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Result<(), RasError> {
+    let requested = RunOptions::from_args(args)?;
+    let mut ctx = RunContext::new(Config::load().map_err(RasError::Config)?, RunOptions::default())?;
+    ctx.options.offline = requested.offline;
+    let mut papers = backlog(&ctx);
+    if let Some(limit) = requested.limit {
+        papers.truncate(limit);
+    }
+    println!("Found {} downloaded PDFs without a summary", papers.len());
+    if papers.is_empty() {
+        return Ok(());
+    }
+    if args.flag("extract-only") {
+        return extract_only(&ctx, &papers).map_err(RasError::from);
+    }
+    drop(ctx);
+
+    let mut options = RunOptions::for_papers(papers);
+    options.offline = requested.offline;
+    options.refine = requested.refine;
+    options.depth = requested.depth;
+    options.languages = requested.languages;
+    options.dry_run = requested.dry_run;
+    options.dashboard = requested.dashboard;
+    options.concurrency = requested.concurrency;
+    options.extractions = requested.extractions;
+    options.llm_calls = requested.llm_calls;
+    pipeline::execute(options)
+}
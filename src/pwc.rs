@@ -0,0 +1,228 @@
+use crate::arxiv::{self, Paper};
+use crate::capabilities::{self, Capability};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+const API_URL: &str = "https://paperswithcode.com/api/v1";
+const LOWER_IS_BETTER: &[&str] = &["error", "loss", "perplexity", "fid", "wer", "cer", "mae", "mse", "rmse", "latency", "params"];
+
+type LeaderboardEntry = (String, BTreeMap<String, String>);
+
+static NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+(?:\.\d+)?").unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CodeInfo {
+    pub repository: String,
+    pub framework: String,
+    pub official: bool,
+    pub stars: u32,
+    pub results: Vec<BenchmarkResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BenchmarkResult {
+    pub task: String,
+    pub dataset: String,
+    pub metrics: BTreeMap<String, String>,
+}
+
+impl CodeInfo {
+    pub fn metadata_lines(&self) -> String {
+        let mut details = Vec::new();
+        if !self.framework.is_empty() && self.framework != "none" {
+            details.push(self.framework.clone());
+        }
+        details.push(if self.official { "official".to_string() } else { "community".to_string() });
+        format!("**Code**: {} ({})\n", self.repository, details.join(", "))
+    }
+
+    pub fn results_table(&self) -> String {
+        if self.results.is_empty() {
+            return String::new();
+        }
+        let mut table = String::from("## Benchmark Results\n\n| Task | Dataset | Metrics |\n|---|---|---|\n");
+        for result in &self.results {
+            let metrics: Vec<String> = result.metrics.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+            table.push_str(&format!("| {} | {} | {} |\n", result.task, result.dataset, metrics.join(", ")));
+        }
+        table.push('\n');
+        table
+    }
+}
+
+fn get(client: &Client, url: &str) -> Result<Value, String> {
+    client
+        .get(url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| e.to_string())
+}
+
+fn results(value: &Value) -> Vec<Value> {
+    value["results"].as_array().cloned().unwrap_or_default()
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+pub fn lookup(client: &Client, arxiv_id: &str) -> Result<Option<CodeInfo>, String> {
+    let papers = get(client, &format!("{}/papers/?arxiv_id={}", API_URL, arxiv_id))?;
+    let Some(pwc_id) = results(&papers).first().and_then(|p| p["id"].as_str().map(|s| s.to_string())) else {
+        return Ok(None);
+    };
+
+    let mut repositories = results(&get(client, &format!("{}/papers/{}/repositories/", API_URL, pwc_id))?);
+    if repositories.is_empty() {
+        return Ok(None);
+    }
+    repositories.sort_by_key(|r| (!r["is_official"].as_bool().unwrap_or(false), std::cmp::Reverse(r["stars"].as_u64().unwrap_or(0))));
+    let repository = &repositories[0];
+
+    let results = results(&get(client, &format!("{}/papers/{}/results/", API_URL, pwc_id)).unwrap_or_default())
+        .iter()
+        .map(|r| BenchmarkResult {
+            task: as_text(&r["task"]),
+            dataset: as_text(&r["dataset"]),
+            metrics: r["metrics"]
+                .as_object()
+                .map(|m| m.iter().map(|(k, v)| (k.clone(), as_text(v))).collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(Some(CodeInfo {
+        repository: as_text(&repository["url"]),
+        framework: as_text(&repository["framework"]),
+        official: repository["is_official"].as_bool().unwrap_or(false),
+        stars: repository["stars"].as_u64().unwrap_or(0) as u32,
+        results,
+    }))
+}
+
+pub fn enrich(client: &Client, papers: &mut Vec<Paper>, require_code: bool) {
+    if papers.is_empty() || !capabilities::available(Capability::PapersWithCode) {
+        return;
+    }
+    println!("Looking up code repositories on Papers with Code...");
+    for paper in papers.iter_mut() {
+        if arxiv::normalize_id(&paper.id).is_none() {
+            continue;
+        }
+        match lookup(client, &paper.id) {
+            Ok(code) => paper.code = code,
+            Err(e) => {
+                capabilities::disable(Capability::PapersWithCode, &e);
+                return;
+            }
+        }
+    }
+    let with_code = papers.iter().filter(|p| p.code.is_some()).count();
+    println!("{}/{} papers have code", with_code, papers.len());
+
+    if require_code {
+        papers.retain(|p| p.code.is_some());
+    }
+}
+
+fn parse_number(value: &str) -> Option<(f64, f64)> {
+    let number = NUMBER_REGEX.find(value)?.as_str();
+    let decimals = number.split_once('.').map_or(0, |(_, d)| d.len()) as i32;
+    Some((number.parse().ok()?, 0.5 * 10f64.powi(-decimals)))
+}
+
+fn lower_is_better(metric: &str) -> bool {
+    let metric = metric.to_lowercase();
+    LOWER_IS_BETTER.iter().any(|m| metric.contains(m))
+}
+
+fn reported_in(text_numbers: &[f64], value: f64, tolerance: f64) -> bool {
+    [value, value * 100.0, value / 100.0]
+        .iter()
+        .any(|candidate| text_numbers.iter().any(|n| (n - candidate).abs() <= tolerance.max(candidate.abs() * 1e-6)))
+}
+
+fn leaderboard(client: &Client, task: &str, dataset: &str) -> Result<Vec<LeaderboardEntry>, String> {
+    let evaluations = get(
+        client,
+        &format!("{}/evaluations/?task={}&dataset={}", API_URL, task.replace(' ', "%20"), dataset.replace(' ', "%20")),
+    )?;
+    let Some(id) = results(&evaluations).first().and_then(|e| e["id"].as_str().map(|s| s.to_string())) else {
+        return Ok(Vec::new());
+    };
+    Ok(results(&get(client, &format!("{}/evaluations/{}/results/?items_per_page=500", API_URL, id))?)
+        .iter()
+        .map(|r| {
+            let metrics = r["metrics"]
+                .as_object()
+                .map(|m| m.iter().map(|(k, v)| (k.clone(), as_text(v))).collect())
+                .unwrap_or_default();
+            (as_text(&r["methodology"]), metrics)
+        })
+        .collect())
+}
+
+pub fn leaderboard_context(client: &Client, code: &CodeInfo, paper_text: &str) -> String {
+    let text_numbers: Vec<f64> = NUMBER_REGEX.find_iter(paper_text).filter_map(|m| m.as_str().parse().ok()).collect();
+    let mut notes = Vec::new();
+
+    for result in &code.results {
+        let entries = match leaderboard(client, &result.task, &result.dataset) {
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("  Failed to fetch leaderboard for {} / {}: {}", result.task, result.dataset, e);
+                Vec::new()
+            }
+        };
+        for (metric, value) in &result.metrics {
+            let Some((number, tolerance)) = parse_number(value) else {
+                continue;
+            };
+            let mut note = format!("- **{} / {}** — {} {}: ", result.dataset, result.task, metric, value);
+            if reported_in(&text_numbers, number, tolerance) {
+                note.push_str("matches the paper text.");
+            } else {
+                note.push_str("listed on Papers with Code but not found in the paper text, check for a discrepancy.");
+            }
+
+            let mut scores: Vec<(f64, &str)> = entries
+                .iter()
+                .filter_map(|(method, metrics)| metrics.get(metric).and_then(|v| parse_number(v)).map(|(n, _)| (n, method.as_str())))
+                .collect();
+            if !scores.is_empty() {
+                if lower_is_better(metric) {
+                    scores.sort_by(|a, b| a.0.total_cmp(&b.0));
+                } else {
+                    scores.sort_by(|a, b| b.0.total_cmp(&a.0));
+                }
+                let rank = 1 + scores
+                    .iter()
+                    .filter(|(n, _)| if lower_is_better(metric) { *n < number } else { *n > number })
+                    .count();
+                let (best, best_method) = scores[0];
+                note.push_str(&format!(" Ranks {} of {} on the leaderboard", rank, scores.len()));
+                if rank > 1 {
+                    note.push_str(&format!(" (best: {} with {})", best_method, best));
+                }
+                note.push('.');
+            }
+            notes.push(note);
+        }
+    }
+
+    if notes.is_empty() {
+        return String::new();
+    }
+    format!("## Leaderboard Context\n\n{}\n", notes.join("\n"))
+}
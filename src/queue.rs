@@ -0,0 +1,169 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex};
+
+struct State<T> {
+    memory: VecDeque<(T, usize)>,
+    memory_bytes: usize,
+    spilled: VecDeque<PathBuf>,
+    next_file: u64,
+    writing: usize,
+    total_spilled: usize,
+    closed: bool,
+}
+
+pub struct SpillQueue<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+    dir: PathBuf,
+    memory_limit: usize,
+}
+
+impl<T: Serialize + DeserializeOwned> SpillQueue<T> {
+    pub fn new(dir: PathBuf, memory_limit: usize) -> Result<SpillQueue<T>, String> {
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear queue directory {}: {}", dir.display(), e))?;
+        }
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create queue directory {}: {}", dir.display(), e))?;
+        Ok(SpillQueue {
+            state: Mutex::new(State {
+                memory: VecDeque::new(),
+                memory_bytes: 0,
+                spilled: VecDeque::new(),
+                next_file: 0,
+                writing: 0,
+                total_spilled: 0,
+                closed: false,
+            }),
+            ready: Condvar::new(),
+            dir,
+            memory_limit,
+        })
+    }
+
+    pub fn push(&self, item: T) -> Result<(), String> {
+        let bytes = serde_json::to_vec(&item).map_err(|e| e.to_string())?;
+        let path = {
+            let mut state = self.state.lock().unwrap();
+            let fits = state.memory.is_empty() || state.memory_bytes + bytes.len() <= self.memory_limit;
+            if fits && state.spilled.is_empty() && state.writing == 0 {
+                state.memory_bytes += bytes.len();
+                state.memory.push_back((item, bytes.len()));
+                self.ready.notify_one();
+                return Ok(());
+            }
+            state.next_file += 1;
+            state.writing += 1;
+            self.dir.join(format!("{:08}.json", state.next_file - 1))
+        };
+        let written = fs::write(&path, &bytes).map_err(|e| format!("Failed to spill queue item to {}: {}", path.display(), e));
+        let mut state = self.state.lock().unwrap();
+        state.writing -= 1;
+        if written.is_ok() {
+            state.total_spilled += 1;
+            state.spilled.push_back(path);
+        }
+        self.ready.notify_all();
+        written
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        loop {
+            let path = {
+                let mut state = self.state.lock().unwrap();
+                loop {
+                    if let Some((item, size)) = state.memory.pop_front() {
+                        state.memory_bytes -= size;
+                        return Some(item);
+                    }
+                    if let Some(path) = state.spilled.pop_front() {
+                        break path;
+                    }
+                    if state.closed && state.writing == 0 {
+                        return None;
+                    }
+                    state = self.ready.wait(state).unwrap();
+                }
+            };
+            let item = fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()));
+            let _ = fs::remove_file(&path);
+            match item {
+                Ok(item) => return Some(item),
+                Err(e) => println!("  Failed to read spilled queue item {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.ready.notify_all();
+    }
+
+    pub fn total_spilled(&self) -> usize {
+        self.state.lock().unwrap().total_spilled
+    }
+}
+
+impl<T> Drop for SpillQueue<T> {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn queue(name: &str, memory_limit: usize) -> SpillQueue<String> {
+        let dir = std::env::temp_dir().join(format!("ras-queue-{}-{}", std::process::id(), name));
+        SpillQueue::new(dir, memory_limit).unwrap()
+    }
+
+    #[test]
+    fn keeps_fifo_order_across_a_spill() {
+        let queue = queue("fifo", 20);
+        for i in 0..10 {
+            queue.push(format!("paper-{}", i)).unwrap();
+        }
+        assert_eq!(queue.total_spilled(), 8);
+        assert_eq!(queue.pop().as_deref(), Some("paper-0"));
+        queue.push("paper-10".to_string()).unwrap();
+        assert_eq!(queue.total_spilled(), 9);
+        queue.close();
+        let rest: Vec<String> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(rest, (1..=10).map(|i| format!("paper-{}", i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stays_in_memory_under_the_limit() {
+        let queue = queue("memory", 1024);
+        for i in 0..10 {
+            queue.push(format!("paper-{}", i)).unwrap();
+        }
+        assert_eq!(queue.total_spilled(), 0);
+        assert!(fs::read_dir(&queue.dir).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn drains_everything_before_reporting_closed() {
+        let queue = Arc::new(queue("drain", 12));
+        let consumer = {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || std::iter::from_fn(|| queue.pop()).collect::<Vec<String>>())
+        };
+        for i in 0..50 {
+            queue.push(format!("paper-{}", i)).unwrap();
+        }
+        queue.close();
+        let popped = consumer.join().unwrap();
+        assert_eq!(popped, (0..50).map(|i| format!("paper-{}", i)).collect::<Vec<_>>());
+        assert_eq!(queue.pop(), None);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::{Config, ReadwiseConfig};
+use crate::db::{Db, PaperRecord};
+use crate::html::summary_html;
+use crate::retry;
+use crate::sources;
+use crate::summary::{self, strip_frontmatter, tldr};
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+use std::fs;
+
+const SAVE_URL: &str = "https://readwise.io/api/v3/save/";
+
+fn document(config: &ReadwiseConfig, record: &PaperRecord, content: &str) -> Value {
+    let mut tags = config.tags.clone();
+    tags.extend(record.tags.iter().filter(|t| !config.tags.contains(t)).cloned());
+    let mut document = json!({
+        "url": sources::abs_url(&record.id),
+        "title": record.title,
+        "summary": tldr(content),
+        "location": config.location,
+        "tags": tags,
+        "saved_using": "ras",
+    });
+    if config.document == "paper" {
+        document["notes"] = json!(strip_frontmatter(content).trim());
+    } else {
+        document["html"] = json!(summary_html(content));
+        document["should_clean_html"] = json!(false);
+        document["category"] = json!("article");
+    }
+    document
+}
+
+fn save(client: &Client, token: &str, document: &Value) -> Result<String, String> {
+    let response = retry::send(SAVE_URL, || client.post(SAVE_URL).header("Authorization", format!("Token {}", token)).json(document))?;
+    let status = response.status();
+    let body = response.text().unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("Readwise API error {}: {}", status, body));
+    }
+    let result: Value = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+    result["id"].as_str().map(|s| s.to_string()).ok_or_else(|| format!("Unexpected Readwise response: {}", body))
+}
+
+pub fn export(client: &Client) -> Result<(), String> {
+    let config = Config::load()?.readwise;
+    let token = config.api_key();
+    if token.is_empty() {
+        return Err("Set [readwise] api_key (or READWISE_TOKEN) in config.toml".to_string());
+    }
+    if !matches!(config.location.as_str(), "new" | "later" | "archive" | "feed") {
+        return Err(format!("Unknown Readwise location: {} (expected new, later, archive or feed)", config.location));
+    }
+
+    let mut db = Db::load()?;
+    let pending: Vec<PaperRecord> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && r.readwise_id.is_empty())
+        .cloned()
+        .collect();
+    if pending.is_empty() {
+        println!("All summarized papers are already in Readwise Reader");
+        return Ok(());
+    }
+
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut pushed = 0;
+    for record in &pending {
+        let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+        let content = summary::with_notes(&content, &record.notes);
+        match save(client, &token, &document(&config, record, &content)) {
+            Ok(id) => {
+                println!("  Saved to Readwise Reader: {}", record.title);
+                if let Some(r) = db.papers.get_mut(&record.id) {
+                    r.readwise_id = id;
+                }
+                db.save()?;
+                pushed += 1;
+            }
+            Err(e) => println!("  Failed to save {} to Readwise Reader: {}", record.title, e),
+        }
+    }
+
+    println!("Pushed {} of {} papers to Readwise Reader", pushed, pending.len());
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::biorxiv;
+use crate::db::{Db, PaperRecord};
+use crate::pipeline::relative_link;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+static REFERENCES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?im)^\s*(references|bibliography)\s*$").unwrap());
+static END_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?im)^\s*(?:[A-H](?:\.\d{1,2})?\.?\s+)?(appendix|appendices|supplementary material)\b").unwrap());
+static NUMBERED_REF_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*\[\d{1,4}\]").unwrap());
+static YEAR_END_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[.,]\s*\(?(19|20)\d{2}[a-z]?\)?\.\s*$").unwrap());
+static ARXIV_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:arxiv[:\s]*|arxiv\.org/(?:abs|pdf)/|10\.48550/arxiv\.)(\d{4}\.\d{4,5})(?:v\d+)?").unwrap()
+});
+static DOI_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(10\.\d{4,9}/[^\s\x22<>]+)").unwrap());
+
+const MAX_REFERENCES: usize = 500;
+const MAX_REFERENCE_CHARS: usize = 400;
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Reference {
+    pub text: String,
+    pub arxiv_id: String,
+    pub doi: String,
+}
+
+pub fn section(text: &str) -> Option<&str> {
+    let start = REFERENCES_REGEX.find_iter(text).last()?.end();
+    let references = &text[start..];
+    let end = END_REGEX.find(references).map_or(references.len(), |m| m.start());
+    Some(&references[..end])
+}
+
+pub fn strip(text: &str) -> String {
+    let Some(heading) = REFERENCES_REGEX.find_iter(text).last() else {
+        return text.to_string();
+    };
+    let rest = &text[heading.end()..];
+    let end = END_REGEX.find(rest).map_or(rest.len(), |m| m.start());
+    format!("{}{}", &text[..heading.start()], &rest[end..])
+}
+
+fn entries(section: &str) -> Vec<String> {
+    let mut entries: Vec<String> = Vec::new();
+    let numbered = NUMBERED_REF_REGEX.is_match(section);
+    let mut current = String::new();
+    for line in section.lines().map(str::trim) {
+        let starts_entry = if numbered { NUMBERED_REF_REGEX.is_match(line) } else { line.is_empty() };
+        if starts_entry && !current.trim().is_empty() {
+            entries.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+        if !numbered && YEAR_END_REGEX.is_match(&current) {
+            entries.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        entries.push(current);
+    }
+    entries
+}
+
+pub fn parse(text: &str) -> Vec<Reference> {
+    let Some(section) = section(text) else {
+        return Vec::new();
+    };
+    entries(section)
+        .into_iter()
+        .map(|entry| entry.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|entry| entry.split(' ').count() >= 3)
+        .take(MAX_REFERENCES)
+        .map(|entry| {
+            let arxiv_id = ARXIV_REGEX.captures(&entry).map(|c| c[1].to_string()).unwrap_or_default();
+            let doi = DOI_REGEX
+                .captures(&entry)
+                .map(|c| c[1].trim_end_matches(['.', ',', ';', ')', ']']).to_lowercase())
+                .filter(|doi| !doi.starts_with("10.48550/"))
+                .unwrap_or_default();
+            Reference { text: entry.chars().take(MAX_REFERENCE_CHARS).collect(), arxiv_id, doi }
+        })
+        .collect()
+}
+
+pub fn resolve<'a>(db: &'a Db, reference: &Reference) -> Option<&'a PaperRecord> {
+    if !reference.arxiv_id.is_empty() {
+        db.papers.get(&reference.arxiv_id)
+    } else if !reference.doi.is_empty() {
+        db.papers.values().find(|r| biorxiv::doi(&r.id).is_some_and(|doi| doi.eq_ignore_ascii_case(&reference.doi)))
+    } else {
+        None
+    }
+}
+
+pub fn in_library<'a>(db: &'a Db, id: &str, references: &[Reference]) -> Vec<(&'a str, &'a str)> {
+    let mut found: Vec<(&str, &str)> = Vec::new();
+    for reference in references {
+        if let Some(record) = resolve(db, reference).filter(|r| r.id != id && r.status == "summarized" && !r.summary_file.is_empty())
+            && !found.iter().any(|(title, _)| *title == record.title)
+        {
+            found.push((&record.title, &record.summary_file));
+        }
+    }
+    found
+}
+
+pub fn library_section(db: &Db, id: &str, summary_file: &str, references: &[Reference]) -> String {
+    let found = in_library(db, id, references);
+    if found.is_empty() {
+        return String::new();
+    }
+    let mut section = "\n\n## Referenced Papers in Your Library\n\n".to_string();
+    for (title, file) in found {
+        section.push_str(&format!("- [{}]({})\n", title, relative_link(summary_file, file)));
+    }
+    section
+}
@@ -0,0 +1,122 @@
+use crate::cache;
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::depth::Depth;
+use crate::error::RasError;
+use crate::pipeline::{self, RunOptions};
+use crate::summary::{self, Frontmatter};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SummaryVersion {
+    pub version: u32,
+    pub file: String,
+    pub model: String,
+    pub prompt: String,
+    pub created: String,
+}
+
+impl SummaryVersion {
+    pub fn describe(content: &str, version: u32, file: &str) -> SummaryVersion {
+        let frontmatter = Frontmatter::parse(content).map(|(frontmatter, _)| frontmatter);
+        let field = |key| frontmatter.as_ref().and_then(|f| f.get(key)).unwrap_or("").to_string();
+        let prompt = field("prompt");
+        SummaryVersion {
+            version,
+            file: file.to_string(),
+            model: field("model"),
+            prompt: if prompt.is_empty() { "default".to_string() } else { prompt },
+            created: field("created"),
+        }
+    }
+}
+
+fn version_file(record: &PaperRecord, version: u32) -> String {
+    let stem = record.summary_file.strip_suffix("-summary.md").unwrap_or(&record.summary_file);
+    format!("{}-summary-v{}.md", stem, version)
+}
+
+fn load_prompt(path: &str) -> Result<(String, String), String> {
+    let template = fs::read_to_string(path).map_err(|e| format!("Failed to read prompt {}: {}", path, e))?;
+    let name = Path::new(path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string());
+    Ok((name, template))
+}
+
+fn restore(record: &PaperRecord, summary_dir: &Path, archived: &str) -> Result<(), String> {
+    fs::rename(summary_dir.join(archived), summary_dir.join(&record.summary_file)).map_err(|e| e.to_string())?;
+    let mut db = Db::load()?;
+    if let Some(current) = db.papers.get_mut(&record.id) {
+        current.summary_versions.pop();
+        current.status = record.status.clone();
+        current.attempts = record.attempts;
+        current.last_error = record.last_error.clone();
+    }
+    db.save()
+}
+
+pub fn run(args: &Args) -> Result<(), RasError> {
+    let query = args.positional.join(" ");
+    if query.is_empty() {
+        return Err(RasError::Other("Usage: regenerate <arxiv-id or title> [--model <provider/model>] [--prompt <file>]".to_string()));
+    }
+    let prompt = args.value("prompt").map(load_prompt).transpose()?;
+
+    let mut db = Db::load()?;
+    let record = match db.find(&query).as_slice() {
+        [] => return Err(RasError::Other(format!("No paper found for: {}", query))),
+        [record] => (*record).clone(),
+        matches => {
+            println!("Multiple papers match \"{}\":", query);
+            for record in matches {
+                println!("  {}  {}", record.id, record.title);
+            }
+            return Ok(());
+        }
+    };
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let current = summary_dir.join(&record.summary_file);
+    if record.summary_file.is_empty() || !current.exists() {
+        return Err(RasError::Other(format!("{} has no summary yet, run `ras fetch --ids {}` instead", record.title, record.id)));
+    }
+
+    let content = fs::read_to_string(&current)?;
+    let version = record.summary_versions.len() as u32 + 1;
+    let archived = version_file(&record, version);
+    fs::rename(&current, summary_dir.join(&archived))?;
+    if let Some(notes) = summary::notes(&content) {
+        db.set_notes(&record.id, &notes);
+    }
+    if let Some(entry) = db.papers.get_mut(&record.id) {
+        entry.summary_versions.push(SummaryVersion::describe(&content, version, &archived));
+    }
+    db.save()?;
+    drop(db);
+    println!("Archived version {} of {} as {}", version, record.title, archived);
+
+    cache::disable();
+    let mut options = RunOptions::for_papers(vec![record.to_paper()]);
+    options.model = args.value("model").map(str::to_string);
+    options.prompt = prompt;
+    options.refine = args.flag("refine");
+    options.depth = args.value("depth").map(Depth::parse).transpose()?.unwrap_or_default();
+    let result = pipeline::execute(options);
+
+    if result.is_err() || !current.exists() {
+        restore(&record, &summary_dir, &archived)?;
+        println!("Regeneration failed, restored version {}", version);
+        return result.and(Err(RasError::Other(format!("Failed to regenerate the summary of {}", record.title))));
+    }
+    let latest = SummaryVersion::describe(&fs::read_to_string(&current)?, version + 1, &record.summary_file);
+    println!(
+        "Saved version {} of {} (model {}, prompt {}), previous summary kept as {}",
+        latest.version,
+        record.title,
+        if latest.model.is_empty() { "unknown" } else { &latest.model },
+        latest.prompt,
+        archived
+    );
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::arxiv::Paper;
+use crate::capabilities::{self, Capability};
+use crate::config::RelevanceConfig;
+use crate::db::Db;
+use crate::embeddings::{self, EmbeddingStore, cosine_similarity};
+use crate::error::RasError;
+use crate::openai;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+const STOPWORDS: &[&str] = &[
+    "with", "from", "that", "this", "towards", "via", "using", "based", "their", "into", "through", "for", "and", "the",
+];
+const NEGATIVE_WEIGHT: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Profile {
+    pub trained_at: Option<String>,
+    pub positives: usize,
+    pub negatives: usize,
+    pub keyword_weights: BTreeMap<String, f32>,
+    pub interest: Vec<f32>,
+}
+
+pub fn profile_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("profile.json"))
+}
+
+impl Profile {
+    pub fn load() -> Profile {
+        profile_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(profile_path()?, json).map_err(|e| e.to_string())
+    }
+
+    pub fn needs_retrain(&self, config: &RelevanceConfig) -> bool {
+        match self.trained_at.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok()) {
+            Some(trained_at) => Utc::now() - trained_at.with_timezone(&Utc) > Duration::hours(config.retrain_hours),
+            None => true,
+        }
+    }
+
+    pub fn keyword_score(&self, title: &str) -> f32 {
+        let weights: Vec<f32> = keywords(title)
+            .iter()
+            .filter_map(|k| self.keyword_weights.get(k).copied())
+            .collect();
+        if weights.is_empty() {
+            return 0.0;
+        }
+        weights.iter().sum::<f32>() / weights.len() as f32
+    }
+
+    pub fn score(&self, title: &str, embedding: Option<&[f32]>) -> f32 {
+        let semantic = match embedding {
+            Some(e) if !self.interest.is_empty() => cosine_similarity(&self.interest, e),
+            _ => 0.0,
+        };
+        self.keyword_score(title) + semantic
+    }
+}
+
+pub fn keywords(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.chars().count() > 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+pub fn retrain(db: &Db, store: &EmbeddingStore, config: &RelevanceConfig) -> Profile {
+    let cutoff = Utc::now() - Duration::days(config.skip_after_days);
+    let mut positives = Vec::new();
+    let mut negatives = Vec::new();
+
+    for record in db.papers.values().filter(|r| r.status == "summarized") {
+        if record.rating < 0 {
+            negatives.push(record);
+        } else if record.rating > 0 || record.opens > 0 {
+            positives.push(record);
+        } else if DateTime::parse_from_rfc3339(&record.added_at).is_ok_and(|t| t.with_timezone(&Utc) < cutoff) {
+            negatives.push(record);
+        }
+    }
+
+    let mut counts: BTreeMap<String, (f32, f32)> = BTreeMap::new();
+    for record in &positives {
+        for k in keywords(&record.title) {
+            counts.entry(k).or_default().0 += 1.0;
+        }
+    }
+    for record in &negatives {
+        for k in keywords(&record.title) {
+            counts.entry(k).or_default().1 += 1.0;
+        }
+    }
+    let keyword_weights = counts
+        .into_iter()
+        .map(|(k, (pos, neg))| (k, (pos - NEGATIVE_WEIGHT * neg) / (pos + neg + 2.0)))
+        .collect();
+
+    let positive_centroid = centroid(positives.iter().filter_map(|r| store.entries.get(&r.summary_file)).map(|e| e.vector.as_slice()));
+    let negative_centroid = centroid(negatives.iter().filter_map(|r| store.entries.get(&r.summary_file)).map(|e| e.vector.as_slice()));
+    let interest = match (positive_centroid, negative_centroid) {
+        (Some(pos), Some(neg)) => pos.iter().zip(&neg).map(|(p, n)| p - NEGATIVE_WEIGHT * n).collect(),
+        (Some(pos), None) => pos,
+        _ => Vec::new(),
+    };
+
+    Profile {
+        trained_at: Some(Utc::now().to_rfc3339()),
+        positives: positives.len(),
+        negatives: negatives.len(),
+        keyword_weights,
+        interest,
+    }
+}
+
+fn centroid<'a>(vectors: impl Iterator<Item = &'a [f32]>) -> Option<Vec<f32>> {
+    let mut sum: Vec<f32> = Vec::new();
+    let mut count = 0;
+    for v in vectors {
+        if sum.is_empty() {
+            sum = vec![0.0; v.len()];
+        }
+        if v.len() != sum.len() {
+            continue;
+        }
+        for (s, x) in sum.iter_mut().zip(v) {
+            *s += x;
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return None;
+    }
+    Some(sum.into_iter().map(|s| s / count as f32).collect())
+}
+
+pub fn load_profile(client: &Client, api_key: &str, config: &RelevanceConfig, persist: bool) -> Profile {
+    let profile = Profile::load();
+    if !profile.needs_retrain(config) {
+        return profile;
+    }
+    train(client, api_key, config, persist)
+}
+
+fn stored_embeddings() -> EmbeddingStore {
+    embeddings::store_path().map(|path| EmbeddingStore::load(&path)).unwrap_or_default()
+}
+
+// Without persist (dry runs) the profile is trained from the stored embeddings and nothing is written
+fn train(client: &Client, api_key: &str, config: &RelevanceConfig, persist: bool) -> Profile {
+    println!("Retraining relevance profile from ratings and read/skip feedback...");
+    let store = if persist {
+        capabilities::attempt(Capability::Embeddings, || embeddings::load_updated(client, api_key))
+            .unwrap_or_else(stored_embeddings)
+    } else {
+        stored_embeddings()
+    };
+    let db = match Db::load() {
+        Ok(db) => db,
+        Err(e) => {
+            println!("  Keeping the previous profile: {}", e);
+            return Profile::load();
+        }
+    };
+    let profile = retrain(&db, &store, config);
+    println!("  Trained on {} read and {} skipped summaries", profile.positives, profile.negatives);
+    if persist
+        && let Err(e) = profile.save()
+    {
+        println!("  Failed to save relevance profile: {}", e);
+    }
+    profile
+}
+
+fn profile_scores(client: &Client, api_key: &str, config: &RelevanceConfig, papers: &[Paper], persist: bool) -> Option<Vec<f32>> {
+    if !config.enabled || papers.is_empty() {
+        return None;
+    }
+    let profile = load_profile(client, api_key, config, persist);
+    if profile.positives < config.min_feedback {
+        return None;
+    }
+
+    let embeddings = if profile.interest.is_empty() {
+        None
+    } else {
+        let titles: Vec<String> = papers.iter().map(|p| p.title.clone()).collect();
+        capabilities::attempt(Capability::Embeddings, || openai::embed(client, api_key, &titles))
+    };
+    Some(papers.iter().enumerate().map(|(i, paper)| profile.score(&paper.title, embeddings.as_ref().map(|e| e[i].as_slice()))).collect())
+}
+
+pub fn rank_papers(client: &Client, api_key: &str, config: &RelevanceConfig, papers: Vec<Paper>, watched: &HashMap<String, Vec<String>>, persist: bool) -> Vec<Paper> {
+    let scores = profile_scores(client, api_key, config, &papers, persist);
+    let before = papers.len();
+    let mut ranked: Vec<(f32, Paper)> = Vec::new();
+    for (i, paper) in papers.into_iter().enumerate() {
+        let boost = paper.scholar.as_ref().map_or(0.0, |s| s.relevance_boost(config.citation_weight));
+        let is_watched = watched.contains_key(&paper.id);
+        if let Some(scores) = &scores
+            && !is_watched
+            && scores[i] + boost < config.min_score
+        {
+            continue;
+        }
+        let score = scores.as_ref().map_or(0.0, |s| s[i]) + boost + if is_watched { config.watchlist_weight } else { 0.0 };
+        ranked.push((score, paper));
+    }
+    if scores.is_some() {
+        println!("Relevance filter kept {}/{} papers", ranked.len(), before);
+    }
+    if config.prioritize {
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    }
+    ranked.into_iter().map(|(_, paper)| paper).collect()
+}
+
+pub fn run_retrain() -> Result<(), String> {
+    let config = crate::config::Config::load()?;
+    let client = crate::build_client()?;
+    // Without a key the profile is retrained from the embeddings already stored
+    let api_key = openai::api_key().unwrap_or_default();
+    train(&client, &api_key, &config.relevance, true);
+    Ok(())
+}
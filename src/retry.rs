@@ -0,0 +1,104 @@
+use crate::arxiv;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use std::thread;
+use std::time::Duration;
+
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_DELAY_MS: u64 = 1000;
+const MAX_DELAY_MS: u64 = 60_000;
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+pub fn backoff(attempt: u32) -> Duration {
+    let delay = BASE_DELAY_MS.saturating_mul(1 << attempt.min(16)).min(MAX_DELAY_MS);
+    Duration::from_millis(delay + rand::random_range(0..=delay / 2))
+}
+
+pub fn retry_after(response: &Response) -> Option<Duration> {
+    parse_retry_after(response.headers().get(RETRY_AFTER)?.to_str().ok()?, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let wait = match value.parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => (DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc) - now).to_std().unwrap_or_default(),
+    };
+    Some(wait.min(MAX_RETRY_AFTER))
+}
+
+pub fn is_throttled(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+pub fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT || is_throttled(status) || status.is_server_error()
+}
+
+pub fn get(client: &Client, url: &str) -> Result<Response, String> {
+    send(url, || client.get(url))
+}
+
+pub fn send(url: &str, request: impl Fn() -> RequestBuilder) -> Result<Response, String> {
+    let mut attempt = 0;
+    loop {
+        if arxiv::is_arxiv_url(url) {
+            arxiv::throttle();
+        }
+        let response = request().send().map_err(|e| e.to_string())?;
+        let status = response.status();
+        attempt += 1;
+        if !is_throttled(status) || attempt == MAX_ATTEMPTS {
+            return Ok(response);
+        }
+        let wait = retry_after(&response).unwrap_or_else(|| backoff(attempt - 1));
+        println!("  {} returned {}, retrying in {:.1}s", url, status, wait.as_secs_f64());
+        thread::sleep(wait);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 5 ", now), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after("Sat, 01 Mar 2025 12:00:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Sat, 01 Mar 2025 11:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn caps_retry_after() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(parse_retry_after("86400", now), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("Sun, 02 Mar 2025 12:00:00 GMT", now), Some(MAX_RETRY_AFTER));
+    }
+
+    #[test]
+    fn backs_off_exponentially_with_jitter() {
+        let millis = |attempt| backoff(attempt).as_millis() as u64;
+        assert!((1000..=1500).contains(&millis(0)));
+        assert!((2000..=3000).contains(&millis(1)));
+        assert!((8000..=12000).contains(&millis(3)));
+        assert!((60_000..=90_000).contains(&millis(10)));
+        assert!((60_000..=90_000).contains(&millis(40)));
+    }
+
+    #[test]
+    fn retries_only_throttling_and_server_errors() {
+        assert!(is_throttled(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_throttled(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_throttled(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_transient(StatusCode::REQUEST_TIMEOUT));
+        assert!(is_transient(StatusCode::BAD_GATEWAY));
+        assert!(!is_transient(StatusCode::NOT_FOUND));
+        assert!(!is_transient(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::error::RasError;
+use crate::openai::Usage;
+use crate::pipeline::Outcome;
+use crate::telemetry::Span;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct PaperRun {
+    pub id: String,
+    pub title: String,
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+    pub downloaded: bool,
+    pub extracted: bool,
+    pub stages: BTreeMap<String, f64>,
+    #[serde(skip)]
+    pub active: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Run {
+    pub id: String,
+    pub started: String,
+    pub finished: String,
+    pub seconds: f64,
+    pub fetched: usize,
+    pub cancelled: bool,
+    pub papers: Vec<PaperRun>,
+    pub usage: BTreeMap<String, Usage>,
+    pub cost: f64,
+}
+
+#[derive(Default)]
+pub struct Tracker {
+    papers: Mutex<BTreeMap<String, PaperRun>>,
+    spans: Mutex<Vec<Span>>,
+}
+
+impl Tracker {
+    fn update(&self, paper: &Paper, f: impl FnOnce(&mut PaperRun)) {
+        let mut papers = self.papers.lock().unwrap();
+        let run = papers.entry(paper.id.clone()).or_insert_with(|| PaperRun {
+            id: paper.id.clone(),
+            title: paper.title.clone(),
+            ..Default::default()
+        });
+        f(run);
+    }
+
+    pub fn time<T>(&self, paper: &Paper, stage: &str, work: impl FnOnce() -> T) -> T {
+        self.update(paper, |run| run.active = stage.to_string());
+        let (started, start) = (Instant::now(), SystemTime::now());
+        let result = work();
+        let seconds = started.elapsed().as_secs_f64();
+        self.span(stage, &paper.id, start, SystemTime::now());
+        self.update(paper, |run| {
+            run.active.clear();
+            *run.stages.entry(stage.to_string()).or_default() += seconds;
+        });
+        result
+    }
+
+    pub fn span(&self, name: &str, paper: &str, start: SystemTime, end: SystemTime) {
+        self.spans.lock().unwrap().push(Span { name: name.to_string(), paper: paper.to_string(), start, end });
+    }
+
+    pub fn spans(&self) -> Vec<Span> {
+        self.spans.lock().unwrap().clone()
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, PaperRun> {
+        self.papers.lock().unwrap().clone()
+    }
+
+    pub fn downloaded(&self, paper: &Paper) {
+        self.update(paper, |run| run.downloaded = true);
+    }
+
+    pub fn extracted(&self, paper: &Paper) {
+        self.update(paper, |run| run.extracted = true);
+    }
+
+    pub fn finish(&self, paper: &Paper, outcome: &Outcome) {
+        let (name, reason) = match outcome {
+            Outcome::Summarized { .. } => ("summarized", String::new()),
+            Outcome::Failed { error, .. } => ("failed", error.clone()),
+            Outcome::Skipped { reason } => ("skipped", reason.clone()),
+        };
+        self.update(paper, |run| {
+            run.outcome = name.to_string();
+            run.reason = reason;
+        });
+    }
+
+    pub fn fail(&self, paper: &Paper, reason: &str) {
+        self.update(paper, |run| {
+            run.outcome = "failed".to_string();
+            run.reason = reason.to_string();
+        });
+    }
+
+    pub fn report(&self, papers: &[Paper]) -> Vec<PaperRun> {
+        for paper in papers {
+            self.update(paper, |run| {
+                if run.outcome.is_empty() {
+                    run.outcome = "not processed".to_string();
+                }
+            });
+        }
+        let tracked = self.papers.lock().unwrap();
+        papers.iter().filter_map(|p| tracked.get(&p.id).cloned()).collect()
+    }
+}
+
+fn runs_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("runs"))
+}
+
+pub fn save(run: &Run) -> Result<PathBuf, String> {
+    fs::create_dir_all(runs_dir()?).map_err(|e| e.to_string())?;
+    let path = runs_dir()?.join(format!("{}.json", run.id));
+    let json = serde_json::to_string_pretty(run).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+pub fn save_log(id: &str, lines: &[String]) -> Result<PathBuf, String> {
+    fs::create_dir_all(runs_dir()?).map_err(|e| e.to_string())?;
+    let path = runs_dir()?.join(format!("{}.log", id));
+    fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+pub fn run_id(started: DateTime<Utc>) -> String {
+    started.with_timezone(&Local).format("%Y%m%d-%H%M%S").to_string()
+}
+
+fn load(id: &str) -> Result<Run, String> {
+    let path = runs_dir()?.join(format!("{}.json", id.trim_end_matches(".json")));
+    let content = fs::read_to_string(&path).map_err(|_| format!("No run report found for: {}", id))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+fn ids() -> Result<Vec<String>, RasError> {
+    let mut ids: Vec<String> = fs::read_dir(runs_dir()?)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().and_then(|n| n.strip_suffix(".json")).map(str::to_string))
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+fn count(run: &Run, outcome: &str) -> usize {
+    run.papers.iter().filter(|p| p.outcome == outcome).count()
+}
+
+fn local_time(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+fn show(run: &Run) {
+    println!("Run {} ({} to {}, {:.0}s)", run.id, local_time(&run.started), local_time(&run.finished), run.seconds);
+    println!(
+        "Fetched {}, selected {}: {} summarized, {} failed, {} skipped{}",
+        run.fetched,
+        run.papers.len(),
+        count(run, "summarized"),
+        count(run, "failed"),
+        count(run, "skipped"),
+        if run.cancelled { " (cancelled)" } else { "" }
+    );
+    for paper in &run.papers {
+        let stages: Vec<String> = paper.stages.iter().map(|(stage, seconds)| format!("{} {:.1}s", stage, seconds)).collect();
+        println!("\n  [{}] {}  {}", paper.outcome, paper.id, paper.title);
+        if !stages.is_empty() {
+            println!("    {}", stages.join(", "));
+        }
+        if !paper.reason.is_empty() {
+            println!("    {}", paper.reason);
+        }
+    }
+    if !run.usage.is_empty() {
+        println!("\nLLM usage:");
+        for (pass, usage) in &run.usage {
+            println!("  {}: {} prompt + {} completion tokens (${:.4})", pass, usage.prompt_tokens, usage.completion_tokens, usage.cost);
+        }
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let ids = ids()?;
+    if args.positional.first().map(String::as_str) == Some("show") {
+        let id = match args.positional.get(1) {
+            Some(id) => id.clone(),
+            None => ids.last().cloned().ok_or("No runs recorded yet")?,
+        };
+        show(&load(&id)?);
+        return Ok(());
+    }
+
+    if ids.is_empty() {
+        println!("No runs recorded yet");
+        return Ok(());
+    }
+    let limit: usize = args.parsed("limit", 20)?;
+    println!("{:<16} {:>8} {:>10} {:>7} {:>8} {:>9}", "run", "fetched", "summarized", "failed", "skipped", "cost");
+    for id in ids.iter().rev().take(limit) {
+        match load(id) {
+            Ok(run) => println!(
+                "{:<16} {:>8} {:>10} {:>7} {:>8} {:>9}",
+                run.id,
+                run.fetched,
+                count(&run, "summarized"),
+                count(&run, "failed"),
+                count(&run, "skipped"),
+                format!("${:.4}", run.cost)
+            ),
+            Err(e) => println!("{:<16} {}", id, e),
+        }
+    }
+    println!("\nShow a run with `ras runs show <run>`");
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::arxiv::Paper;
+use crate::biorxiv;
+use crate::capabilities::{self, Capability};
+use crate::config::{Config, ScholarConfig};
+use crate::db::Db;
+use crate::references::Reference;
+use crate::summary::set_frontmatter_field;
+use chrono::Utc;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+
+const BATCH_URL: &str = "https://api.semanticscholar.org/graph/v1/paper/batch";
+const FIELDS: &str = "citationCount,influentialCitationCount,fieldsOfStudy,s2FieldsOfStudy";
+const BATCH_SIZE: usize = 500;
+const PAPER_URL: &str = "https://api.semanticscholar.org/graph/v1/paper";
+const MAX_LINKS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ScholarInfo {
+    pub citations: u32,
+    pub influential_citations: u32,
+    pub fields_of_study: Vec<String>,
+    pub fetched_at: String,
+}
+
+impl ScholarInfo {
+    pub fn from_json(paper: &Value) -> ScholarInfo {
+        let mut fields: Vec<String> = paper["fieldsOfStudy"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .chain(paper["s2FieldsOfStudy"].as_array().into_iter().flatten().map(|f| &f["category"]))
+            .filter_map(|f| f.as_str().map(|s| s.to_string()))
+            .collect();
+        fields.sort();
+        fields.dedup();
+        ScholarInfo {
+            citations: paper["citationCount"].as_u64().unwrap_or(0) as u32,
+            influential_citations: paper["influentialCitationCount"].as_u64().unwrap_or(0) as u32,
+            fields_of_study: fields,
+            fetched_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn relevance_boost(&self, weight: f32) -> f32 {
+        weight * (1.0 + self.citations as f32 + 2.0 * self.influential_citations as f32).ln()
+    }
+}
+
+fn paper_id(id: &str) -> String {
+    match biorxiv::doi(id) {
+        Some(doi) => format!("DOI:{}", doi),
+        None => format!("arXiv:{}", id),
+    }
+}
+
+pub fn fetch(client: &Client, config: &ScholarConfig, ids: &[String]) -> HashMap<String, ScholarInfo> {
+    let api_key = config.api_key();
+    let mut found = HashMap::new();
+    for batch in ids.chunks(BATCH_SIZE) {
+        let body = json!({ "ids": batch.iter().map(|id| paper_id(id)).collect::<Vec<_>>() });
+        let mut request = client.post(BATCH_URL).query(&[("fields", FIELDS)]).json(&body);
+        if !api_key.is_empty() {
+            request = request.header("x-api-key", &api_key);
+        }
+        let results: Vec<Value> = match request.send().and_then(|r| r.error_for_status()).and_then(|r| r.json()) {
+            Ok(results) => results,
+            Err(e) => {
+                capabilities::disable(Capability::SemanticScholar, &e.to_string());
+                break;
+            }
+        };
+        for (id, paper) in batch.iter().zip(results.iter()) {
+            if !paper.is_null() {
+                found.insert(id.clone(), ScholarInfo::from_json(paper));
+            }
+        }
+    }
+    found
+}
+
+fn linked(client: &Client, api_key: &str, id: &str, relation: &str, side: &str) -> Result<Vec<Reference>, String> {
+    let url = format!("{}/{}/{}", PAPER_URL, paper_id(id), relation);
+    let mut request = client.get(&url).query(&[("fields", "externalIds".to_string()), ("limit", MAX_LINKS.to_string())]);
+    if !api_key.is_empty() {
+        request = request.header("x-api-key", api_key);
+    }
+    let body: Value = request.send().and_then(|r| r.error_for_status()).and_then(|r| r.json()).map_err(|e| e.to_string())?;
+    Ok(body["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|entry| {
+            let ids = &entry[side]["externalIds"];
+            Reference {
+                text: String::new(),
+                arxiv_id: ids["ArXiv"].as_str().unwrap_or_default().to_string(),
+                doi: ids["DOI"].as_str().unwrap_or_default().to_lowercase(),
+            }
+        })
+        .filter(|r| !r.arxiv_id.is_empty() || !r.doi.is_empty())
+        .collect())
+}
+
+pub fn neighbors(client: &Client, config: &ScholarConfig, id: &str) -> Result<(Vec<Reference>, Vec<Reference>), String> {
+    let api_key = config.api_key();
+    let references = linked(client, &api_key, id, "references", "citedPaper")?;
+    let citations = linked(client, &api_key, id, "citations", "citingPaper")?;
+    Ok((references, citations))
+}
+
+pub fn enrich(client: &Client, config: &ScholarConfig, papers: &mut [Paper]) {
+    if !config.enabled || papers.is_empty() || !capabilities::available(Capability::SemanticScholar) {
+        return;
+    }
+    let ids: Vec<String> = papers.iter().map(|p| p.id.clone()).collect();
+    let mut found = fetch(client, config, &ids);
+    println!("Semantic Scholar data found for {}/{} papers", found.len(), papers.len());
+    for paper in papers.iter_mut() {
+        if let Some(info) = found.remove(&paper.id) {
+            paper.scholar = Some(info);
+        }
+    }
+}
+
+pub fn run() -> Result<(), String> {
+    let config = Config::load()?.semantic_scholar;
+    let client = crate::build_client()?;
+    let mut db = Db::load()?;
+    let ids: Vec<String> = db.papers.keys().cloned().collect();
+    println!("Refreshing Semantic Scholar data for {} papers", ids.len());
+    let found = fetch(&client, &config, &ids);
+
+    let summary_dir = crate::ras_dir()?.join("summary");
+    for (id, info) in &found {
+        let Some(record) = db.papers.get_mut(id) else {
+            continue;
+        };
+        let path = summary_dir.join(&record.summary_file);
+        if let Ok(content) = fs::read_to_string(&path) {
+            let content = set_frontmatter_field(&content, "citations", &info.citations.to_string());
+            let content = set_frontmatter_field(&content, "influential_citations", &info.influential_citations.to_string());
+            let content = set_frontmatter_field(&content, "fields_of_study", &info.fields_of_study.join(", "));
+            fs::write(&path, content).map_err(|e| e.to_string())?;
+        }
+        record.scholar = Some(info.clone());
+    }
+    db.save()?;
+    println!("Updated {} papers", found.len());
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::db::Db;
+use crate::summary::strip_frontmatter;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+pub struct SearchIndex {
+    ids: Vec<String>,
+    lengths: Vec<usize>,
+    postings: HashMap<String, Vec<(usize, u32)>>,
+    avg_len: f32,
+    signature: u64,
+}
+
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn signature(db: &Db, summary_dir: &Path) -> u64 {
+    db.papers
+        .values()
+        .filter_map(|r| fs::metadata(summary_dir.join(&r.summary_file)).ok())
+        .filter_map(|m| m.modified().ok())
+        .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .fold(db.papers.len() as u64, |acc, t| acc.wrapping_mul(31).wrapping_add(t))
+}
+
+impl SearchIndex {
+    pub fn build(db: &Db, summary_dir: &Path) -> SearchIndex {
+        let mut index = SearchIndex {
+            ids: Vec::new(),
+            lengths: Vec::new(),
+            postings: HashMap::new(),
+            avg_len: 0.0,
+            signature: signature(db, summary_dir),
+        };
+
+        for record in db.papers.values() {
+            let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+            let tokens = tokenize(&format!("{} {}", record.title, strip_frontmatter(&content)));
+            let doc = index.ids.len();
+            index.ids.push(record.id.clone());
+            index.lengths.push(tokens.len());
+
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for token in tokens {
+                *counts.entry(token).or_default() += 1;
+            }
+            for (token, tf) in counts {
+                index.postings.entry(token).or_default().push((doc, tf));
+            }
+        }
+
+        let total: usize = index.lengths.iter().sum();
+        index.avg_len = if index.ids.is_empty() { 0.0 } else { total as f32 / index.ids.len() as f32 };
+        index
+    }
+
+    pub fn is_stale(&self, db: &Db, summary_dir: &Path) -> bool {
+        self.signature != signature(db, summary_dir)
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(String, f32)> {
+        let n = self.ids.len() as f32;
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+
+        for token in tokenize(query) {
+            let Some(postings) = self.postings.get(&token) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for &(doc, tf) in postings {
+                let tf = tf as f32;
+                let norm = 1.0 - B + B * self.lengths[doc] as f32 / self.avg_len.max(1.0);
+                *scores.entry(doc).or_default() += idf * tf * (K1 + 1.0) / (tf + K1 * norm);
+            }
+        }
+
+        let mut results: Vec<(String, f32)> = scores.into_iter().map(|(doc, score)| (self.ids[doc].clone(), score)).collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results.truncate(limit);
+        results
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::{Condvar, Mutex};
+
+pub struct Semaphore {
+    state: Mutex<(usize, usize)>,
+    released: Condvar,
+}
+
+pub struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+    pub const fn new(limit: usize) -> Semaphore {
+        Semaphore { state: Mutex::new((0, limit)), released: Condvar::new() }
+    }
+
+    pub fn set_limit(&self, limit: usize) {
+        self.state.lock().unwrap().1 = limit.max(1);
+        self.released.notify_all();
+    }
+
+    pub fn acquire(&self) -> Permit<'_> {
+        let mut state = self.state.lock().unwrap();
+        while state.0 >= state.1.max(1) {
+            state = self.released.wait(state).unwrap();
+        }
+        state.0 += 1;
+        Permit { semaphore: self }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.state.lock().unwrap().0 -= 1;
+        self.semaphore.released.notify_one();
+    }
+}
@@ -0,0 +1,246 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::openai;
+use crate::pdf::extract_text_from_pdf;
+use crate::pipeline::{self, RunContext, RunOptions, MAX_PROMPT_CHARS, SUMMARY_SECTIONS};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+static PART_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(.*?)[\s:,\-–—(]*\bpart\s+([ivx]+|\d+|one|two|three)\b").unwrap());
+static APPENDIX_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:supplementary materials?|supplemental materials?|appendix|appendices|supplement)\s+(?:for|to)\s*:?\s*(.+)$")
+        .unwrap()
+});
+static ARXIV_ID_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(\d{4}\.\d{4,5})(?:v\d+)?\b").unwrap());
+
+const CITATION_CUES: &[(&str, &str)] = &[
+    ("companion paper", "companion"),
+    ("companion", "companion"),
+    ("supplementary material", "appendix-of"),
+    ("appendix", "appendix-of"),
+    ("our previous work", "follows-up"),
+    ("our prior work", "follows-up"),
+    ("our earlier work", "follows-up"),
+    ("we extend", "follows-up"),
+    ("extends our", "follows-up"),
+    ("dataset paper", "companion"),
+];
+const CUE_WINDOW: usize = 250;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Relation {
+    pub id: String,
+    pub kind: String,
+}
+
+fn inverse(kind: &str) -> &'static str {
+    match kind {
+        "appendix-of" => "has-appendix",
+        "has-appendix" => "appendix-of",
+        "follows-up" => "followed-up-by",
+        "followed-up-by" => "follows-up",
+        "series" => "series",
+        _ => "companion",
+    }
+}
+
+fn normalize(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn series_stem(title: &str) -> Option<String> {
+    PART_REGEX
+        .captures(title)
+        .map(|c| normalize(&c[1]))
+        .filter(|stem| stem.split(' ').count() >= 2)
+}
+
+pub fn cited_companions(text: &str) -> Vec<Relation> {
+    let lower = text.to_ascii_lowercase();
+    let mut relations: Vec<Relation> = Vec::new();
+    for m in ARXIV_ID_REGEX.captures_iter(&lower) {
+        let whole = m.get(0).unwrap();
+        let start = (0..=whole.start().saturating_sub(CUE_WINDOW)).rev().find(|i| lower.is_char_boundary(*i)).unwrap_or(0);
+        let end = ((whole.end() + CUE_WINDOW).min(lower.len())..=lower.len())
+            .find(|i| lower.is_char_boundary(*i))
+            .unwrap_or(lower.len());
+        let window = &lower[start..end];
+        if let Some((_, kind)) = CITATION_CUES.iter().find(|(cue, _)| window.contains(cue)) {
+            let relation = Relation { id: m[1].to_string(), kind: kind.to_string() };
+            if !relations.iter().any(|r| r.id == relation.id) {
+                relations.push(relation);
+            }
+        }
+    }
+    relations
+}
+
+pub fn detect(paper: &Paper, cited: &[Relation], db: &Db) -> Vec<Relation> {
+    let mut relations: Vec<Relation> = cited
+        .iter()
+        .filter(|r| r.id != paper.id && db.papers.contains_key(&r.id))
+        .cloned()
+        .collect();
+
+    let mut add = |id: &str, kind: &str| {
+        if id != paper.id && !relations.iter().any(|r| r.id == id) {
+            relations.push(Relation { id: id.to_string(), kind: kind.to_string() });
+        }
+    };
+
+    if let Some(stem) = series_stem(&paper.title) {
+        for record in db.papers.values() {
+            if series_stem(&record.title).as_deref() == Some(stem.as_str()) {
+                add(&record.id, "series");
+            }
+        }
+    }
+
+    if let Some(caps) = APPENDIX_REGEX.captures(&paper.title) {
+        let target = normalize(&caps[1]);
+        if let Some(record) = db.papers.values().find(|r| normalize(&r.title) == target) {
+            add(&record.id, "appendix-of");
+        }
+    }
+
+    let own = normalize(&paper.title);
+    for record in db.papers.values() {
+        if let Some(caps) = APPENDIX_REGEX.captures(&record.title)
+            && normalize(&caps[1]) == own
+        {
+            add(&record.id, "has-appendix");
+        }
+    }
+
+    relations
+}
+
+pub fn link(db: &mut Db, id: &str, relations: &[Relation]) {
+    for relation in relations {
+        if let Some(record) = db.papers.get_mut(id)
+            && !record.related.contains(relation)
+        {
+            record.related.push(relation.clone());
+        }
+        let back = Relation { id: id.to_string(), kind: inverse(&relation.kind).to_string() };
+        if let Some(other) = db.papers.get_mut(&relation.id)
+            && !other.related.contains(&back)
+        {
+            other.related.push(back);
+        }
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+pub fn companion_texts(ctx: &RunContext, relations: &[Relation]) -> Vec<(PaperRecord, String)> {
+    let related: Vec<PaperRecord> = {
+        let db = ctx.db.lock().unwrap();
+        relations.iter().filter_map(|r| db.papers.get(&r.id).cloned()).collect()
+    };
+    related
+        .into_iter()
+        .filter_map(|record| {
+            let text = extract_text_from_pdf(&ctx.papers_dir.join(&record.pdf_file)).ok()?;
+            Some((record, text))
+        })
+        .collect()
+}
+
+pub fn summarize_combined(
+    ctx: &RunContext,
+    paper: &Paper,
+    pdf_text: &str,
+    companions: &[(PaperRecord, String)],
+) -> Result<String, String> {
+    let share = MAX_PROMPT_CHARS / (companions.len() + 1);
+    let mut sources = format!("Main paper: {} (arXiv {})\n{}\n", paper.title, paper.id, truncate(pdf_text, share));
+    for (record, text) in companions {
+        sources.push_str(&format!(
+            "\nRelated paper: {} (arXiv {})\n{}\n",
+            record.title,
+            record.id,
+            truncate(text, share)
+        ));
+    }
+
+    let prompt = format!(
+        r#"Please provide a comprehensive, evidence-based summary of the main paper below, reading it together with its related papers (other parts of the same series, appendices, dataset or follow-up papers).
+        Title: {}
+        arXiv ID: {}
+        PDF URL: {}
+
+        {}
+
+        {}
+
+        5. **Related Papers**: Explain how the main paper relates to each related paper and what each one contributes.
+
+        **Constraint:** Do not hallucinate. Base the summary *strictly* on the provided texts and attribute findings to the paper they come from."#,
+        paper.title, paper.id, paper.pdf_url, sources, SUMMARY_SECTIONS
+    );
+
+    let content = openai::chat(&ctx.client, &ctx.openai_key, &prompt, 2500)?;
+    Ok(pipeline::render_summary(paper, &content))
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let query = args.positional.join(" ");
+    if query.is_empty() {
+        return Err("Usage: related <arxiv-id or title> [--combine]".to_string());
+    }
+
+    let db = Db::load()?;
+    let record = match db.find(&query).as_slice() {
+        [] => return Err(format!("No paper found for: {}", query)),
+        [record] => (*record).clone(),
+        matches => {
+            println!("Multiple papers match \"{}\":", query);
+            for record in matches {
+                println!("  {}  {}", record.id, record.title);
+            }
+            return Ok(());
+        }
+    };
+
+    if record.related.is_empty() {
+        println!("No related papers linked to {}", record.title);
+        return Ok(());
+    }
+
+    println!("{}  {}", record.id, record.title);
+    for relation in &record.related {
+        let title = db.papers.get(&relation.id).map(|r| r.title.as_str()).unwrap_or("(not in archive)");
+        println!("  {:<15} {}  {}", relation.kind, relation.id, title);
+    }
+
+    if !args.flag("combine") {
+        return Ok(());
+    }
+
+    let ctx = RunContext::new(crate::config::Config::load()?, RunOptions::default())?;
+    let paper = record.to_paper();
+    let pdf_text = extract_text_from_pdf(&ctx.papers_dir.join(&record.pdf_file))?;
+    let companions = companion_texts(&ctx, &record.related);
+    if companions.is_empty() {
+        return Err("No related papers with a local PDF to combine".to_string());
+    }
+
+    println!("\nGenerating combined summary with {} related papers", companions.len());
+    let summary = summarize_combined(&ctx, &paper, &pdf_text, &companions)?;
+    let stem = ctx.summary_file(&paper).trim_end_matches("-summary.md").to_string();
+    let path = ctx.summary_dir.join(format!("{}-combined.md", stem));
+    pipeline::write_summary(&path, &summary).map_err(|e| e.to_string())?;
+    println!("Combined summary saved: {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,382 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::feedback;
+use crate::glossary::Glossary;
+use crate::html::{self, escape_html, page};
+use crate::search::SearchIndex;
+use crate::summary::{Frontmatter, strip_frontmatter, tldr};
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const SEARCH_LIMIT: usize = 200;
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+struct ServerState {
+    summary_dir: PathBuf,
+    db: Db,
+    index: SearchIndex,
+}
+
+#[derive(Default)]
+struct Filters {
+    q: String,
+    category: String,
+    tag: String,
+    from: String,
+    to: String,
+    state: String,
+}
+
+impl Filters {
+    fn from_query(params: &HashMap<String, String>) -> Filters {
+        let get = |k: &str| params.get(k).cloned().unwrap_or_default();
+        Filters {
+            q: get("q"),
+            category: get("category"),
+            tag: get("tag"),
+            from: get("from"),
+            to: get("to"),
+            state: get("state"),
+        }
+    }
+
+    fn matches(&self, record: &PaperRecord) -> bool {
+        let date = record.added_at.get(..10).unwrap_or_default();
+        (self.category.is_empty() || record.category == self.category)
+            && (self.tag.is_empty() || record.tags.iter().any(|t| t == &self.tag))
+            && (self.from.is_empty() || date >= self.from.as_str())
+            && (self.to.is_empty() || date <= self.to.as_str())
+            && match self.state.as_str() {
+                "unread" => !record.is_read(),
+                "read" => record.is_read(),
+                "starred" => record.starred,
+                _ => true,
+            }
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let port: u16 = args.parsed("port", 8080)?;
+    let host = args.value("host").unwrap_or("127.0.0.1");
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let db = Db::load()?;
+    let index = SearchIndex::build(&db, &summary_dir);
+    let mut state = ServerState { summary_dir, db, index };
+
+    let server = Server::http((host, port)).map_err(|e| format!("Failed to listen on {}:{}: {}", host, port, e))?;
+    println!("Serving {} summaries on http://{}:{}", state.db.papers.len(), host, port);
+
+    for request in server.incoming_requests() {
+        match Db::load() {
+            Ok(db) => state.db = db,
+            Err(e) => eprintln!("{}", e),
+        }
+        if state.index.is_stale(&state.db, &state.summary_dir) {
+            state.index = SearchIndex::build(&state.db, &state.summary_dir);
+        }
+        let response = handle(&state, &request);
+        let _ = request.respond(response);
+    }
+    Ok(())
+}
+
+fn toggle(request: &Request, id: &str, action: &str) -> HttpResponse {
+    let mut db = match Db::load() {
+        Ok(db) => db,
+        Err(e) => return text_response(500, &e.to_string()),
+    };
+    let Some(record) = db.papers.get(id) else {
+        return text_response(404, "not found");
+    };
+    match action {
+        "read" => {
+            let read = !record.is_read();
+            db.set_read(id, read);
+        }
+        _ => {
+            let starred = !record.starred;
+            db.set_starred(id, starred);
+        }
+    }
+    if let Err(e) = db.save() {
+        return text_response(500, &e);
+    }
+    let referer = header(request, "Referer").and_then(|url| same_origin_path(&url, header(request, "Host").as_deref()));
+    // Going back to the paper page would record another open and mark it read again
+    let back = match referer {
+        Some(path) if action != "read" || !path.starts_with("/papers/") => path,
+        _ if action == "read" => "/".to_string(),
+        _ => paper_url(id),
+    };
+    match Header::from_bytes("Location", back.as_bytes()) {
+        Ok(location) => Response::from_data(Vec::new()).with_status_code(303).with_header(location),
+        Err(_) => text_response(500, "invalid redirect target"),
+    }
+}
+
+fn header(request: &Request, name: &'static str) -> Option<String> {
+    request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.to_string())
+}
+
+// Browsers send Origin on cross-site form posts; clients like curl send neither header
+fn cross_site(source: Option<&str>, host: Option<&str>) -> bool {
+    source.is_some_and(|source| same_origin_path(source, host).is_none())
+}
+
+fn same_origin_path(referer: &str, host: Option<&str>) -> Option<String> {
+    let path = match referer.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            if host != Some(authority) {
+                return None;
+            }
+            if path.is_empty() { "/" } else { path }
+        }
+        None => referer,
+    };
+    (path.starts_with('/') && !path.starts_with("//") && !path.starts_with("/\\")).then(|| path.to_string())
+}
+
+fn handle(state: &ServerState, request: &Request) -> HttpResponse {
+    let (path, params) = parse_url(request.url());
+    let filters = Filters::from_query(&params);
+    let segments: Vec<String> = path.trim_matches('/').split('/').map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    if request.method() == &Method::Post {
+        let source = header(request, "Origin").or_else(|| header(request, "Referer"));
+        if cross_site(source.as_deref(), header(request, "Host").as_deref()) {
+            return text_response(403, "cross-site request rejected");
+        }
+        return match segments.as_slice() {
+            ["papers", id, action @ ("read" | "star")] => toggle(request, id, action),
+            ["papers", id, "open"] if state.db.papers.contains_key(*id) => match feedback::record_open(id) {
+                Ok(()) => Response::from_data(Vec::new()).with_status_code(204),
+                Err(e) => text_response(500, &e),
+            },
+            _ => text_response(404, "not found"),
+        };
+    }
+    if request.method() != &Method::Get {
+        return text_response(405, "method not allowed");
+    }
+
+    match segments.as_slice() {
+        [""] => html_response(200, list_page(state, &filters)),
+        ["papers", id] => match state.db.papers.get(*id) {
+            Some(record) => html_response(200, paper_page(state, record)),
+            None => html_response(404, page("Not found", "<p>Paper not found. <a href=\"/\">Back</a></p>")),
+        },
+        ["glossary"] => html_response(200, Glossary::load().render_html()),
+        ["api", "papers"] => {
+            let records: Vec<&PaperRecord> = filtered_records(state, &filters).into_iter().map(|(r, _)| r).collect();
+            json_response(200, &json!(records))
+        }
+        ["api", "summaries", id] => match state.db.papers.get(*id) {
+            Some(record) => {
+                let content = fs::read_to_string(state.summary_dir.join(&record.summary_file)).unwrap_or_default();
+                let frontmatter = Frontmatter::parse(&content).map(|(f, _)| f.to_map()).unwrap_or_default();
+                json_response(
+                    200,
+                    &json!({
+                        "id": record.id,
+                        "title": record.title,
+                        "category": record.category,
+                        "added_at": record.added_at,
+                        "tags": record.tags,
+                        "read": record.is_read(),
+                        "starred": record.starred,
+                        "frontmatter": frontmatter,
+                        "markdown": strip_frontmatter(&content),
+                    }),
+                )
+            }
+            None => json_response(404, &json!({ "error": "not found" })),
+        },
+        _ => text_response(404, "not found"),
+    }
+}
+
+fn filtered_records<'a>(state: &'a ServerState, filters: &Filters) -> Vec<(&'a PaperRecord, Option<f32>)> {
+    if filters.q.trim().is_empty() {
+        let mut records: Vec<(&PaperRecord, Option<f32>)> =
+            state.db.papers.values().filter(|r| filters.matches(r)).map(|r| (r, None)).collect();
+        records.sort_by(|a, b| b.0.added_at.cmp(&a.0.added_at));
+        return records;
+    }
+
+    state
+        .index
+        .search(&filters.q, SEARCH_LIMIT)
+        .into_iter()
+        .filter_map(|(id, score)| state.db.papers.get(&id).map(|r| (r, Some(score))))
+        .filter(|(r, _)| filters.matches(r))
+        .collect()
+}
+
+fn options(values: &BTreeSet<&str>, selected: &str) -> String {
+    let mut html = String::from("<option value=\"\">all</option>");
+    for value in values {
+        let attr = if *value == selected { " selected" } else { "" };
+        html.push_str(&format!("<option{}>{}</option>", attr, escape_html(value)));
+    }
+    html
+}
+
+fn list_page(state: &ServerState, filters: &Filters) -> String {
+    let categories: BTreeSet<&str> = state.db.papers.values().map(|r| r.category.as_str()).filter(|c| !c.is_empty()).collect();
+    let tags: BTreeSet<&str> = state.db.papers.values().flat_map(|r| r.tags.iter().map(|t| t.as_str())).collect();
+    let records = filtered_records(state, filters);
+
+    let mut body = String::from("<h1>ras summaries</h1>\n<p><a href=\"/glossary\">Glossary</a></p>\n");
+    body.push_str(&format!(
+        "<form method=\"get\" action=\"/\"><input name=\"q\" placeholder=\"search\" value=\"{}\"> \
+         category <select name=\"category\">{}</select> tag <select name=\"tag\">{}</select> \
+         from <input type=\"date\" name=\"from\" value=\"{}\"> to <input type=\"date\" name=\"to\" value=\"{}\"> \
+         show <select name=\"state\">{}</select> <button>Filter</button></form>\n",
+        escape_html(&filters.q),
+        options(&categories, &filters.category),
+        options(&tags, &filters.tag),
+        escape_html(&filters.from),
+        escape_html(&filters.to),
+        options(&BTreeSet::from(["read", "starred", "unread"]), &filters.state),
+    ));
+    let unread = records.iter().filter(|(r, _)| !r.is_read()).count();
+    body.push_str(&format!(
+        "<p class=\"meta\">{} papers, {} unread</p>\n<table>\n<tr><th>Date</th><th>Category</th><th>Paper</th><th></th></tr>\n",
+        records.len(),
+        unread
+    ));
+
+    for (record, _) in records {
+        let content = fs::read_to_string(state.summary_dir.join(&record.summary_file)).unwrap_or_default();
+        body.push_str(&html::paper_row(record, &paper_url(&record.id), &tldr(&content), &state_buttons(record)));
+    }
+    body.push_str("</table>\n");
+    page("ras summaries", &body)
+}
+
+fn state_buttons(record: &PaperRecord) -> String {
+    let url = escape_html(&paper_url(&record.id));
+    format!(
+        "<form method=\"post\" action=\"{}/star\" style=\"display:inline\"><button title=\"star\">{}</button></form> \
+         <form method=\"post\" action=\"{}/read\" style=\"display:inline\"><button>{}</button></form>",
+        url,
+        if record.starred { "&#9733;" } else { "&#9734;" },
+        url,
+        if record.is_read() { "mark unread" } else { "mark read" },
+    )
+}
+
+fn paper_page(state: &ServerState, record: &PaperRecord) -> String {
+    let content = fs::read_to_string(state.summary_dir.join(&record.summary_file)).unwrap_or_default();
+    // Opens are recorded by a beacon once the page is shown, so link prefetches don't count
+    let body = format!(
+        "<p><a href=\"/\">&larr; All summaries</a> &middot; <a href=\"/glossary\">Glossary</a></p>\n<p class=\"meta\">{} &middot; {}</p>\n{}\n<script>navigator.sendBeacon(\"{}/open\");</script>",
+        html::paper_meta(record),
+        state_buttons(record),
+        Glossary::load().link_acronyms(&html::summary_html(&content)),
+        paper_url(&record.id),
+    );
+    page(&record.title, &body)
+}
+
+fn parse_url(url: &str) -> (String, HashMap<String, String>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+    (path.to_string(), params)
+}
+
+pub fn paper_url(id: &str) -> String {
+    format!("/papers/{}", percent_encode(id))
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn html_response(status: u16, html: String) -> HttpResponse {
+    Response::from_data(html.into_bytes())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "text/html; charset=utf-8").unwrap())
+}
+
+fn json_response(status: u16, value: &serde_json::Value) -> HttpResponse {
+    Response::from_data(serde_json::to_vec(value).unwrap_or_default())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+}
+
+fn text_response(status: u16, text: &str) -> HttpResponse {
+    Response::from_data(text.as_bytes().to_vec()).with_status_code(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirects_back_only_within_the_site() {
+        let host = Some("localhost:8080");
+        assert_eq!(same_origin_path("http://localhost:8080/?q=rl", host).as_deref(), Some("/?q=rl"));
+        assert_eq!(same_origin_path("http://localhost:8080", host).as_deref(), Some("/"));
+        assert_eq!(same_origin_path("/papers/2501.01234", host).as_deref(), Some("/papers/2501.01234"));
+        assert_eq!(same_origin_path("https://evil.example/phish", host), None);
+        assert_eq!(same_origin_path("http://localhost:8080.evil.example/", host), None);
+        assert_eq!(same_origin_path("http://localhost:8080/", None), None);
+        assert_eq!(same_origin_path("//evil.example/", host), None);
+        assert_eq!(same_origin_path("/\\evil.example/", host), None);
+        assert_eq!(same_origin_path("javascript:alert(1)", host), None);
+    }
+
+    #[test]
+    fn rejects_posts_from_other_sites() {
+        let host = Some("localhost:8080");
+        assert!(!cross_site(Some("http://localhost:8080"), host));
+        assert!(!cross_site(Some("http://localhost:8080/?q=rl"), host));
+        assert!(!cross_site(None, host));
+        assert!(cross_site(Some("https://evil.example"), host));
+        assert!(cross_site(Some("null"), host));
+        assert!(cross_site(Some("http://localhost:8080"), None));
+    }
+}
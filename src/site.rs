@@ -0,0 +1,46 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::html::{self, page};
+use crate::pipeline::sanitize_filename;
+use crate::summary::tldr;
+use std::fs;
+use std::path::PathBuf;
+
+fn page_file(record: &PaperRecord) -> String {
+    format!("papers/{}.html", sanitize_filename(&record.id))
+}
+
+pub fn export(args: &Args) -> Result<(), String> {
+    let output = match args.value("output") {
+        Some(output) => PathBuf::from(output),
+        None => crate::ras_dir()?.join("html"),
+    };
+    let db = Db::load()?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut records: Vec<&PaperRecord> = db.papers.values().filter(|r| r.status == "summarized").collect();
+    if records.is_empty() {
+        return Err("No summarized papers to export".to_string());
+    }
+    records.sort_by(|a, b| b.added_at.cmp(&a.added_at));
+    fs::create_dir_all(output.join("papers")).map_err(|e| e.to_string())?;
+
+    let mut index = format!(
+        "<h1>ras summaries</h1>\n<p class=\"meta\">{} papers</p>\n<table>\n<tr><th>Date</th><th>Category</th><th>Paper</th><th></th></tr>\n",
+        records.len()
+    );
+    for record in &records {
+        let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+        let body = format!(
+            "<p><a href=\"../index.html\">&larr; All summaries</a></p>\n<p class=\"meta\">{}</p>\n{}",
+            html::paper_meta(record),
+            html::summary_html(&content)
+        );
+        let file = page_file(record);
+        fs::write(output.join(&file), page(&record.title, &body)).map_err(|e| format!("Failed to write {}: {}", file, e))?;
+        index.push_str(&html::paper_row(record, &file, &tldr(&content), ""));
+    }
+    index.push_str("</table>\n");
+    fs::write(output.join("index.html"), page("ras summaries", &index)).map_err(|e| e.to_string())?;
+    println!("Rendered {} summaries to {}", records.len(), output.join("index.html").display());
+    Ok(())
+}
@@ -0,0 +1,368 @@
+use crate::arxiv::{self, Metadata, Paper};
+use crate::biorxiv;
+use crate::cli::Args;
+use crate::config::{OpenReviewSourceConfig, PreprintSourceConfig, SourcesConfig};
+use crate::db::Db;
+use crate::error::RasError;
+use crate::openreview;
+use crate::pipeline::{self, RunOptions};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::IsTerminal;
+
+pub struct FetchOptions {
+    pub limit: usize,
+    pub since: Option<DateTime<Utc>>,
+    pub primary_only: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        FetchOptions { limit: 100, since: None, primary_only: false }
+    }
+}
+
+pub trait PaperSource {
+    fn name(&self) -> &str;
+    fn fetch(&self, client: &Client, opts: &FetchOptions) -> Result<Vec<Paper>, String>;
+}
+
+pub struct ArxivListing {
+    pub categories: Vec<String>,
+    pub primary_only: bool,
+    pub max_per_category: BTreeMap<String, usize>,
+}
+
+impl ArxivListing {
+    fn primary_categories(client: &Client, papers: &mut [Paper]) {
+        let ids: Vec<String> = papers.iter().map(|p| p.id.clone()).collect();
+        let metadata = arxiv::fetch_metadata(client, &ids);
+        for paper in papers.iter_mut() {
+            if let Some(meta) = metadata.get(&paper.id).filter(|m| !m.primary_category.is_empty()) {
+                paper.category = meta.primary_category.clone();
+            }
+        }
+    }
+}
+
+impl PaperSource for ArxivListing {
+    fn name(&self) -> &str {
+        "arxiv"
+    }
+
+    fn fetch(&self, client: &Client, opts: &FetchOptions) -> Result<Vec<Paper>, String> {
+        let mut papers: Vec<Paper> = Vec::new();
+        for category in &self.categories {
+            let fetched = match opts.since {
+                Some(since) => arxiv::search_since(client, &format!("cat:{}", category), since, opts.limit)?
+                    .iter()
+                    .map(|m| m.to_paper())
+                    .collect(),
+                None => arxiv::fetch_arxiv_papers(client, category, opts.limit)?,
+            };
+            for paper in fetched {
+                if !papers.iter().any(|p| p.id == paper.id) {
+                    papers.push(paper);
+                }
+            }
+        }
+
+        if opts.primary_only || self.primary_only {
+            if opts.since.is_none() {
+                Self::primary_categories(client, &mut papers);
+            }
+            let before = papers.len();
+            papers.retain(|p| self.categories.contains(&p.category));
+            if papers.len() < before {
+                println!("Skipped {} papers cross-listed from other categories", before - papers.len());
+            }
+        }
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        papers.retain(|p| {
+            let count = counts.entry(p.category.clone()).or_default();
+            *count += 1;
+            self.max_per_category.get(&p.category).is_none_or(|max| *count <= *max)
+        });
+        Ok(papers)
+    }
+}
+
+pub struct ArxivQuery {
+    pub query: String,
+}
+
+impl PaperSource for ArxivQuery {
+    fn name(&self) -> &str {
+        "arxiv-api"
+    }
+
+    fn fetch(&self, client: &Client, opts: &FetchOptions) -> Result<Vec<Paper>, String> {
+        let results = match opts.since {
+            Some(since) => arxiv::search_since(client, &self.query, since, opts.limit)?,
+            None => arxiv::search(client, &self.query, opts.limit)?,
+        };
+        Ok(results.iter().map(|m| m.to_paper()).collect())
+    }
+}
+
+pub struct IdFile {
+    pub path: String,
+}
+
+fn parse_ids<'a>(values: impl Iterator<Item = &'a str>, origin: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for value in values.map(|v| v.split('#').next().unwrap_or("").trim()).filter(|v| !v.is_empty()) {
+        match arxiv::normalize_id(value) {
+            Some(id) if !ids.contains(&id) => ids.push(id),
+            Some(_) => {}
+            None => println!("  Ignoring invalid arXiv ID in {}: {}", origin, value),
+        }
+    }
+    ids
+}
+
+pub fn read_ids(path: &str) -> Result<Vec<String>, String> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{}", std::env::var("HOME").unwrap_or_default(), rest),
+        None => path.to_string(),
+    };
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(parse_ids(content.lines(), &path))
+}
+
+pub fn papers_for_ids(client: &Client, ids: &[String]) -> Vec<Paper> {
+    let metadata = arxiv::fetch_metadata(client, ids);
+    ids.iter()
+        .map(|id| match metadata.get(id) {
+            Some(meta) => meta.to_paper(),
+            None => Metadata { id: id.clone(), title: format!("Paper-{}", id), ..Default::default() }.to_paper(),
+        })
+        .collect()
+}
+
+impl PaperSource for IdFile {
+    fn name(&self) -> &str {
+        "id-file"
+    }
+
+    fn fetch(&self, client: &Client, _opts: &FetchOptions) -> Result<Vec<Paper>, String> {
+        Ok(papers_for_ids(client, &read_ids(&self.path)?))
+    }
+}
+
+pub struct Preprints {
+    pub server: &'static str,
+    pub days: i64,
+    pub categories: Vec<String>,
+}
+
+impl Preprints {
+    fn new(server: &'static str, config: &PreprintSourceConfig) -> Preprints {
+        Preprints { server, days: config.days, categories: config.categories.clone() }
+    }
+}
+
+impl PaperSource for Preprints {
+    fn name(&self) -> &str {
+        self.server
+    }
+
+    fn fetch(&self, client: &Client, opts: &FetchOptions) -> Result<Vec<Paper>, String> {
+        let days = opts.since.map_or(self.days, |since| (Utc::now() - since).num_days() + 1);
+        biorxiv::fetch(client, self.server, days, &self.categories, opts.limit)
+    }
+}
+
+pub struct OpenReview {
+    pub config: OpenReviewSourceConfig,
+}
+
+impl PaperSource for OpenReview {
+    fn name(&self) -> &str {
+        "openreview"
+    }
+
+    fn fetch(&self, client: &Client, opts: &FetchOptions) -> Result<Vec<Paper>, String> {
+        openreview::fetch(client, &self.config, opts.limit)
+    }
+}
+
+pub struct HuggingFaceDaily;
+
+impl PaperSource for HuggingFaceDaily {
+    fn name(&self) -> &str {
+        "huggingface"
+    }
+
+    fn fetch(&self, client: &Client, opts: &FetchOptions) -> Result<Vec<Paper>, String> {
+        let entries: Vec<Value> = client
+            .get("https://huggingface.co/api/daily_papers")
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| e.to_string())?;
+
+        let ids: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| entry["paper"]["id"].as_str().map(|s| s.to_string()))
+            .take(opts.limit)
+            .collect();
+        let metadata = arxiv::fetch_metadata(client, &ids);
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| {
+                let paper = &entry["paper"];
+                let id = paper["id"].as_str()?;
+                if !ids.iter().any(|i| i == id) {
+                    return None;
+                }
+                if let Some(meta) = metadata.get(id) {
+                    return Some(meta.to_paper());
+                }
+                let title = paper["title"].as_str().or(entry["title"].as_str())?;
+                Some(Paper {
+                    pdf_url: format!("https://arxiv.org/pdf/{}.pdf", id),
+                    title: title.split_whitespace().collect::<Vec<_>>().join(" "),
+                    id: id.to_string(),
+                    category: "cs.AI".to_string(),
+                    scholar: None,
+                    code: None,
+                    sources: Vec::new(),
+                })
+            })
+            .collect())
+    }
+}
+
+type Factory = fn(&SourcesConfig) -> Box<dyn PaperSource>;
+
+pub struct Registry {
+    factories: Vec<(&'static str, Factory)>,
+}
+
+impl Registry {
+    pub fn builtin() -> Registry {
+        let mut registry = Registry { factories: Vec::new() };
+        registry.register("arxiv", |config| Box::new(ArxivListing { categories: config.arxiv.categories.clone(), primary_only: config.arxiv.primary_only, max_per_category: config.arxiv.max_per_category.clone() }));
+        registry.register("arxiv-api", |config| Box::new(ArxivQuery { query: config.arxiv_api.query.clone() }));
+        registry.register("id-file", |config| Box::new(IdFile { path: config.id_file.path.clone() }));
+        registry.register("biorxiv", |config| Box::new(Preprints::new("biorxiv", &config.biorxiv)));
+        registry.register("medrxiv", |config| Box::new(Preprints::new("medrxiv", &config.medrxiv)));
+        registry.register("openreview", |config| Box::new(OpenReview { config: config.openreview.clone() }));
+        registry.register("huggingface", |_| Box::new(HuggingFaceDaily));
+        registry
+    }
+
+    pub fn register(&mut self, name: &'static str, factory: Factory) {
+        self.factories.retain(|(n, _)| *n != name);
+        self.factories.push((name, factory));
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.factories.iter().map(|(n, _)| *n).collect()
+    }
+
+    pub fn build(&self, config: &SourcesConfig) -> Vec<Box<dyn PaperSource>> {
+        let mut sources = Vec::new();
+        for name in &config.enabled {
+            match self.factories.iter().find(|(n, _)| n == name) {
+                Some((_, factory)) => sources.push(factory(config)),
+                None => println!("Unknown paper source \"{}\" (available: {})", name, self.names().join(", ")),
+            }
+        }
+        sources
+    }
+}
+
+pub fn abs_url(id: &str) -> String {
+    biorxiv::abs_url(id)
+        .or_else(|| openreview::abs_url(id))
+        .unwrap_or_else(|| format!("https://arxiv.org/abs/{}", id))
+}
+
+pub fn configured(config: &SourcesConfig) -> Vec<Box<dyn PaperSource>> {
+    Registry::builtin().build(config)
+}
+
+pub fn fetch_all(client: &Client, sources: &[Box<dyn PaperSource>], opts: &FetchOptions) -> (Vec<Paper>, usize) {
+    let mut merged: Vec<Paper> = Vec::new();
+    let mut failed = 0;
+    for source in sources {
+        let papers = match source.fetch(client, opts) {
+            Ok(papers) => papers,
+            Err(e) => {
+                println!("Failed to fetch papers from {}: {}", source.name(), e);
+                failed += 1;
+                continue;
+            }
+        };
+        println!("Found {} papers on {}", papers.len(), source.name());
+        for mut paper in papers {
+            match merged.iter_mut().find(|p| p.id == paper.id) {
+                Some(existing) => existing.sources.push(source.name().to_string()),
+                None => {
+                    paper.sources = vec![source.name().to_string()];
+                    merged.push(paper);
+                }
+            }
+        }
+    }
+    (merged, failed)
+}
+
+pub fn run_fetch(args: &Args) -> Result<(), RasError> {
+    let mut ids = parse_ids(args.value("ids").unwrap_or("").split(','), "--ids");
+    if let Some(path) = args.value("ids-file") {
+        for id in read_ids(path)? {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    let query = args.value("query");
+    if ids.is_empty() && query.is_none() {
+        return Err(RasError::Other("Usage: ras fetch --ids 2501.01234,2501.04321 | --ids-file ids.txt | --query \"ti:transformer AND cat:cs.LG\" [--max-results 100]".to_string()));
+    }
+
+    let client = crate::build_client()?;
+    let requested = RunOptions::from_args(args)?;
+    let mut papers: Vec<Paper> = Vec::new();
+    if let Some(query) = query {
+        let max_results: usize = args.parsed("max-results", 100)?;
+        println!("Searching arXiv for {} (up to {} results)...", query, max_results);
+        let results = match requested.since {
+            Some(since) => arxiv::search_since(&client, query, since, max_results)?,
+            None => arxiv::search(&client, query, max_results)?,
+        };
+        println!("Found {} papers matching the query", results.len());
+        papers.extend(results.iter().map(|m| m.to_paper()).filter(|p| !ids.contains(&p.id)));
+    }
+
+    let db = Db::load()?;
+    let archived = |id: &str| db.papers.get(id).is_some_and(|r| r.status == "summarized");
+    let (skipped, ids): (Vec<String>, Vec<String>) = ids.into_iter().partition(|id| archived(id));
+    let before = papers.len();
+    papers.retain(|p| !archived(&p.id));
+    let skipped_count = skipped.len() + before - papers.len();
+    if skipped_count > 0 {
+        println!("Skipping {} papers already in the archive", skipped_count);
+    }
+
+    if !ids.is_empty() {
+        println!("Fetching {} papers by ID...", ids.len());
+        papers.extend(papers_for_ids(&client, &ids));
+    }
+    if papers.is_empty() {
+        println!("Nothing to fetch");
+        return Ok(());
+    }
+    for paper in papers.iter_mut() {
+        paper.sources = vec![if query.is_some() && !ids.contains(&paper.id) { "query" } else { "ids" }.to_string()];
+    }
+    crate::openai::set_echo(papers.len() == 1 && std::io::stdout().is_terminal());
+    pipeline::execute(requested.with_papers(papers))
+}
@@ -0,0 +1,137 @@
+use crate::cli::Args;
+use crate::config::{Config, StorageConfig};
+use crate::db::Db;
+use crate::pipeline::parse_window;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub fn free_mb(dir: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(dir).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let available: u64 = stdout.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available / 1024)
+}
+
+pub fn check_free_space(config: &StorageConfig, dir: &Path) -> Result<(), String> {
+    if config.min_free_mb == 0 {
+        return Ok(());
+    }
+    let Some(free) = free_mb(dir) else {
+        return Ok(());
+    };
+    if free >= config.min_free_mb {
+        return Ok(());
+    }
+    let message = format!("Only {} MB free in {} (min_free_mb = {})", free, dir.display(), config.min_free_mb);
+    if config.abort_below_min_free {
+        return Err(format!("{}, aborting the run. Free some space with `ras clean`.", message));
+    }
+    println!("Warning: {}. Free some space with `ras clean`.", message);
+    Ok(())
+}
+
+fn modified(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+fn expired(config: &StorageConfig, path: &Path) -> Result<bool, String> {
+    if config.delete_pdf_after_summary {
+        return Ok(true);
+    }
+    if config.keep_pdfs.is_empty() {
+        return Ok(false);
+    }
+    let window = parse_window(&config.keep_pdfs)
+        .map_err(|_| format!("Invalid keep_pdfs: {} (expected e.g. 30d or 8w)", config.keep_pdfs))?;
+    Ok(modified(path).is_some_and(|at| at < Utc::now() - window))
+}
+
+pub fn remove_pdf(db: &mut Db, papers_dir: &Path, id: &str) -> Result<u64, String> {
+    let Some(record) = db.papers.get_mut(id) else {
+        return Ok(0);
+    };
+    let path = papers_dir.join(&record.pdf_file);
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    fs::remove_file(&path).map_err(|e| e.to_string())?;
+    record.pdf_file.clear();
+    record.pdf_sha256.clear();
+    Ok(size)
+}
+
+pub fn clean(config: &StorageConfig, db: &mut Db, papers_dir: &Path, dry_run: bool) -> Result<(usize, u64), String> {
+    let candidates: Vec<(String, PathBuf)> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && r.stage != "abstract" && !r.pdf_file.is_empty())
+        .map(|r| (r.id.clone(), papers_dir.join(&r.pdf_file)))
+        .filter(|(_, path)| path.exists())
+        .collect();
+
+    let (mut removed, mut freed) = (0, 0);
+    for (id, path) in candidates {
+        if !expired(config, &path)? {
+            continue;
+        }
+        if dry_run {
+            println!("  Would delete {}", path.display());
+            freed += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            removed += 1;
+            continue;
+        }
+        match remove_pdf(db, papers_dir, &id) {
+            Ok(size) => {
+                println!("  Deleted {}", path.display());
+                freed += size;
+                removed += 1;
+            }
+            Err(e) => println!("  Failed to delete {}: {}", path.display(), e),
+        }
+    }
+
+    for entry in fs::read_dir(papers_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let stale = path.extension().is_some_and(|e| e == "part") && modified(&path).is_some_and(|at| at < Utc::now() - chrono::Duration::days(1));
+        if !stale {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if dry_run {
+            println!("  Would delete partial download {}", path.display());
+        } else if fs::remove_file(&path).is_ok() {
+            println!("  Deleted partial download {}", path.display());
+        } else {
+            continue;
+        }
+        freed += size;
+        removed += 1;
+    }
+    Ok((removed, freed))
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let config = Config::load()?;
+    let papers_dir = crate::ras_dir()?.join("papers");
+    let dry_run = args.flag("dry-run");
+    let mut storage = config.storage;
+    if let Some(older_than) = args.value("older-than") {
+        storage.keep_pdfs = older_than.to_string();
+    }
+    if storage.keep_pdfs.is_empty() && !storage.delete_pdf_after_summary {
+        println!("No retention policy set. Set keep_pdfs or delete_pdf_after_summary under [storage], or pass --older-than 30d.");
+    }
+
+    let mut db = Db::load()?;
+    let (removed, freed) = clean(&storage, &mut db, &papers_dir, dry_run)?;
+    if dry_run {
+        println!("Would delete {} files and free {:.1} MB", removed, freed as f64 / 1_048_576.0);
+        return Ok(());
+    }
+    db.save()?;
+    println!("Deleted {} files and freed {:.1} MB", removed, freed as f64 / 1_048_576.0);
+    if let Some(free) = free_mb(&papers_dir) {
+        println!("{} MB free", free);
+    }
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use crate::arxiv::Paper;
+use crate::db::PaperRecord;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static SECTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?:\d+\.\s+\*\*([^*]+?):?\*\*:?|#{2,4}\s+(.+))\s*(.*)$").unwrap());
+
+const MAX_TLDR_CHARS: usize = 300;
+const NOTES_START: &str = "<!-- ras:notes -->";
+const NOTES_END: &str = "<!-- /ras:notes -->";
+const NOTES_HEADING: &str = "## My Notes";
+const REQUIRED_KEYS: &[&str] = &["id", "title", "category", "pdf", "status", "created"];
+const STATUSES: &[&str] = &["summarized", "error"];
+
+#[derive(Default, Clone)]
+pub struct Frontmatter {
+    fields: Vec<(String, String)>,
+}
+
+impl Frontmatter {
+    pub fn for_paper(paper: &Paper, status: &str) -> Frontmatter {
+        let mut frontmatter = Frontmatter::default();
+        frontmatter.set("id", &paper.id);
+        frontmatter.set("title", &paper.title);
+        frontmatter.set("category", &paper.category);
+        frontmatter.set("pdf", &paper.pdf_url);
+        frontmatter.set("status", status);
+        frontmatter.set("created", &Utc::now().to_rfc3339());
+        if !paper.sources.is_empty() {
+            frontmatter.set("sources", &paper.sources.join(", "));
+        }
+        if let Some(scholar) = &paper.scholar {
+            frontmatter.set("citations", &scholar.citations.to_string());
+            frontmatter.set("influential_citations", &scholar.influential_citations.to_string());
+            frontmatter.set("fields_of_study", &scholar.fields_of_study.join(", "));
+        }
+        if let Some(code) = &paper.code {
+            frontmatter.set("code", &code.repository);
+            frontmatter.set("framework", &code.framework);
+        }
+        frontmatter
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.fields.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.fields.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        self.fields.iter().cloned().collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("---\n");
+        for (key, value) in &self.fields {
+            out.push_str(&format!("{}: {}\n", key, serde_json::to_string(value).unwrap_or_default()));
+        }
+        out.push_str("---\n\n");
+        out
+    }
+
+    pub fn parse(content: &str) -> Option<(Frontmatter, &str)> {
+        let rest = content.strip_prefix("---\n")?;
+        let end = rest.find("\n---\n")?;
+        let mut frontmatter = Frontmatter::default();
+        for line in rest[..end].lines() {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            let value = if value.starts_with('"') {
+                serde_json::from_str(value).ok()?
+            } else {
+                value.to_string()
+            };
+            frontmatter.set(key.trim(), &value);
+        }
+        Some((frontmatter, rest[end + 5..].trim_start_matches('\n')))
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors: Vec<String> = REQUIRED_KEYS
+            .iter()
+            .filter(|k| self.get(k).is_none_or(str::is_empty))
+            .map(|k| format!("missing field '{}'", k))
+            .collect();
+        if let Some(status) = self.get("status")
+            && !STATUSES.contains(&status) {
+                errors.push(format!("unknown status '{}'", status));
+            }
+        if let Some(created) = self.get("created")
+            && DateTime::parse_from_rfc3339(created).is_err() {
+                errors.push(format!("invalid created timestamp '{}'", created));
+            }
+        errors
+    }
+}
+
+pub fn set_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    match Frontmatter::parse(content) {
+        Some((mut frontmatter, body)) => {
+            frontmatter.set(key, value);
+            format!("{}{}", frontmatter.render(), body)
+        }
+        None => content.to_string(),
+    }
+}
+
+pub fn strip_frontmatter(content: &str) -> &str {
+    Frontmatter::parse(content).map(|(_, body)| body).unwrap_or(content)
+}
+
+fn notes_range(content: &str) -> Option<(usize, usize)> {
+    let start = content.find(NOTES_START)?;
+    let end = content[start..].find(NOTES_END)? + start + NOTES_END.len();
+    Some((start, end))
+}
+
+pub fn notes(content: &str) -> Option<String> {
+    let (start, end) = notes_range(content)?;
+    let block = &content[start + NOTES_START.len()..end - NOTES_END.len()];
+    let block = block.trim().strip_prefix(NOTES_HEADING).unwrap_or(block);
+    Some(block.trim().to_string())
+}
+
+pub fn with_notes(content: &str, notes: &str) -> String {
+    let content = match notes_range(content) {
+        Some((start, end)) => format!("{}\n{}", content[..start].trim_end(), content[end..].trim_start()),
+        None if notes.trim().is_empty() => return content.to_string(),
+        None => content.to_string(),
+    };
+    if notes.trim().is_empty() {
+        return content;
+    }
+    format!("{}\n\n{}\n{}\n\n{}\n{}\n", content.trim_end(), NOTES_START, NOTES_HEADING, notes.trim(), NOTES_END)
+}
+
+pub fn sections(content: &str) -> Vec<(String, String)> {
+    let content = with_notes(content, "");
+    let body = strip_frontmatter(&content);
+    let body = body.split_once("\n---\n").map(|(_, b)| b).unwrap_or(body);
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in body.lines() {
+        match SECTION_REGEX.captures(line.trim()) {
+            Some(captures) => {
+                let heading = captures.get(1).or(captures.get(2)).map_or("", |m| m.as_str());
+                sections.push((heading.trim().to_string(), captures[3].to_string()));
+            }
+            None => {
+                if let Some((_, text)) = sections.last_mut() {
+                    text.push('\n');
+                    text.push_str(line);
+                }
+            }
+        }
+    }
+    sections.into_iter().map(|(heading, text)| (heading, text.trim().to_string())).filter(|(_, text)| !text.is_empty()).collect()
+}
+
+pub fn tldr(content: &str) -> String {
+    let content = strip_frontmatter(content);
+    let body = content.split_once("\n---\n").map(|(_, b)| b).unwrap_or(content);
+    let mut lines = body.lines().map(str::trim).skip_while(|l| !l.to_lowercase().contains("overview"));
+    let first = lines.next().unwrap_or_default();
+
+    let mut paragraph = first
+        .split_once("**:")
+        .or_else(|| first.split_once(":**"))
+        .map(|(_, rest)| rest.trim().to_string())
+        .unwrap_or_default();
+    if paragraph.is_empty() {
+        paragraph = lines
+            .by_ref()
+            .skip_while(|l| l.is_empty())
+            .take_while(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    if paragraph.is_empty() {
+        paragraph = body
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    let cleaned = paragraph.replace("**", "").trim_start_matches(['-', '*', ' ']).to_string();
+    let sentence = match cleaned.find(". ") {
+        Some(end) => cleaned[..=end].to_string(),
+        None => cleaned,
+    };
+    if sentence.chars().count() > MAX_TLDR_CHARS {
+        let truncated: String = sentence.chars().take(MAX_TLDR_CHARS).collect();
+        format!("{}...", truncated.trim_end())
+    } else {
+        sentence
+    }
+}
+
+pub struct SummaryInfo {
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    pub pdf_url: String,
+    pub tldr: String,
+    pub path: PathBuf,
+}
+
+impl SummaryInfo {
+    pub fn from_record(record: &PaperRecord, summary_dir: &Path) -> SummaryInfo {
+        let path = summary_dir.join(&record.summary_file);
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        SummaryInfo {
+            id: record.id.clone(),
+            title: record.title.clone(),
+            category: record.category.clone(),
+            pdf_url: record.pdf_url.clone(),
+            tldr: tldr(&content),
+            path,
+        }
+    }
+
+    pub fn abs_url(&self) -> String {
+        crate::sources::abs_url(&self.id)
+    }
+}
+
+pub fn group_by_category(summaries: &[SummaryInfo]) -> BTreeMap<&str, Vec<&SummaryInfo>> {
+    let mut groups: BTreeMap<&str, Vec<&SummaryInfo>> = BTreeMap::new();
+    for summary in summaries {
+        groups.entry(summary.category.as_str()).or_default().push(summary);
+    }
+    groups
+}
@@ -0,0 +1,153 @@
+use crate::arxiv::Paper;
+use crate::cancel::CancellationToken;
+use crate::chunking::{self, Section};
+use crate::config::SurveyConfig;
+use crate::openai;
+use crate::pipeline::{self, MAX_PROMPT_CHARS, RunContext, SUMMARY_SECTIONS, sanitize_filename};
+use crate::references;
+use crate::summary::Frontmatter;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static SURVEY_TITLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(survey|review|overview|tutorial|taxonomy|state of the art|state-of-the-art|systematic literature|landscape)\b").unwrap()
+});
+static NUMBERED_REF_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*\[\d{1,4}\]").unwrap());
+static YEAR_REF_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)[.,]\s*\(?(19|20)\d{2}[a-z]?\)?\.\s*$").unwrap());
+static SKIPPED_TOPICS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(front matter|abstract|introduction|conclusions?|references|bibliography|acknowledg|appendix)").unwrap()
+});
+
+const TOPIC_CHARS: usize = MAX_PROMPT_CHARS / 2;
+
+pub type TopicFiles = Vec<(String, String)>;
+
+pub fn reference_count(text: &str) -> usize {
+    let Some(references) = references::section(text) else {
+        return 0;
+    };
+    let numbered = NUMBERED_REF_REGEX.find_iter(references).count();
+    if numbered > 0 { numbered } else { YEAR_REF_REGEX.find_iter(references).count() }
+}
+
+pub fn is_survey(config: &SurveyConfig, paper: &Paper, text: &str) -> bool {
+    if !config.enabled {
+        return false;
+    }
+    let long = text.chars().count() >= config.min_chars;
+    let references = reference_count(text);
+    if SURVEY_TITLE_REGEX.is_match(&paper.title) {
+        long || references >= config.min_references
+    } else {
+        long && references >= config.min_references * 2
+    }
+}
+
+struct Topic {
+    heading: String,
+    text: String,
+}
+
+fn major_topics(sections: Vec<Section>) -> Vec<Topic> {
+    let mut topics: Vec<Topic> = Vec::new();
+    for section in sections {
+        let top_level = !section.anchor.contains('.');
+        if top_level {
+            topics.push(Topic { heading: section.heading, text: section.text });
+        } else if let Some(last) = topics.last_mut() {
+            last.text.push_str(&section.text);
+        }
+    }
+    topics.retain(|t| !SKIPPED_TOPICS_REGEX.is_match(&t.heading));
+    topics
+}
+
+fn topics_dir(overview_file: &str) -> String {
+    format!("{}-topics", overview_file.strip_suffix("-summary.md").unwrap_or(overview_file))
+}
+
+fn summarize_topic(ctx: &RunContext, paper: &Paper, topic: &Topic) -> Result<String, String> {
+    let text: String = topic.text.chars().take(TOPIC_CHARS).collect();
+    let prompt = format!(
+        r#"The following text is the section "{}" of the survey paper "{}".
+        Write a focused summary of this topic with these sections:
+
+        1. **Scope**: What this part of the survey covers.
+        2. **Taxonomy and Approaches**: The main families of methods and how the authors organize them.
+        3. **Representative Works**: The key papers or systems discussed and what distinguishes them.
+        4. **Comparisons and Findings**: Any quantitative or qualitative comparisons the authors report.
+        5. **Open Challenges**: Gaps and future directions the authors identify for this topic.
+
+        **Constraint:** Do not hallucinate. Base the summary *strictly* on the provided text.
+
+        Text:
+        {}"#,
+        topic.heading, paper.title, text
+    );
+    openai::chat(&ctx.client, &ctx.openai_key, &prompt, 1500)
+}
+
+pub fn summarize(ctx: &RunContext, paper: &Paper, text: &str, cancel: &CancellationToken) -> Result<Option<(String, TopicFiles)>, String> {
+    let mut topics = major_topics(chunking::split_sections(text));
+    if topics.len() < 2 {
+        return Ok(None);
+    }
+    if topics.len() > ctx.config.survey.max_topics {
+        println!("  Survey has {} topics, summarizing the first {}: {}", topics.len(), ctx.config.survey.max_topics, paper.title);
+        topics.truncate(ctx.config.survey.max_topics);
+    }
+
+    let overview_file = ctx.summary_file(paper);
+    let dir = topics_dir(&overview_file);
+    let mut files: TopicFiles = Vec::new();
+    let mut notes = String::new();
+    let mut index = String::from("\n\n## Topics\n\n");
+
+    for (i, topic) in topics.iter().enumerate() {
+        cancel.check()?;
+        println!("  Summarizing survey topic {}/{}: {}", i + 1, topics.len(), topic.heading);
+        let content = summarize_topic(ctx, paper, topic)?;
+        let file = format!("{}/{:02}-{}.md", dir, i + 1, sanitize_filename(&topic.heading));
+
+        let mut frontmatter = Frontmatter::for_paper(paper, "summarized");
+        frontmatter.set("topic", &topic.heading);
+        frontmatter.set("overview", &overview_file);
+        files.push((
+            file.clone(),
+            format!(
+                "{}# {}: {}\n\n**Survey**: [{}]({})\n**arXiv ID**: {}\n\n---\n\n{}",
+                frontmatter.render(),
+                paper.title,
+                topic.heading,
+                paper.title,
+                pipeline::relative_link(&file, &overview_file),
+                paper.id,
+                content
+            ),
+        ));
+        notes.push_str(&format!("### {}\n{}\n\n", topic.heading, content));
+        index.push_str(&format!("{}. [{}]({})\n", i + 1, topic.heading, pipeline::relative_link(&overview_file, &file)));
+    }
+
+    cancel.check()?;
+    let intro: String = text.chars().take(TOPIC_CHARS / 2).collect();
+    let prompt = format!(
+        r#"Please provide an overview summary of the following survey paper, based on its introduction and on summaries of each of its major topics.
+        Title: {}
+        arXiv ID: {}
+        PDF URL: {}
+
+        Introduction:
+        {}
+
+        Topic summaries:
+        {}
+
+        {}
+
+        **Constraint:** Do not hallucinate. Base the summary *strictly* on the provided text and topic summaries."#,
+        paper.title, paper.id, paper.pdf_url, intro, notes, SUMMARY_SECTIONS
+    );
+    let overview = openai::chat(&ctx.client, &ctx.openai_key, &prompt, 2000)? + &index;
+    Ok(Some((pipeline::render_summary(paper, &overview), files)))
+}
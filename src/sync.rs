@@ -0,0 +1,75 @@
+use crate::cli::Args;
+use crate::config::{Config, SyncConfig};
+use crate::error::RasError;
+use crate::s3;
+use crate::webdav;
+use reqwest::blocking::Client;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct LocalFile {
+    pub key: String,
+    pub path: PathBuf,
+}
+
+pub fn encode(text: &str, keep_slash: bool) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b'/' if keep_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn walk(dir: &Path, key: &str, files: &mut Vec<LocalFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let key = format!("{}/{}", key, name);
+        if path.is_dir() {
+            walk(&path, &key, files);
+        } else {
+            files.push(LocalFile { key, path });
+        }
+    }
+}
+
+pub fn local_files(pdfs: bool) -> Result<Vec<LocalFile>, RasError> {
+    let ras_dir = crate::ras_dir()?;
+    let mut files = Vec::new();
+    walk(&ras_dir.join("summary"), "summary", &mut files);
+    if pdfs {
+        walk(&ras_dir.join("papers"), "papers", &mut files);
+    }
+    files.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(files)
+}
+
+pub fn sync(client: &Client, config: &SyncConfig, target: &str, pdfs: bool) -> Result<(), String> {
+    let files = local_files(pdfs)?;
+    let (uploaded, unchanged) = if target.starts_with("s3://") {
+        s3::sync(client, config, target, &files)?
+    } else if target.starts_with("https://") || target.starts_with("http://") {
+        webdav::sync(client, config, target, &files)?
+    } else {
+        return Err(format!("Unsupported sync target: {} (expected s3://bucket/prefix or a WebDAV https:// URL)", target));
+    };
+    println!("Synced {}: {} uploaded, {} unchanged", target, uploaded, unchanged);
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let config = Config::load()?.sync;
+    let target = args.positional.first().map(|s| s.as_str()).unwrap_or(&config.target);
+    if target.is_empty() {
+        return Err("Usage: sync <s3://bucket/prefix|https://webdav/url> [--pdfs], or set target in the [sync] config section".to_string());
+    }
+    sync(&crate::build_client()?, &config, target, config.pdfs || args.flag("pdfs"))
+}
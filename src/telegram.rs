@@ -0,0 +1,113 @@
+use crate::config::TelegramConfig;
+use crate::notifications::WatchAlert;
+use crate::summary::{SummaryInfo, strip_frontmatter};
+use chrono::Local;
+use reqwest::blocking::Client;
+use serde_json::json;
+use std::fs;
+
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+pub fn deliver(config: &TelegramConfig, client: &Client, summaries: &[SummaryInfo]) {
+    if config.bot_token.is_empty() || config.chat_id.is_empty() || summaries.is_empty() {
+        return;
+    }
+
+    let messages: Vec<String> = if config.mode == "digest" {
+        vec![digest_message(summaries)]
+    } else {
+        summaries.iter().map(|s| summary_message(s, config.include_full)).collect()
+    };
+
+    let mut sent = 0;
+    for message in messages {
+        for part in split_message(&message, MAX_MESSAGE_CHARS) {
+            match send_message(config, client, &part) {
+                Ok(()) => sent += 1,
+                Err(e) => println!("Failed to send Telegram message: {}", e),
+            }
+        }
+    }
+    println!("Telegram: sent {} messages", sent);
+}
+
+pub fn alert(config: &TelegramConfig, client: &Client, alerts: &[WatchAlert]) {
+    if config.bot_token.is_empty() || config.chat_id.is_empty() {
+        return;
+    }
+    for (summary, authors) in alerts {
+        let message = format!("New paper by {}: {}\n\n{}\n\n{}", authors.join(", "), summary.title, summary.tldr, summary.abs_url());
+        if let Err(e) = send_message(config, client, &message) {
+            println!("Failed to send Telegram message: {}", e);
+        }
+    }
+}
+
+fn summary_message(summary: &SummaryInfo, include_full: bool) -> String {
+    let mut message = format!("{}\n\n{}\n\n{}", summary.title, summary.tldr, summary.abs_url());
+    if include_full
+        && let Ok(content) = fs::read_to_string(&summary.path) {
+            message.push_str("\n\n");
+            message.push_str(strip_frontmatter(&content).trim());
+        }
+    message
+}
+
+fn digest_message(summaries: &[SummaryInfo]) -> String {
+    let mut message = format!("ras digest {}: {} new summaries", Local::now().format("%Y-%m-%d"), summaries.len());
+    for summary in summaries {
+        message.push_str(&format!("\n\n• {}\n{}\n{}", summary.title, summary.tldr, summary.abs_url()));
+    }
+    message
+}
+
+fn send_message(config: &TelegramConfig, client: &Client, text: &str) -> Result<(), String> {
+    let url = format!("https://api.telegram.org/bot{}/sendMessage", config.bot_token);
+    let payload = json!({
+        "chat_id": config.chat_id,
+        "text": text,
+        "disable_web_page_preview": true,
+    });
+    let response = client.post(url).json(&payload).send().map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(format!("Telegram API error {}: {}", status, body));
+    }
+    Ok(())
+}
+
+pub fn split_message(text: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        let separator = if current.is_empty() { 0 } else { 2 };
+        if current.chars().count() + separator + paragraph.chars().count() <= max_chars {
+            if separator > 0 {
+                current.push_str("\n\n");
+            }
+            current.push_str(paragraph);
+            continue;
+        }
+
+        if !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+        }
+
+        let chars: Vec<char> = paragraph.chars().collect();
+        let mut pieces = chars.chunks(max_chars).map(|c| c.iter().collect::<String>()).peekable();
+        while let Some(piece) = pieces.next() {
+            if pieces.peek().is_some() {
+                parts.push(piece);
+            } else {
+                current = piece;
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
@@ -0,0 +1,97 @@
+use crate::config::TelemetryConfig;
+use crate::runs::Run;
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone)]
+pub struct Span {
+    pub name: String,
+    pub paper: String,
+    pub start: SystemTime,
+    pub end: SystemTime,
+}
+
+fn nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default().to_string()
+}
+
+fn span_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+fn attributes(pairs: &[(&str, &str)]) -> Vec<Value> {
+    pairs.iter().filter(|(_, value)| !value.is_empty()).map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } })).collect()
+}
+
+fn span(trace_id: &str, parent: &str, name: &str, (start, end): (SystemTime, SystemTime), attrs: Vec<Value>, failed: bool) -> (String, Value) {
+    let id = span_id();
+    let span = json!({
+        "traceId": trace_id,
+        "spanId": id,
+        "parentSpanId": parent,
+        "name": name,
+        "kind": 1,
+        "startTimeUnixNano": nanos(start),
+        "endTimeUnixNano": nanos(end),
+        "attributes": attrs,
+        "status": { "code": if failed { 2 } else { 1 } },
+    });
+    (id, span)
+}
+
+fn trace(config: &TelemetryConfig, run: &Run, started: SystemTime, finished: SystemTime, spans: &[Span]) -> Value {
+    let trace_id = format!("{:032x}", rand::random::<u128>());
+    let failed = run.papers.iter().any(|p| p.outcome == "failed");
+    let (root, run_span) = span(&trace_id, "", "run", (started, finished), attributes(&[("ras.run_id", &run.id)]), failed);
+    let mut out = vec![run_span];
+
+    let mut papers: BTreeMap<&str, Vec<&Span>> = BTreeMap::new();
+    for span in spans {
+        papers.entry(&span.paper).or_default().push(span);
+    }
+    for (paper, stages) in papers {
+        let parent = if paper.is_empty() {
+            root.clone()
+        } else {
+            let record = run.papers.iter().find(|p| p.id == paper);
+            let outcome = record.map(|p| p.outcome.as_str()).unwrap_or_default();
+            let start = stages.iter().map(|s| s.start).min().unwrap_or(started);
+            let end = stages.iter().map(|s| s.end).max().unwrap_or(finished);
+            let attrs = attributes(&[
+                ("paper.id", paper),
+                ("paper.title", record.map(|p| p.title.as_str()).unwrap_or_default()),
+                ("ras.outcome", outcome),
+                ("ras.reason", record.map(|p| p.reason.as_str()).unwrap_or_default()),
+            ]);
+            let (id, paper_span) = span(&trace_id, &root, "paper", (start, end), attrs, outcome == "failed");
+            out.push(paper_span);
+            id
+        };
+        for stage in stages {
+            out.push(span(&trace_id, &parent, &stage.name, (stage.start, stage.end), attributes(&[("paper.id", paper)]), false).1);
+        }
+    }
+
+    json!({
+        "resourceSpans": [{
+            "resource": { "attributes": attributes(&[("service.name", &config.service_name)]) },
+            "scopeSpans": [{ "scope": { "name": "ras" }, "spans": out }],
+        }]
+    })
+}
+
+pub fn export(client: &Client, config: &TelemetryConfig, run: &Run, started: SystemTime, finished: SystemTime, spans: &[Span]) -> Result<usize, String> {
+    let body = trace(config, run, started, finished, spans);
+    let count = body["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().map_or(0, Vec::len);
+    let mut request = client.post(format!("{}/v1/traces", config.endpoint.trim_end_matches('/'))).json(&body);
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("OTLP endpoint returned {}: {}", response.status(), response.text().unwrap_or_default().trim()));
+    }
+    Ok(count)
+}
@@ -0,0 +1,122 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::history::print_record;
+use crate::openai;
+use crate::summary::strip_frontmatter;
+use chrono::{Local, Months};
+use reqwest::blocking::Client;
+use std::collections::{BTreeMap, HashMap};
+
+const MAX_TAGS: usize = 5;
+const VOCABULARY_SIZE: usize = 60;
+const SUMMARY_CHARS: usize = 6000;
+
+pub fn normalize(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches(['-', '*', '#', '•'])
+        .trim()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub fn vocabulary(db: &Db) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in db.papers.values().flat_map(|r| r.tags.iter()).filter(|t| !t.contains('/')) {
+        *counts.entry(tag.as_str()).or_default() += 1;
+    }
+    let mut tags: Vec<(&str, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    tags.into_iter().take(VOCABULARY_SIZE).map(|(t, _)| t.to_string()).collect()
+}
+
+pub fn tag(client: &Client, api_key: &str, paper: &Paper, summary: &str, vocabulary: &[String]) -> Result<Vec<String>, String> {
+    let summary: String = strip_frontmatter(summary).chars().take(SUMMARY_CHARS).collect();
+    let existing = if vocabulary.is_empty() { "(none yet)".to_string() } else { vocabulary.join(", ") };
+    let prompt = format!(
+        r#"Assign 3 to 5 topic tags to the academic paper "{}" based on its summary below.
+        Tags are short lowercase research topics such as "diffusion-models", "reinforcement-learning" or "code-generation".
+        Reuse tags from the existing list whenever one fits, and only create a new tag when none does.
+
+        Existing tags: {}
+
+        Summary:
+        {}
+
+        Return only the tags, one per line."#,
+        paper.title, existing, summary
+    );
+    let reply = openai::chat(client, api_key, &prompt, 100)?;
+    let mut tags: Vec<String> = Vec::new();
+    for tag in reply.split([',', '\n']).map(normalize).filter(|t| !t.is_empty()) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_TAGS);
+    Ok(tags)
+}
+
+pub fn run_list(args: &Args) -> Result<(), String> {
+    let db = Db::load()?;
+    let mut records: Vec<&PaperRecord> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized")
+        .filter(|r| args.value("category").is_none_or(|c| r.category == c))
+        .filter(|r| args.value("tag").is_none_or(|t| r.tags.iter().any(|tag| tag == t || *tag == normalize(t))))
+        .filter(|r| !args.flag("unread") || !r.is_read())
+        .filter(|r| !args.flag("starred") || r.starred)
+        .collect();
+    records.sort_by(|a, b| b.added_at.cmp(&a.added_at));
+    for record in &records {
+        print_record(record);
+    }
+    let unread = records.iter().filter(|r| !r.is_read()).count();
+    println!("\n{} papers, {} unread", records.len(), unread);
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let months: u32 = args.parsed("months", 6)?;
+    let top: usize = args.parsed("top", 30)?;
+    let today = Local::now().date_naive();
+    let columns: Vec<String> = (0..months.max(1))
+        .rev()
+        .map(|i| today.checked_sub_months(Months::new(i)).unwrap_or(today).format("%Y-%m").to_string())
+        .collect();
+
+    let db = Db::load()?;
+    let mut counts: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for record in db.papers.values().filter(|r| r.status == "summarized") {
+        let month = record.added_at.get(..7).unwrap_or("");
+        for tag in &record.tags {
+            *counts.entry(tag.as_str()).or_default().entry(month).or_default() += 1;
+        }
+    }
+    if counts.is_empty() {
+        println!("No tagged papers yet. Enable [tagging] in the config to tag new summaries.");
+        return Ok(());
+    }
+
+    let mut rows: Vec<(&str, usize, &BTreeMap<&str, usize>)> =
+        counts.iter().map(|(tag, by_month)| (*tag, by_month.values().sum(), by_month)).collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    print!("{:<32} {:>6}", "tag", "total");
+    for column in &columns {
+        print!(" {:>8}", column);
+    }
+    println!();
+    for (tag, total, by_month) in rows.into_iter().take(top) {
+        print!("{:<32} {:>6}", tag, total);
+        for column in &columns {
+            print!(" {:>8}", by_month.get(column.as_str()).copied().unwrap_or(0));
+        }
+        println!();
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::openai;
+use crate::summary::{Frontmatter, set_frontmatter_field};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::blocking::Client;
+
+static LANGUAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z]{2,3}(?:-[A-Za-z0-9]{2,8})*$").unwrap());
+
+pub fn parse_languages(value: &str) -> Result<Vec<String>, String> {
+    let mut languages: Vec<String> = Vec::new();
+    for language in value.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+        if !LANGUAGE_REGEX.is_match(language) {
+            return Err(format!("Invalid language tag: {} (expected e.g. pt-BR or de)", language));
+        }
+        if !languages.iter().any(|l| l == language) {
+            languages.push(language.to_string());
+        }
+    }
+    Ok(languages)
+}
+
+pub fn filename(summary_filename: &str, language: &str) -> String {
+    match summary_filename.strip_suffix(".md") {
+        Some(stem) => format!("{}.{}.md", stem, language),
+        None => format!("{}.{}", summary_filename, language),
+    }
+}
+
+pub fn translate(client: &Client, api_key: &str, summary: &str, language: &str) -> Result<String, String> {
+    let (frontmatter, body) = match Frontmatter::parse(summary) {
+        Some((frontmatter, body)) => (frontmatter.render(), body),
+        None => (String::new(), summary),
+    };
+    let prompt = format!(
+        r#"Translate the following Markdown summary of an academic paper into the language with the tag "{}".
+        Keep the Markdown structure, headings, tables, numbers, units, URLs, arXiv IDs and citation anchors such as [§4.2] exactly as they are.
+        Keep technical terms, model names and dataset names that are usually left in English.
+        Return only the translated Markdown.
+
+        Summary:
+        {}"#,
+        language, body
+    );
+    let translated = openai::in_pass("translate", || openai::chat(client, api_key, &prompt, 4000))?;
+    Ok(set_frontmatter_field(&format!("{}{}", frontmatter, translated.trim()), "lang", language))
+}
@@ -0,0 +1,238 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::error::RasError;
+use crate::pipeline::parse_window;
+use crate::relevance;
+use crate::summary::strip_frontmatter;
+use chrono::{DateTime, Local, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b[A-Z][A-Za-z]*(?:[A-Z]|\d)[A-Za-z0-9]*(?:[-.][A-Za-z0-9]+)*\b").unwrap());
+static MODEL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(gpt|llama|claude|gemini|gemma|mistral|mixtral|qwen|deepseek|phi|falcon|bert|roberta|t5|vit|clip|whisper|olmo|yi|opt|bloom)|\d+(\.\d+)?[bm]$").unwrap()
+});
+const IGNORED_NAMES: &[&str] = &[
+    "AI", "LLM", "LLMs", "NLP", "SOTA", "API", "GPU", "GPUs", "CPU", "RL", "ML", "CV", "TL", "DR", "URL", "PDF", "ID", "IDs", "QA", "MLP",
+];
+const TOP: usize = 10;
+
+#[derive(Serialize)]
+struct Trend {
+    name: String,
+    current: usize,
+    previous: usize,
+    change: f64,
+}
+
+#[derive(Serialize)]
+struct Report {
+    window: String,
+    from: String,
+    to: String,
+    papers: usize,
+    previous_papers: usize,
+    rising_topics: Vec<Trend>,
+    falling_topics: Vec<Trend>,
+    rising_keywords: Vec<Trend>,
+    new_benchmarks: Vec<String>,
+    top_benchmarks: Vec<Trend>,
+    top_models: Vec<Trend>,
+}
+
+#[derive(Default)]
+struct Mentions {
+    tags: HashSet<String>,
+    keywords: HashSet<String>,
+    benchmarks: HashSet<String>,
+    models: HashSet<String>,
+}
+
+fn trends_dir() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("trends"))
+}
+
+fn mentions(record: &PaperRecord, summary_dir: &Path) -> Mentions {
+    let content = fs::read_to_string(summary_dir.join(&record.summary_file)).unwrap_or_default();
+    let body = strip_frontmatter(&content);
+    let body = body.split_once("\n---\n").map(|(_, b)| b).unwrap_or(body);
+
+    let mut mentions = Mentions {
+        tags: record.tags.iter().filter(|t| !t.contains('/')).cloned().collect(),
+        keywords: relevance::keywords(&record.title),
+        ..Default::default()
+    };
+    if let Some(code) = &record.code {
+        mentions.benchmarks.extend(code.results.iter().map(|r| r.dataset.clone()).filter(|d| !d.is_empty()));
+    }
+    for name in NAME_REGEX.find_iter(body).map(|m| m.as_str()) {
+        if IGNORED_NAMES.contains(&name) || name.chars().count() < 3 || record.id.contains(name) {
+            continue;
+        }
+        if MODEL_REGEX.is_match(name) {
+            mentions.models.insert(name.to_string());
+        } else {
+            mentions.benchmarks.insert(name.to_string());
+        }
+    }
+    mentions
+}
+
+fn count<'a>(all: impl Iterator<Item = &'a HashSet<String>>) -> BTreeMap<String, usize> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for names in all {
+        for name in names {
+            *counts.entry(name.clone()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn compare(current: &BTreeMap<String, usize>, previous: &BTreeMap<String, usize>, papers: usize, previous_papers: usize) -> Vec<Trend> {
+    let share = |count: usize, total: usize| if total == 0 { 0.0 } else { count as f64 / total as f64 };
+    let names: BTreeSet<&String> = current.keys().chain(previous.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let now = current.get(name).copied().unwrap_or(0);
+            let before = previous.get(name).copied().unwrap_or(0);
+            Trend { name: name.clone(), current: now, previous: before, change: share(now, papers) - share(before, previous_papers) }
+        })
+        .collect()
+}
+
+fn rising(mut trends: Vec<Trend>, min_count: usize) -> Vec<Trend> {
+    trends.retain(|t| t.change > 0.0 && t.current >= min_count);
+    trends.sort_by(|a, b| b.change.total_cmp(&a.change).then(b.current.cmp(&a.current)));
+    trends.truncate(TOP);
+    trends
+}
+
+fn falling(mut trends: Vec<Trend>) -> Vec<Trend> {
+    trends.retain(|t| t.change < 0.0 && t.previous >= 2);
+    trends.sort_by(|a, b| a.change.total_cmp(&b.change));
+    trends.truncate(TOP);
+    trends
+}
+
+fn top(counts: &BTreeMap<String, usize>, previous: &BTreeMap<String, usize>) -> Vec<Trend> {
+    let mut trends: Vec<Trend> = counts
+        .iter()
+        .filter(|(_, count)| **count >= 2)
+        .map(|(name, count)| Trend { name: name.clone(), current: *count, previous: previous.get(name).copied().unwrap_or(0), change: 0.0 })
+        .collect();
+    trends.sort_by(|a, b| b.current.cmp(&a.current).then(a.name.cmp(&b.name)));
+    trends.truncate(TOP);
+    trends
+}
+
+fn added_at(record: &PaperRecord) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&record.added_at).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn table(title: &str, trends: &[Trend]) -> String {
+    let mut section = format!("## {}\n\n", title);
+    if trends.is_empty() {
+        section.push_str("Nothing stood out.\n\n");
+        return section;
+    }
+    section.push_str("| Name | Papers | Previous window |\n|---|---|---|\n");
+    for trend in trends {
+        section.push_str(&format!("| {} | {} | {} |\n", trend.name, trend.current, trend.previous));
+    }
+    section.push('\n');
+    section
+}
+
+fn render(report: &Report) -> String {
+    let mut markdown = format!(
+        "# Trends — last {} ({} to {})\n\n{} papers in this window, {} in the previous one.\n\n",
+        report.window, report.from, report.to, report.papers, report.previous_papers
+    );
+    markdown.push_str(&table("Rising Topics", &report.rising_topics));
+    markdown.push_str(&table("Falling Topics", &report.falling_topics));
+    markdown.push_str(&table("Rising Keywords", &report.rising_keywords));
+    markdown.push_str("## New Benchmarks\n\n");
+    if report.new_benchmarks.is_empty() {
+        markdown.push_str("No benchmarks appeared for the first time.\n\n");
+    } else {
+        markdown.push_str(&format!("{}\n\n", report.new_benchmarks.join(", ")));
+    }
+    markdown.push_str(&table("Most Mentioned Benchmarks", &report.top_benchmarks));
+    markdown.push_str(&table("Most Mentioned Models", &report.top_models));
+    markdown
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let window_arg = args.value("last").unwrap_or("30d");
+    let window = parse_window(window_arg)?;
+    let to = Utc::now();
+    let from = to - window;
+    let previous_from = from - window;
+
+    let db = Db::load()?;
+    let summary_dir = crate::ras_dir()?.join("summary");
+    let mut current: Vec<Mentions> = Vec::new();
+    let mut previous: Vec<Mentions> = Vec::new();
+    let mut earlier_benchmarks: HashSet<String> = HashSet::new();
+    for record in db.papers.values().filter(|r| r.status == "summarized") {
+        let Some(at) = added_at(record) else {
+            continue;
+        };
+        if at >= from {
+            current.push(mentions(record, &summary_dir));
+        } else {
+            let mentions = mentions(record, &summary_dir);
+            earlier_benchmarks.extend(mentions.benchmarks.iter().cloned());
+            if at >= previous_from {
+                previous.push(mentions);
+            }
+        }
+    }
+    if current.is_empty() {
+        println!("No summaries from the last {}", window_arg);
+        return Ok(());
+    }
+
+    let (papers, previous_papers) = (current.len(), previous.len());
+    let (current_tags, previous_tags) = (count(current.iter().map(|m| &m.tags)), count(previous.iter().map(|m| &m.tags)));
+    let keywords = compare(&count(current.iter().map(|m| &m.keywords)), &count(previous.iter().map(|m| &m.keywords)), papers, previous_papers);
+    let benchmarks = count(current.iter().map(|m| &m.benchmarks));
+    let models = count(current.iter().map(|m| &m.models));
+    let mut new_benchmarks: Vec<String> =
+        benchmarks.iter().filter(|(name, count)| **count >= 2 && !earlier_benchmarks.contains(*name)).map(|(name, _)| name.clone()).collect();
+    new_benchmarks.truncate(TOP * 2);
+
+    let report = Report {
+        window: window_arg.to_string(),
+        from: from.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+        to: to.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+        papers,
+        previous_papers,
+        rising_topics: rising(compare(&current_tags, &previous_tags, papers, previous_papers), 2),
+        falling_topics: falling(compare(&current_tags, &previous_tags, papers, previous_papers)),
+        rising_keywords: rising(keywords, 3),
+        new_benchmarks,
+        top_benchmarks: top(&benchmarks, &count(previous.iter().map(|m| &m.benchmarks))),
+        top_models: top(&models, &count(previous.iter().map(|m| &m.models))),
+    };
+
+    fs::create_dir_all(trends_dir()?).map_err(|e| e.to_string())?;
+    let stem = format!("trends-{}-{}", report.to, window_arg);
+    let markdown = trends_dir()?.join(format!("{}.md", stem));
+    let json = trends_dir()?.join(format!("{}.json", stem));
+    fs::write(&markdown, render(&report)).map_err(|e| e.to_string())?;
+    fs::write(&json, serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    if args.flag("json") {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+    } else {
+        print!("{}", render(&report));
+    }
+    println!("Trend report saved: {}", markdown.display());
+    Ok(())
+}
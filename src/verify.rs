@@ -0,0 +1,207 @@
+use crate::cli::Args;
+use crate::db::{Db, PaperRecord};
+use crate::embeddings::{self, EmbeddingStore};
+use crate::pdf::{sha256_file, validate_pdf};
+use crate::summary::Frontmatter;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+#[derive(Serialize)]
+struct Issue {
+    check: &'static str,
+    target: String,
+    severity: &'static str,
+    message: String,
+}
+
+#[derive(Serialize, Default)]
+struct Report {
+    ok: bool,
+    pdfs_checked: usize,
+    summaries_checked: usize,
+    records_checked: usize,
+    index_entries_checked: usize,
+    errors: usize,
+    warnings: usize,
+    issues: Vec<Issue>,
+}
+
+fn error(check: &'static str, target: &str, message: String) -> Issue {
+    Issue { check, target: target.to_string(), severity: "error", message }
+}
+
+fn warning(check: &'static str, target: &str, message: String) -> Issue {
+    Issue { check, target: target.to_string(), severity: "warning", message }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let ras_dir = crate::ras_dir()?;
+    let papers_dir = ras_dir.join("papers");
+    let summary_dir = ras_dir.join("summary");
+    let db = Db::load()?;
+    let store = EmbeddingStore::load(&embeddings::store_path()?);
+
+    let pdfs = list_files(&papers_dir, ".pdf");
+    let summaries = list_files(&summary_dir, "-summary.md");
+    let hashes: HashMap<&str, &str> = db
+        .papers
+        .values()
+        .filter(|r| !r.pdf_file.is_empty() && !r.pdf_sha256.is_empty())
+        .map(|r| (r.pdf_file.as_str(), r.pdf_sha256.as_str()))
+        .collect();
+
+    let mut report = Report {
+        pdfs_checked: pdfs.len(),
+        summaries_checked: summaries.len(),
+        records_checked: db.papers.len(),
+        index_entries_checked: store.entries.len(),
+        ..Default::default()
+    };
+
+    thread::scope(|scope| {
+        let pdf_issues = scope.spawn(|| parallel_map(&pdfs, |path| check_pdf(path, &hashes)));
+        let summary_issues = scope.spawn(|| parallel_map(&summaries, |path| check_summary(path, &db)));
+        let db_issues = scope.spawn(|| check_db(&db, &papers_dir, &summary_dir));
+        let index_issues = scope.spawn(|| check_index(&store, &summary_dir));
+
+        for handle in [pdf_issues, summary_issues, db_issues, index_issues] {
+            report.issues.extend(handle.join().unwrap_or_default());
+        }
+    });
+
+    report.errors = report.issues.iter().filter(|i| i.severity == "error").count();
+    report.warnings = report.issues.len() - report.errors;
+    report.ok = report.errors == 0;
+
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    match args.value("output") {
+        Some(path) => fs::write(path, &json).map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        None => println!("{}", json),
+    }
+
+    if report.ok {
+        Ok(())
+    } else {
+        Err(format!("Verification found {} errors", report.errors))
+    }
+}
+
+fn list_files(dir: &Path, suffix: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in fs::read_dir(dir).into_iter().flatten().flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            files.extend(list_files(&path, suffix));
+        } else if path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.ends_with(suffix)) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+fn parallel_map<F>(paths: &[PathBuf], check: F) -> Vec<Issue>
+where
+    F: Fn(&Path) -> Vec<Issue> + Sync,
+{
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let chunk_size = paths.len().div_ceil(workers).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().flat_map(|p| check(p)).collect::<Vec<_>>()))
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect()
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn check_pdf(path: &Path, hashes: &HashMap<&str, &str>) -> Vec<Issue> {
+    let name = file_name(path);
+    let mut issues = Vec::new();
+
+    if let Err(e) = validate_pdf(path) {
+        issues.push(error("pdf", &name, e));
+    }
+
+    match hashes.get(name.as_str()) {
+        Some(expected) => match sha256_file(path) {
+            Ok(actual) if actual == *expected => {}
+            Ok(actual) => issues.push(error("pdf", &name, format!("sha256 mismatch: expected {}, got {}", expected, actual))),
+            Err(e) => issues.push(error("pdf", &name, format!("failed to hash: {}", e))),
+        },
+        None => issues.push(warning("pdf", &name, "no recorded hash".to_string())),
+    }
+
+    issues
+}
+
+fn check_summary(path: &Path, db: &Db) -> Vec<Issue> {
+    let name = file_name(path);
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => return vec![error("summary", &name, format!("unreadable: {}", e))],
+    };
+
+    let Some((frontmatter, _)) = Frontmatter::parse(&content) else {
+        return vec![warning("summary", &name, "no frontmatter (legacy summary)".to_string())];
+    };
+
+    let mut issues: Vec<Issue> = frontmatter
+        .validate()
+        .into_iter()
+        .map(|e| error("summary", &name, format!("frontmatter: {}", e)))
+        .collect();
+
+    if let Some(id) = frontmatter.get("id")
+        && !db.papers.contains_key(id) {
+            issues.push(warning("summary", &name, format!("paper {} not in database", id)));
+        }
+
+    issues
+}
+
+fn check_db(db: &Db, papers_dir: &Path, summary_dir: &Path) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (id, record) in &db.papers {
+        if id != &record.id {
+            issues.push(error("database", id, format!("key does not match record id {}", record.id)));
+        }
+        issues.extend(check_record(record, papers_dir, summary_dir));
+    }
+    issues
+}
+
+fn check_record(record: &PaperRecord, papers_dir: &Path, summary_dir: &Path) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    if record.status == "failed" {
+        return issues;
+    }
+    if record.summary_file.is_empty() || !summary_dir.join(&record.summary_file).exists() {
+        issues.push(error("database", &record.id, format!("summary file missing: {}", record.summary_file)));
+    }
+    if !record.pdf_file.is_empty() && !papers_dir.join(&record.pdf_file).exists() {
+        issues.push(warning("database", &record.id, format!("pdf file missing: {}", record.pdf_file)));
+    }
+    issues
+}
+
+fn check_index(store: &EmbeddingStore, summary_dir: &Path) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = store
+        .entries
+        .keys()
+        .filter(|name| !summary_dir.join(name).exists())
+        .map(|name| warning("index", name, "embedding for deleted summary".to_string()))
+        .collect();
+
+    let stale = store.stale_files(summary_dir);
+    if !stale.is_empty() {
+        issues.push(warning("index", "embeddings.json", format!("{} summaries not embedded or out of date, run `index`", stale.len())));
+    }
+    issues
+}
@@ -0,0 +1,156 @@
+use crate::arxiv::{self, Paper};
+use crate::cli::Args;
+use crate::config::VersionsConfig;
+use crate::db::{Db, PaperRecord};
+use crate::openai;
+use crate::pipeline::{self, RunOptions};
+use crate::summary::strip_frontmatter;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const CHANGELOG_CHARS: usize = 20000;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Update {
+    pub previous: u32,
+    pub current: u32,
+    pub archived_summary: String,
+}
+
+fn recent(record: &PaperRecord, config: &VersionsConfig) -> bool {
+    let cutoff = Utc::now() - Duration::days(config.max_age_days);
+    DateTime::parse_from_rfc3339(&record.added_at).is_ok_and(|t| t.with_timezone(&Utc) >= cutoff)
+}
+
+pub fn check(client: &Client, db: &mut Db, config: &VersionsConfig) -> Vec<(String, u32, u32)> {
+    let ids: Vec<String> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && arxiv::normalize_id(&r.id).is_some() && recent(r, config))
+        .map(|r| r.id.clone())
+        .collect();
+    if ids.is_empty() {
+        return Vec::new();
+    }
+    println!("Checking {} papers for new arXiv versions...", ids.len());
+    let metadata = arxiv::fetch_metadata(client, &ids);
+
+    let mut updates = Vec::new();
+    for (id, meta) in metadata {
+        let Some(record) = db.papers.get_mut(&id) else {
+            continue;
+        };
+        if meta.version == 0 || meta.version <= record.version {
+            continue;
+        }
+        if record.version == 0 {
+            record.version = meta.version;
+        } else {
+            updates.push((id, record.version, meta.version));
+        }
+    }
+    updates.sort();
+    updates
+}
+
+fn archive_pdf(papers_dir: &Path, record: &PaperRecord) {
+    let pdf = papers_dir.join(&record.pdf_file);
+    if !record.pdf_file.is_empty() && pdf.exists() {
+        let stem = record.pdf_file.strip_suffix(".pdf").unwrap_or(&record.pdf_file);
+        let _ = fs::rename(&pdf, papers_dir.join(format!("{}-v{}.pdf", stem, record.version)));
+    }
+}
+
+pub fn archive_summary(summary_dir: &Path, summary_file: &str, update: &Update) -> Result<(), String> {
+    let (current, archived) = (summary_dir.join(summary_file), summary_dir.join(&update.archived_summary));
+    if archived.exists() || !current.exists() {
+        return Ok(());
+    }
+    fs::rename(current, archived).map_err(|e| e.to_string())
+}
+
+pub fn prepare(ctx: &pipeline::RunContext) -> Vec<Paper> {
+    let mut db = ctx.db.lock().unwrap();
+    let updates = check(&ctx.client, &mut db, &ctx.config.versions);
+    let mut papers = Vec::new();
+    let mut pending = ctx.versions.lock().unwrap();
+    for (id, previous, current) in updates {
+        let Some(record) = db.papers.get(&id) else {
+            continue;
+        };
+        println!("New version v{} of {} (was v{}), re-summarizing", current, record.title, previous);
+        archive_pdf(&ctx.papers_dir, record);
+        let stem = record.summary_file.strip_suffix("-summary.md").unwrap_or(&record.summary_file);
+        let archived_summary = format!("{}-v{}.md", stem, record.version);
+        let mut paper = record.to_paper();
+        paper.pdf_url = format!("https://arxiv.org/pdf/{}v{}.pdf", id, current);
+        papers.push(paper);
+        pending.insert(id, Update { previous, current, archived_summary });
+    }
+    if let Err(e) = db.save() {
+        println!("Failed to record paper versions: {}", e);
+    }
+    papers
+}
+
+fn summary_body(content: &str) -> String {
+    let body = strip_frontmatter(content);
+    let body = body.split_once("\n---\n").map(|(_, b)| b).unwrap_or(body);
+    body.trim().chars().take(CHANGELOG_CHARS).collect()
+}
+
+pub fn changelog(client: &Client, api_key: &str, previous: &str, update: &Update, summary: &str) -> Result<String, String> {
+    let prompt = format!(
+        r#"Below are two summaries of the same academic paper: one of version v{} and one of the new version v{}.
+        List what changed between the versions as concise bullet points: new or removed results, changed numbers (give old and new values), new experiments, methods or sections, and claims that were dropped or weakened.
+        Ignore differences in wording that don't change the content. If nothing substantive changed, say so in one sentence.
+
+        Summary of v{}:
+        {}
+
+        Summary of v{}:
+        {}"#,
+        update.previous,
+        update.current,
+        update.previous,
+        summary_body(previous),
+        update.current,
+        summary_body(summary)
+    );
+    let changes = openai::chat(client, api_key, &prompt, 1000)?;
+    let archived = update.archived_summary.rsplit('/').next().unwrap_or(&update.archived_summary);
+    Ok(format!(
+        "\n\n## Changes Since v{}\n\n{}\n\nPrevious summary: [{}]({})\n",
+        update.previous,
+        changes.trim(),
+        archived,
+        archived.replace(' ', "%20")
+    ))
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    if args.flag("update") {
+        let mut options = RunOptions::for_papers(Vec::new());
+        options.update_versions = true;
+        return pipeline::execute(options).map_err(|e| e.to_string());
+    }
+
+    let config = crate::config::Config::load()?;
+    let client = crate::build_client()?;
+    let mut db = Db::load()?;
+    let updates = check(&client, &mut db, &config.versions);
+    db.save()?;
+    if updates.is_empty() {
+        println!("All summarized papers are on their latest arXiv version");
+        return Ok(());
+    }
+    for (id, previous, current) in &updates {
+        let title = db.papers.get(id).map(|r| r.title.as_str()).unwrap_or("");
+        println!("  {}  v{} -> v{}  {}", id, previous, current, title);
+    }
+    println!("\n{} papers have a new version. Run `ras versions --update` to re-summarize them.", updates.len());
+    Ok(())
+}
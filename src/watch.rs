@@ -0,0 +1,104 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::config::Config;
+use crate::extraction;
+use crate::pdf::validate_pdf;
+use crate::pipeline::{self, RunContext, RunOptions};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+const SETTLE: Duration = Duration::from_secs(2);
+
+fn summary_path(pdf: &Path) -> PathBuf {
+    let stem = pdf.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    pdf.with_file_name(format!("{}-summary.md", stem))
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+}
+
+fn summarize(ctx: &RunContext, pdf: &Path) -> Result<PathBuf, String> {
+    validate_pdf(pdf)?;
+    let stem = pdf.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let paper = Paper {
+        id: format!("local:{}", stem),
+        title: stem.replace('_', " "),
+        pdf_url: pdf.display().to_string(),
+        category: String::new(),
+        scholar: None,
+        code: None,
+        sources: vec!["watch".to_string()],
+    };
+    println!("Extracting text: {}", pdf.display());
+    let cancel = ctx.options.paper_token(&paper.id);
+    let extraction = extraction::extract(pdf, &cancel)?;
+    if extraction.text.trim().is_empty() {
+        return Err("PDF text extraction returned empty content".to_string());
+    }
+    println!("Generating summary: {}", paper.title);
+    let summary = pipeline::generate_summary(ctx, &paper, &extraction.text, &cancel)?;
+    let path = summary_path(pdf);
+    pipeline::write_summary(&path, &pipeline::render_summary(&paper, &summary)).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn handle(ctx: &RunContext, pdf: &Path) {
+    match summarize(ctx, pdf) {
+        Ok(path) => println!("Summary saved: {}", path.display()),
+        Err(e) => println!("Failed to summarize {}: {}", pdf.display(), e),
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let dir = PathBuf::from(args.positional.first().ok_or("Usage: ras watch <dir>")?);
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+    let ctx = RunContext::new(Config::load()?, RunOptions::default())?;
+
+    let mut existing: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|path| is_pdf(path) && !summary_path(path).exists())
+        .collect();
+    existing.sort();
+    for pdf in existing {
+        handle(&ctx, &pdf);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let _ = sender.send(event);
+    })
+    .map_err(|e| e.to_string())?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive).map_err(|e| e.to_string())?;
+    println!("Watching {} for new PDFs (Ctrl-C to stop)", dir.display());
+
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    loop {
+        match receiver.recv_timeout(Duration::from_secs(1)) {
+            Ok(Ok(event)) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                for path in event.paths.into_iter().filter(|p| is_pdf(p)) {
+                    pending.insert(path, Instant::now());
+                }
+            }
+            Ok(Ok(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Ok(Err(e)) => println!("Watch error: {}", e),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Err("Directory watcher stopped".to_string()),
+        }
+
+        let settled: Vec<PathBuf> = pending.iter().filter(|(_, at)| at.elapsed() >= SETTLE).map(|(path, _)| path.clone()).collect();
+        for pdf in settled {
+            pending.remove(&pdf);
+            if pdf.exists() {
+                handle(&ctx, &pdf);
+            }
+        }
+    }
+}
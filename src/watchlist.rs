@@ -0,0 +1,37 @@
+use crate::arxiv::{self, Paper};
+use crate::config::WatchlistConfig;
+use reqwest::blocking::Client;
+use std::collections::HashMap;
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace(['.', ','], " ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn find(client: &Client, config: &WatchlistConfig, papers: &[Paper]) -> HashMap<String, Vec<String>> {
+    let mut watched = HashMap::new();
+    if config.authors.is_empty() || papers.is_empty() {
+        return watched;
+    }
+    let names: Vec<(String, &String)> = config.authors.iter().map(|a| (normalize(a), a)).collect();
+    let ids: Vec<String> = papers.iter().filter_map(|p| arxiv::normalize_id(&p.id)).collect();
+    println!("Checking {} papers against the author watchlist...", ids.len());
+    let metadata = arxiv::fetch_metadata(client, &ids);
+
+    for paper in papers {
+        let Some(meta) = metadata.get(&paper.id) else {
+            continue;
+        };
+        let authors: Vec<String> = meta
+            .authors
+            .iter()
+            .filter_map(|author| {
+                let author = normalize(author);
+                names.iter().find(|(name, _)| *name == author).map(|(_, original)| original.to_string())
+            })
+            .collect();
+        if !authors.is_empty() {
+            watched.insert(paper.id.clone(), authors);
+        }
+    }
+    watched
+}
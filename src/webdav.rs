@@ -0,0 +1,297 @@
+use crate::config::SyncConfig;
+use crate::error::RasError;
+use crate::retry;
+use crate::sync::{LocalFile, encode};
+use chrono::Local;
+use md5::{Digest, Md5};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct Synced {
+    md5: String,
+    etag: String,
+}
+
+type State = BTreeMap<String, BTreeMap<String, Synced>>;
+
+struct Server<'a> {
+    client: &'a Client,
+    base: String,
+    username: String,
+    password: String,
+}
+
+pub fn state_path() -> Result<PathBuf, RasError> {
+    Ok(crate::ras_dir()?.join("webdav.json"))
+}
+
+fn load_state() -> State {
+    state_path().ok().and_then(|path| fs::read_to_string(path).ok()).and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default()
+}
+
+fn save_state(state: &State) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(state_path()?, json).map_err(|e| e.to_string())
+}
+
+fn etag(response: &Response) -> String {
+    response.headers().get("etag").and_then(|v| v.to_str().ok()).unwrap_or_default().to_string()
+}
+
+impl Server<'_> {
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base, encode(key, true))
+    }
+
+    fn send(&self, method: &str, key: &str, build: impl Fn(RequestBuilder) -> RequestBuilder) -> Result<Response, String> {
+        let method = Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+        let url = self.url(key);
+        retry::send(&url, || {
+            let request = self.client.request(method.clone(), &url);
+            let request = if self.username.is_empty() { request } else { request.basic_auth(&self.username, Some(&self.password)) };
+            build(request)
+        })
+    }
+
+    fn make_dirs(&self, key: &str, created: &mut HashSet<String>) -> Result<(), String> {
+        let mut dir = String::new();
+        for part in key.split('/').rev().skip(1).collect::<Vec<_>>().into_iter().rev() {
+            dir = if dir.is_empty() { part.to_string() } else { format!("{}/{}", dir, part) };
+            if created.contains(&dir) {
+                continue;
+            }
+            let response = self.send("MKCOL", &format!("{}/", dir), |r| r)?;
+            if !response.status().is_success() && response.status() != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(format!("Failed to create {}: {}", self.url(&dir), response.status()));
+            }
+            created.insert(dir.clone());
+        }
+        Ok(())
+    }
+
+    fn put(&self, key: &str, bytes: &[u8], condition: Option<(&str, &str)>) -> Result<Result<String, StatusCode>, String> {
+        let response = self.send("PUT", key, |r| {
+            let r = match condition {
+                Some((name, value)) => r.header(name, value),
+                None => r,
+            };
+            r.body(bytes.to_vec())
+        })?;
+        let status = response.status();
+        if status == StatusCode::PRECONDITION_FAILED {
+            return Ok(Err(status));
+        }
+        if !status.is_success() {
+            return Err(format!("WebDAV error {}", status));
+        }
+        let tag = etag(&response);
+        if !tag.is_empty() {
+            return Ok(Ok(tag));
+        }
+        Ok(Ok(etag(&self.send("HEAD", key, |r| r)?)))
+    }
+
+    fn get(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, String> {
+        let response = self.send("GET", key, |r| r)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV error {}", response.status()));
+        }
+        let tag = etag(&response);
+        let bytes = response.bytes().map_err(|e| e.to_string())?;
+        Ok(Some((bytes.to_vec(), tag)))
+    }
+}
+
+fn conflict_key(key: &str) -> String {
+    let host = std::env::var("HOSTNAME").ok().filter(|h| !h.is_empty()).unwrap_or_else(|| "local".to_string());
+    let suffix = format!(".conflict-{}-{}", host, Local::now().format("%Y%m%d-%H%M%S"));
+    match key.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains('/') => format!("{}{}.{}", stem, suffix, ext),
+        _ => format!("{}{}", key, suffix),
+    }
+}
+
+pub fn sync(client: &Client, config: &SyncConfig, url: &str, files: &[LocalFile]) -> Result<(usize, usize), String> {
+    let password = crate::credentials::get(&config.password_env).unwrap_or_default();
+    if !config.username.is_empty() && password.is_empty() {
+        return Err(format!("Set {} to sync to WebDAV as {}", config.password_env, config.username));
+    }
+    let server = Server { client, base: url.trim_end_matches('/').to_string(), username: config.username.clone(), password };
+    let mut state = load_state();
+    let (uploaded, unchanged, conflicts) = sync_files(&server, state.entry(url.to_string()).or_default(), files)?;
+    save_state(&state)?;
+    if conflicts > 0 {
+        println!("{} files changed on the server since the last sync and were left untouched", conflicts);
+    }
+    Ok((uploaded, unchanged))
+}
+
+fn sync_files(server: &Server, known: &mut BTreeMap<String, Synced>, files: &[LocalFile]) -> Result<(usize, usize, usize), String> {
+    let mut created = HashSet::new();
+    let (mut uploaded, mut unchanged, mut conflicts) = (0, 0, 0);
+
+    for file in files {
+        let bytes = fs::read(&file.path).map_err(|e| format!("Failed to read {}: {}", file.path.display(), e))?;
+        let md5 = format!("{:x}", Md5::digest(&bytes));
+        let previous = known.get(&file.key).cloned();
+        if previous.as_ref().is_some_and(|p| p.md5 == md5) {
+            unchanged += 1;
+            continue;
+        }
+        let condition = match &previous {
+            Some(p) if !p.etag.is_empty() => Some(("If-Match", p.etag.as_str())),
+            Some(p) => match server.get(&file.key) {
+                Ok(Some((remote, _))) if format!("{:x}", Md5::digest(&remote)) == p.md5 => None,
+                Ok(Some(remote)) => {
+                    if resolve_conflict(server, known, &file.key, &bytes, md5, remote) {
+                        conflicts += 1;
+                    } else {
+                        unchanged += 1;
+                    }
+                    continue;
+                }
+                Ok(None) => Some(("If-None-Match", "*")),
+                Err(e) => {
+                    println!("  Failed to check the remote copy of {}: {}", file.key, e);
+                    continue;
+                }
+            },
+            None => Some(("If-None-Match", "*")),
+        };
+        match server.make_dirs(&file.key, &mut created).and_then(|_| server.put(&file.key, &bytes, condition)) {
+            Err(e) => println!("  Failed to upload {}: {}", file.key, e),
+            Ok(Ok(etag)) => {
+                println!("  Uploaded {}", file.key);
+                known.insert(file.key.clone(), Synced { md5, etag });
+                uploaded += 1;
+            }
+            Ok(Err(_)) => match server.get(&file.key) {
+                Ok(Some(remote)) => {
+                    if resolve_conflict(server, known, &file.key, &bytes, md5, remote) {
+                        conflicts += 1;
+                    } else {
+                        unchanged += 1;
+                    }
+                }
+                Ok(None) => println!("  Failed to check the remote copy of {}: it was deleted", file.key),
+                Err(e) => println!("  Failed to check the remote copy of {}: {}", file.key, e),
+            },
+        }
+    }
+    Ok((uploaded, unchanged, conflicts))
+}
+
+fn resolve_conflict(server: &Server, known: &mut BTreeMap<String, Synced>, key: &str, bytes: &[u8], md5: String, remote: (Vec<u8>, String)) -> bool {
+    let (remote, etag) = remote;
+    if format!("{:x}", Md5::digest(&remote)) == md5 {
+        known.insert(key.to_string(), Synced { md5, etag });
+        return false;
+    }
+    let copy = conflict_key(key);
+    if let Ok(Ok(_)) = server.put(&copy, bytes, Some(("If-None-Match", "*"))) {
+        println!("  Conflict on {}: the remote copy was edited, uploaded yours as {}", key, copy);
+        let etag = known.get(key).map(|p| p.etag.clone()).unwrap_or_default();
+        known.insert(key.to_string(), Synced { md5, etag });
+    } else {
+        println!("  Conflict on {}: the remote copy was edited and yours could not be uploaded as {}, retrying on the next sync", key, copy);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    type Files = Arc<Mutex<BTreeMap<String, Vec<u8>>>>;
+
+    fn fake_server(etags: bool) -> (String, Files) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let files: Files = Arc::default();
+        let stored = Arc::clone(&files);
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let key = request.url().trim_start_matches('/').to_string();
+                let header = |name: &'static str| request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.to_string());
+                let (if_match, if_none_match) = (header("If-Match"), header("If-None-Match"));
+                let mut files = stored.lock().unwrap();
+                let tag = |bytes: &[u8]| format!("\"{:x}\"", Md5::digest(bytes));
+                let current = files.get(&key).map(|bytes| tag(bytes));
+                let (status, body) = match request.method().as_str() {
+                    "MKCOL" => (201, Vec::new()),
+                    "PUT" if if_none_match.is_some() && current.is_some() => (412, Vec::new()),
+                    "PUT" if if_match.is_some() && if_match != current => (412, Vec::new()),
+                    "PUT" if files.contains_key(".locked") => (423, Vec::new()),
+                    "PUT" => {
+                        let mut body = Vec::new();
+                        request.as_reader().read_to_end(&mut body).unwrap();
+                        files.insert(key.clone(), body);
+                        (201, Vec::new())
+                    }
+                    _ => match files.get(&key) {
+                        Some(bytes) => (200, bytes.clone()),
+                        None => (404, Vec::new()),
+                    },
+                };
+                let mut response = tiny_http::Response::from_data(body).with_status_code(status);
+                if let Some(bytes) = files.get(&key).filter(|_| etags && status < 400) {
+                    response.add_header(tiny_http::Header::from_bytes("ETag", tag(bytes)).unwrap());
+                }
+                drop(files);
+                let _ = request.respond(response);
+            }
+        });
+        (base, files)
+    }
+
+    fn sync_once(server: &Server, known: &mut BTreeMap<String, Synced>, path: &std::path::Path, content: &str) -> (usize, usize, usize) {
+        fs::write(path, content).unwrap();
+        sync_files(server, known, &[LocalFile { key: "notes/a-summary.md".to_string(), path: path.to_path_buf() }]).unwrap()
+    }
+
+    fn check_never_overwrites_remote_edits(etags: bool) {
+        let (base, files) = fake_server(etags);
+        let client = Client::new();
+        let server = Server { client: &client, base, username: String::new(), password: String::new() };
+        let path = std::env::temp_dir().join(format!("ras-webdav-{}-{}.md", std::process::id(), etags));
+        let mut known = BTreeMap::new();
+
+        assert_eq!(sync_once(&server, &mut known, &path, "v1"), (1, 0, 0));
+        assert_eq!(sync_once(&server, &mut known, &path, "v2"), (1, 0, 0));
+        assert_eq!(files.lock().unwrap()["notes/a-summary.md"], b"v2");
+
+        files.lock().unwrap().insert("notes/a-summary.md".to_string(), b"edited by hand".to_vec());
+        files.lock().unwrap().insert(".locked".to_string(), Vec::new());
+        assert_eq!(sync_once(&server, &mut known, &path, "v3"), (0, 0, 1));
+        // The conflict copy was refused, so the next sync tries it again
+        files.lock().unwrap().remove(".locked");
+        assert_eq!(sync_once(&server, &mut known, &path, "v3"), (0, 0, 1));
+        assert_eq!(sync_once(&server, &mut known, &path, "v3"), (0, 1, 0));
+        let files = files.lock().unwrap();
+        assert_eq!(files["notes/a-summary.md"], b"edited by hand");
+        let copy = files.keys().find(|k| k.starts_with("notes/a-summary.conflict-")).expect("conflict copy");
+        assert!(copy.ends_with(".md"));
+        assert_eq!(files[copy], b"v3");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn keeps_remote_edits_with_etags() {
+        check_never_overwrites_remote_edits(true);
+    }
+
+    #[test]
+    fn keeps_remote_edits_without_etags() {
+        check_never_overwrites_remote_edits(false);
+    }
+}
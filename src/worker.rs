@@ -0,0 +1,333 @@
+use crate::arxiv::Paper;
+use crate::cli::Args;
+use crate::config::Config;
+use crate::lock::{self, RunLock};
+use crate::pipeline::{self, Outcome, RunContext, RunOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const IDLE_POLL_SECS: u64 = 10;
+const RESULT_RETRIES: u32 = 3;
+const DONE_GRACE_SECS: u64 = 3 * IDLE_POLL_SECS;
+
+#[derive(Serialize, Deserialize)]
+struct JobResponse {
+    job: Option<Paper>,
+    done: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JobResult {
+    worker: String,
+    paper: Paper,
+    outcome: Outcome,
+}
+
+struct Lease {
+    paper: Paper,
+    worker: String,
+    started: Instant,
+}
+
+fn token(args: &Args) -> Result<String, String> {
+    args.value("token")
+        .map(|t| t.to_string())
+        .or_else(|| crate::credentials::get("RAS_WORKER_TOKEN"))
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| "A shared token is required: pass --token or set RAS_WORKER_TOKEN".to_string())
+}
+
+fn authorized(request: &Request, token: &str) -> bool {
+    let expected = format!("Bearer {}", token);
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .is_some_and(|h| constant_time_eq(h.value.as_str().as_bytes(), expected.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<std::io::Cursor<Vec<u8>>> {
+    let json = serde_json::to_vec(body).unwrap_or_default();
+    Response::from_data(json)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+}
+
+fn worker_name(request: &Request) -> String {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("X-Ras-Worker"))
+        .map(|h| h.value.to_string())
+        .unwrap_or_else(|| request.remote_addr().map(|a| a.to_string()).unwrap_or_default())
+}
+
+fn requeue_expired(leases: &mut HashMap<String, Lease>, queue: &mut VecDeque<Paper>, lease: Duration, now: Instant) {
+    let expired: Vec<String> = leases
+        .iter()
+        .filter(|(_, l)| now.saturating_duration_since(l.started) > lease)
+        .map(|(id, _)| id.clone())
+        .collect();
+    for id in expired {
+        if let Some(l) = leases.remove(&id) {
+            println!("  Lease expired for {} on worker {}, requeueing", l.paper.id, l.worker);
+            queue.push_back(l.paper);
+        }
+    }
+}
+
+fn leased_elsewhere(leases: &HashMap<String, Lease>, result: &JobResult, worker: &str) -> bool {
+    leases.get(&result.paper.id).is_some_and(|l| l.worker != result.worker || l.worker != worker)
+}
+
+// A late result for an expired lease still counts while its paper waits in the queue
+fn take_job(leases: &mut HashMap<String, Lease>, queue: &mut VecDeque<Paper>, id: &str) -> Option<Paper> {
+    leases.remove(id).map(|l| l.paper).or_else(|| queue.iter().position(|p| p.id == id).and_then(|i| queue.remove(i)))
+}
+
+pub fn run_coordinator(args: &Args) -> Result<(), String> {
+    let token = token(args)?;
+    let port: u16 = args.parsed("port", 7070)?;
+    let host = args.value("host").unwrap_or("127.0.0.1");
+    let lease = Duration::from_secs(60 * args.parsed("lease-minutes", 30u64)?);
+
+    let _lock = RunLock::acquire(&lock::lock_path()?)?;
+    let ctx = RunContext::new(Config::load()?, RunOptions::default())?;
+    let (fetched, papers) = pipeline::select_papers(&ctx);
+
+    let mut queue: VecDeque<Paper> = papers.iter().cloned().collect();
+    let mut leases: HashMap<String, Lease> = HashMap::new();
+    let total = papers.len();
+    let mut completed = 0;
+
+    let server = Server::http((host, port)).map_err(|e| format!("Failed to listen on {}:{}: {}", host, port, e))?;
+    println!("Coordinator listening on {}:{} with {} jobs", host, port, total);
+    if !matches!(host, "127.0.0.1" | "localhost" | "::1") {
+        println!("Warning: the token and summaries travel unencrypted over plain HTTP, use a TLS reverse proxy outside a trusted network");
+    }
+
+    let mut drained: Option<Instant> = None;
+    loop {
+        if queue.is_empty() && leases.is_empty() {
+            let since = *drained.get_or_insert_with(|| {
+                println!("All jobs done, telling workers to exit");
+                Instant::now()
+            });
+            if since.elapsed() > Duration::from_secs(DONE_GRACE_SECS) {
+                break;
+            }
+        }
+        requeue_expired(&mut leases, &mut queue, lease, Instant::now());
+
+        let mut request = match server.recv_timeout(Duration::from_secs(5)) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        if !authorized(&request, &token) {
+            let _ = request.respond(Response::from_string("unauthorized").with_status_code(401));
+            continue;
+        }
+
+        let worker = worker_name(&request);
+        let response = match (request.method(), request.url()) {
+            (Method::Post, "/jobs/next") => {
+                let job = queue.pop_front();
+                if let Some(paper) = &job {
+                    println!("  Assigned {} to {}", paper.id, worker);
+                    leases.insert(paper.id.clone(), Lease { paper: paper.clone(), worker: worker.clone(), started: Instant::now() });
+                }
+                json_response(200, &JobResponse { done: job.is_none() && leases.is_empty(), job })
+            }
+            (Method::Post, "/jobs/result") => {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                match serde_json::from_str::<JobResult>(&body) {
+                    Ok(result) if leased_elsewhere(&leases, &result, &worker) => {
+                        println!("  Rejected result for {} from {}: leased to another worker", result.paper.id, result.worker);
+                        json_response(409, &serde_json::json!({ "error": "job is leased to another worker" }))
+                    }
+                    Ok(result) => {
+                        if let Some(paper) = take_job(&mut leases, &mut queue, &result.paper.id) {
+                            pipeline::store_outcome(&paper, &result.outcome, &ctx);
+                            if let Outcome::Skipped { reason } = &result.outcome {
+                                println!("  {} skipped on {}: {}", paper.id, result.worker, reason);
+                            }
+                            completed += 1;
+                            println!("Progress: {}/{}", completed, total);
+                        }
+                        json_response(200, &serde_json::json!({ "ok": true }))
+                    }
+                    Err(e) => json_response(400, &serde_json::json!({ "error": e.to_string() })),
+                }
+            }
+            _ => json_response(404, &serde_json::json!({ "error": "not found" })),
+        };
+        let _ = request.respond(response);
+    }
+
+    pipeline::finish_run(&ctx, fetched, &papers);
+    Ok(())
+}
+
+pub fn run_worker(args: &Args) -> Result<(), String> {
+    let coordinator = args
+        .value("connect")
+        .ok_or("Usage: worker --connect http://host:7070 [--token ...] [--threads 2]")?
+        .trim_end_matches('/')
+        .to_string();
+    let token = token(args)?;
+    let threads: usize = args.parsed("threads", 2)?;
+    let name = args
+        .value("name")
+        .map(|n| n.to_string())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| format!("worker-{}", std::process::id()));
+
+    let ctx = Arc::new(RunContext::new(Config::load()?, RunOptions::default())?);
+    let reported = Arc::new(AtomicBool::new(false));
+    println!("Worker {} connecting to {} with {} threads", name, coordinator, threads);
+
+    let handles: Vec<_> = (0..threads)
+        .map(|i| {
+            let ctx = Arc::clone(&ctx);
+            let coordinator = coordinator.clone();
+            let token = token.clone();
+            let reported = Arc::clone(&reported);
+            let name = format!("{}#{}", name, i);
+            thread::spawn(move || worker_loop(&ctx, &coordinator, &token, &name, &reported))
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().map_err(|_| "Worker thread panicked".to_string())??;
+    }
+
+    println!("\nCoordinator has no more jobs, worker exiting");
+    Ok(())
+}
+
+fn worker_loop(ctx: &RunContext, coordinator: &str, token: &str, name: &str, reported: &AtomicBool) -> Result<(), String> {
+    loop {
+        let response: JobResponse = match ctx
+            .client
+            .post(format!("{}/jobs/next", coordinator))
+            .bearer_auth(token)
+            .header("X-Ras-Worker", name)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+        {
+            Ok(response) => response,
+            // the coordinator shuts down once the grace period after the last result is over
+            Err(e) if e.is_connect() && reported.load(Ordering::SeqCst) => return Ok(()),
+            Err(e) => return Err(format!("Failed to fetch job: {}", e)),
+        };
+
+        let paper = match response.job {
+            Some(paper) => paper,
+            None if response.done => return Ok(()),
+            None => {
+                thread::sleep(Duration::from_secs(IDLE_POLL_SECS));
+                continue;
+            }
+        };
+
+        let outcome = pipeline::produce_outcome(&paper, ctx);
+        let result = JobResult { worker: name.to_string(), paper, outcome };
+
+        let mut last_error = String::new();
+        for attempt in 0..RESULT_RETRIES {
+            if attempt > 0 {
+                thread::sleep(Duration::from_secs(2 * attempt as u64));
+            }
+            match ctx
+                .client
+                .post(format!("{}/jobs/result", coordinator))
+                .bearer_auth(token)
+                .header("X-Ras-Worker", name)
+                .json(&result)
+                .send()
+                .and_then(|r| r.error_for_status())
+            {
+                Ok(_) => {
+                    last_error.clear();
+                    reported.store(true, Ordering::SeqCst);
+                    break;
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+        if !last_error.is_empty() {
+            println!("  Failed to report result for {}: {}", result.paper.id, last_error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(id: &str) -> Paper {
+        Paper {
+            id: id.to_string(),
+            title: format!("Paper {}", id),
+            pdf_url: String::new(),
+            category: "cs.AI".to_string(),
+            scholar: None,
+            code: None,
+            sources: Vec::new(),
+        }
+    }
+
+    fn lease(id: &str, worker: &str, started: Instant) -> (String, Lease) {
+        (id.to_string(), Lease { paper: paper(id), worker: worker.to_string(), started })
+    }
+
+    #[test]
+    fn requeues_only_expired_leases() {
+        let start = Instant::now();
+        let mut leases: HashMap<String, Lease> =
+            [lease("1", "a#0", start), lease("2", "b#0", start + Duration::from_secs(44 * 60))].into_iter().collect();
+        let mut queue: VecDeque<Paper> = [paper("3")].into_iter().collect();
+        requeue_expired(&mut leases, &mut queue, Duration::from_secs(30 * 60), start + Duration::from_secs(45 * 60));
+        assert_eq!(queue.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["3", "1"]);
+        assert_eq!(leases.keys().collect::<Vec<_>>(), ["2"]);
+    }
+
+    #[test]
+    fn accepts_results_only_from_the_lease_holder() {
+        let mut leases: HashMap<String, Lease> = [lease("1", "a#0", Instant::now())].into_iter().collect();
+        let mut queue: VecDeque<Paper> = [paper("2"), paper("3")].into_iter().collect();
+        let result = |id: &str, worker: &str| JobResult { worker: worker.to_string(), paper: paper(id), outcome: Outcome::Skipped { reason: String::new() } };
+        assert!(!leased_elsewhere(&leases, &result("1", "a#0"), "a#0"));
+        assert!(leased_elsewhere(&leases, &result("1", "b#0"), "b#0"));
+        assert!(leased_elsewhere(&leases, &result("1", "a#0"), "b#0"));
+        assert!(!leased_elsewhere(&leases, &result("2", "b#0"), "b#0"));
+
+        // "2" was requeued after its lease expired, so the late result is taken off the queue
+        assert_eq!(take_job(&mut leases, &mut queue, "2").map(|p| p.id).as_deref(), Some("2"));
+        assert_eq!(queue.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["3"]);
+        assert!(take_job(&mut leases, &mut queue, "2").is_none());
+        assert_eq!(take_job(&mut leases, &mut queue, "1").map(|p| p.id).as_deref(), Some("1"));
+        assert!(leases.is_empty());
+    }
+
+    #[test]
+    fn compares_tokens_exactly() {
+        assert!(constant_time_eq(b"Bearer secret", b"Bearer secret"));
+        assert!(!constant_time_eq(b"Bearer secret", b"Bearer secreT"));
+        assert!(!constant_time_eq(b"Bearer secret", b"Bearer secret2"));
+        assert!(!constant_time_eq(b"", b"x"));
+    }
+}
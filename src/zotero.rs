@@ -0,0 +1,227 @@
+use crate::arxiv::{self, Metadata};
+use crate::biorxiv;
+use crate::config::{Config, ZoteroConfig};
+use crate::db::{Db, PaperRecord};
+use crate::html::render_markdown;
+use crate::sources;
+use crate::summary::strip_frontmatter;
+use md5::{Digest, Md5};
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+const API_URL: &str = "https://api.zotero.org";
+
+struct Library<'a> {
+    client: &'a Client,
+    base: String,
+    api_key: String,
+}
+
+impl Library<'_> {
+    fn request(&self, builder: RequestBuilder) -> Result<Value, String> {
+        let response = builder
+            .header("Zotero-API-Key", &self.api_key)
+            .header("Zotero-API-Version", "3")
+            .send()
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("Zotero API error {}: {}", status, body));
+        }
+        if body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&body).map_err(|e| e.to_string())
+    }
+
+    fn get(&self, path: &str) -> Result<Value, String> {
+        self.request(self.client.get(format!("{}{}", self.base, path)))
+    }
+
+    fn create(&self, path: &str, objects: Value) -> Result<String, String> {
+        let result = self.request(self.client.post(format!("{}{}", self.base, path)).json(&json!([objects])))?;
+        result["successful"]["0"]["key"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| format!("Zotero rejected the item: {}", result["failed"]))
+    }
+
+    fn collection_key(&self, name: &str) -> Result<Option<String>, String> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let collections = self.get("/collections?limit=100")?;
+        let existing = collections.as_array().into_iter().flatten().find(|c| {
+            c["key"].as_str() == Some(name) || c["data"]["name"].as_str() == Some(name)
+        });
+        if let Some(collection) = existing {
+            return Ok(collection["key"].as_str().map(|s| s.to_string()));
+        }
+        println!("Creating Zotero collection: {}", name);
+        self.create("/collections", json!({ "name": name })).map(Some)
+    }
+
+    fn upload_pdf(&self, parent: &str, path: &Path) -> Result<(), String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let attachment = self.create(
+            "/items",
+            json!({
+                "itemType": "attachment",
+                "parentItem": parent,
+                "linkMode": "imported_file",
+                "title": "Full Text PDF",
+                "contentType": "application/pdf",
+                "filename": filename,
+            }),
+        )?;
+
+        let md5 = format!("{:x}", Md5::digest(&bytes));
+        let mtime = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let auth = self.request(
+            self.client
+                .post(format!("{}/items/{}/file", self.base, attachment))
+                .header("If-None-Match", "*")
+                .form(&[
+                    ("md5", md5),
+                    ("filename", filename),
+                    ("filesize", bytes.len().to_string()),
+                    ("mtime", mtime.to_string()),
+                ]),
+        )?;
+        if auth["exists"].as_i64() == Some(1) {
+            return Ok(());
+        }
+
+        let field = |name: &str| auth[name].as_str().unwrap_or_default().to_string();
+        let mut body = field("prefix").into_bytes();
+        body.extend_from_slice(&bytes);
+        body.extend_from_slice(field("suffix").as_bytes());
+        let response = self
+            .client
+            .post(field("url"))
+            .header("Content-Type", field("contentType"))
+            .body(body)
+            .send()
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("PDF upload failed: {}", response.status()));
+        }
+
+        self.request(
+            self.client
+                .post(format!("{}/items/{}/file", self.base, attachment))
+                .header("If-None-Match", "*")
+                .form(&[("upload", field("uploadKey"))]),
+        )?;
+        Ok(())
+    }
+}
+
+fn item(meta: &Metadata, record: &PaperRecord, collection: Option<&str>) -> Value {
+    let creators: Vec<Value> = meta
+        .authors
+        .iter()
+        .map(|name| match name.rsplit_once(' ') {
+            Some((first, last)) => json!({ "creatorType": "author", "firstName": first, "lastName": last }),
+            None => json!({ "creatorType": "author", "name": name }),
+        })
+        .collect();
+    json!({
+        "itemType": "preprint",
+        "title": if meta.title.is_empty() { &record.title } else { &meta.title },
+        "creators": creators,
+        "abstractNote": meta.summary,
+        "repository": biorxiv::split_id(&record.id).map_or("arXiv", |(server, _)| server),
+        "archiveID": if biorxiv::split_id(&record.id).is_some() { String::new() } else { format!("arXiv:{}", record.id) },
+        "date": meta.published.get(..10).unwrap_or(""),
+        "DOI": meta.doi.clone().or_else(|| biorxiv::doi(&record.id)).unwrap_or_default(),
+        "url": sources::abs_url(&record.id),
+        "tags": record.tags.iter().map(|t| json!({ "tag": t })).collect::<Vec<_>>(),
+        "collections": collection.into_iter().collect::<Vec<_>>(),
+    })
+}
+
+fn push_paper(
+    library: &Library,
+    config: &ZoteroConfig,
+    record: &PaperRecord,
+    meta: &Metadata,
+    collection: Option<&str>,
+) -> Result<String, String> {
+    let ras_dir = crate::ras_dir()?;
+    let key = library.create("/items", item(meta, record, collection))?;
+
+    let summary = fs::read_to_string(ras_dir.join("summary").join(&record.summary_file)).unwrap_or_default();
+    if !summary.is_empty() {
+        library.create(
+            "/items",
+            json!({ "itemType": "note", "parentItem": key, "note": render_markdown(strip_frontmatter(&summary)) }),
+        )?;
+    }
+
+    let pdf_path = ras_dir.join("papers").join(&record.pdf_file);
+    if config.attach_pdf
+        && !record.pdf_file.is_empty()
+        && pdf_path.exists()
+        && let Err(e) = library.upload_pdf(&key, &pdf_path)
+    {
+        println!("  Failed to attach PDF for {}: {}", record.id, e);
+    }
+    Ok(key)
+}
+
+pub fn export(client: &Client) -> Result<(), String> {
+    let config = Config::load()?.zotero;
+    let api_key = config.api_key();
+    if api_key.is_empty() || config.library_id.is_empty() {
+        return Err("Set [zotero] library_id and api_key (or ZOTERO_API_KEY) in config.toml".to_string());
+    }
+    let library = Library {
+        client,
+        base: format!("{}/{}s/{}", API_URL, config.library_type, config.library_id),
+        api_key,
+    };
+    let collection = library.collection_key(&config.collection)?;
+
+    let mut db = Db::load()?;
+    let pending: Vec<PaperRecord> = db
+        .papers
+        .values()
+        .filter(|r| r.status == "summarized" && r.zotero_key.is_empty())
+        .cloned()
+        .collect();
+    if pending.is_empty() {
+        println!("All summarized papers are already in Zotero");
+        return Ok(());
+    }
+
+    let ids: Vec<String> = pending.iter().map(|r| r.id.clone()).collect();
+    let metadata = arxiv::fetch_metadata(client, &ids);
+    let mut pushed = 0;
+    for record in &pending {
+        let meta = metadata.get(&record.id).cloned().unwrap_or_default();
+        match push_paper(&library, &config, record, &meta, collection.as_deref()) {
+            Ok(key) => {
+                println!("  Added to Zotero: {}", record.title);
+                if let Some(r) = db.papers.get_mut(&record.id) {
+                    r.zotero_key = key;
+                }
+                db.save()?;
+                pushed += 1;
+            }
+            Err(e) => println!("  Failed to add {} to Zotero: {}", record.title, e),
+        }
+    }
+
+    println!("Pushed {} of {} papers to Zotero", pushed, pending.len());
+    Ok(())
+}